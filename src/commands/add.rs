@@ -1,11 +1,15 @@
 use crate::{
     config::{BinLikeTargetKind, CargoCompeteConfigAdd},
+    hooks::Hook,
     oj_api,
     project::{MetadataExt as _, PackageExt as _},
-    shell::ColorChoice,
+    shell::{ColorChoice, Shell},
+    web::{aoj, cache::ProblemCache, library_checker},
 };
 use anyhow::{bail, ensure, Context as _};
+use camino::Utf8Path;
 use cargo_metadata as cm;
+use heck::KebabCase as _;
 use liquid::object;
 use maplit::{btreeset, hashmap};
 use once_cell::sync::Lazy;
@@ -25,6 +29,14 @@ pub struct OptCompeteAdd {
     #[structopt(long)]
     pub open: bool,
 
+    /// Use only the cached problems without sending requests
+    #[structopt(long)]
+    pub offline: bool,
+
+    /// Name of the template in `template.src` to use for all of the problems
+    #[structopt(long, value_name("NAME"))]
+    pub template: Option<String>,
+
     /// Package (see `cargo help pkgid`)
     #[structopt(short, long, value_name("SPEC"))]
     pub package: Option<String>,
@@ -33,6 +45,10 @@ pub struct OptCompeteAdd {
     #[structopt(long, value_name("PATH"))]
     pub manifest_path: Option<PathBuf>,
 
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
     /// Coloring
     #[structopt(
         long,
@@ -50,8 +66,11 @@ pub(crate) fn run(opt: OptCompeteAdd, ctx: crate::Context<'_>) -> anyhow::Result
     let OptCompeteAdd {
         full,
         open,
+        offline,
+        template,
         package,
         manifest_path,
+        profile: _,
         color,
         args,
     } = opt;
@@ -59,11 +78,18 @@ pub(crate) fn run(opt: OptCompeteAdd, ctx: crate::Context<'_>) -> anyhow::Result
     let crate::Context {
         cwd,
         cookies_path,
+        cache_dir,
+        replay_dir: _,
+        profile,
         shell,
     } = ctx;
 
+    let profile = profile.as_deref();
+
     shell.set_color_choice(color);
 
+    let cache = &ProblemCache::new(&cache_dir, offline);
+
     let manifest_path = manifest_path
         .map(|p| Ok(cwd.join(p.strip_prefix(".").unwrap_or(&p))))
         .unwrap_or_else(|| crate::project::locate_project(&cwd))?;
@@ -71,135 +97,117 @@ pub(crate) fn run(opt: OptCompeteAdd, ctx: crate::Context<'_>) -> anyhow::Result
     let member = metadata.query_for_member(package.as_deref())?;
     let (cargo_compete_config, cargo_compete_config_path) =
         crate::config::load_for_package(member, shell)?;
-    let src_content = &cargo_compete_config
+    let src_template = &cargo_compete_config
         .template(&cargo_compete_config_path, shell)?
         .src;
-    let cargo_compete_config_add = cargo_compete_config
-        .add
-        .as_ref()
-        .with_context(|| "`add` field is required for this command")?;
-
-    let url = cargo_compete_config_add
-        .url
-        .render(&object!({ "args": &args }))?;
-    ensure!(!url.is_empty(), "empty URL for {:?}", args);
-    let url = url
-        .parse::<Url>()
-        .with_context(|| format!("could not parse {url:?} as a URL"))?;
-
-    let is_contest = if let Some(args) = &cargo_compete_config_add.is_contest {
-        ensure!(!args.is_empty(), "`add.is-contest` is empty");
-        crate::process::process(&args[0])
-            .args(&args[1..])
-            .pipe_input(Some(url.as_str()))
-            .cwd(&metadata.workspace_root)
-            .status()?
-            .success()
+    let cargo_compete_config_add = cargo_compete_config.add.as_ref();
+
+    // Without `[add]`, the arguments are URLs of problems or contests.
+    let targets = if let Some(cargo_compete_config_add) = cargo_compete_config_add {
+        let url = cargo_compete_config_add
+            .url
+            .render(&object!({ "args": &args }))?;
+        ensure!(!url.is_empty(), "empty URL for {:?}", args);
+        let url = url
+            .parse::<Url>()
+            .with_context(|| format!("could not parse {url:?} as a URL"))?;
+
+        let is_contest = if let Some(args) = &cargo_compete_config_add.is_contest {
+            ensure!(!args.is_empty(), "`add.is-contest` is empty");
+            crate::process::process(&args[0])
+                .args(&args[1..])
+                .pipe_input(Some(url.as_str()))
+                .cwd(&metadata.workspace_root)
+                .status()?
+                .success()
+        } else {
+            false
+        };
+
+        vec![(url, Some(is_contest))]
     } else {
-        false
+        ensure!(
+            !args.is_empty(),
+            "no URLs given. give URLs of problems or contests, or set `add` in `{}`",
+            cargo_compete_config_path,
+        );
+        args.iter()
+            .map(|arg| {
+                let url = arg.parse::<Url>().with_context(|| {
+                    format!(
+                        "could not parse {arg:?} as a URL. without `add` in \
+                         `{cargo_compete_config_path}`, the arguments must be URLs",
+                    )
+                })?;
+                let is_contest = is_contest_url(&url);
+                Ok((url, is_contest))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?
     };
 
-    let problems = match PlatformKind::from_url(&url) {
-        Ok(PlatformKind::Atcoder) => crate::web::retrieve_testcases::dl_from_atcoder(
-            if is_contest {
-                ProblemsInContest::Indexes {
-                    contest: crate::web::url::atcoder_contest(&url)?,
-                    problems: None,
-                }
-            } else {
-                ProblemsInContest::Urls {
-                    urls: btreeset!(url),
-                }
-            },
+    let mut problems = vec![];
+    for (url, is_contest) in targets {
+        problems.extend(retrieve(
+            url,
+            is_contest,
             full,
+            &metadata.workspace_root,
             &cookies_path,
+            profile,
+            &cache_dir,
+            cache,
             shell,
-        )?
-        .into_iter()
-        .map(crate::web::retrieve_testcases::Problem::<Option<String>>::from)
-        .collect::<Vec<_>>(),
-
-        Ok(PlatformKind::Codeforces) => crate::web::retrieve_testcases::dl_from_codeforces(
-            if is_contest {
-                ProblemsInContest::Indexes {
-                    contest: crate::web::url::codeforces_contest(&url)?,
-                    problems: None,
-                }
-            } else {
-                ProblemsInContest::Urls {
-                    urls: btreeset!(url),
-                }
-            },
-            &cookies_path,
-            shell,
-        )?
-        .into_iter()
-        .map(crate::web::retrieve_testcases::Problem::<Option<String>>::from)
-        .collect::<Vec<_>>(),
-
-        Ok(PlatformKind::Yukicoder) => crate::web::retrieve_testcases::dl_from_yukicoder(
-            if is_contest {
-                YukicoderRetrieveTestCasesTargets::Contest(
-                    crate::web::url::codeforces_contest(&url)?,
-                    None,
-                )
-            } else {
-                YukicoderRetrieveTestCasesTargets::Urls(btreeset!(url))
-            },
-            full,
-            shell,
-        )?
-        .into_iter()
-        .map(crate::web::retrieve_testcases::Problem::<Option<String>>::from)
-        .collect::<Vec<_>>(),
+        )?);
+    }
 
-        Err(_) => if is_contest {
-            oj_api::get_contest(&url, &metadata.workspace_root, shell)?
-        } else {
-            vec![(url, None)]
-        }
-        .into_iter()
-        .map(|(problem_url, alphabet)| {
-            let problem = oj_api::get_problem(&problem_url, full, &metadata.workspace_root, shell)?;
-            let mut problem = crate::web::retrieve_testcases::Problem::from_oj_api(problem, full);
-            if let Some(problem_index) = alphabet {
-                problem.index = Some(problem_index);
-            }
-            Ok(problem)
-        })
-        .collect::<anyhow::Result<_>>()?,
-    };
+    crate::web::statement::retrieve_all(
+        &mut problems,
+        &cargo_compete_config.statement,
+        &cookies_path,
+        cache,
+        shell,
+    )?;
 
     let manifest =
         &mut crate::fs::read_to_string(&member.manifest_path)?.parse::<toml_edit::Document>()?;
 
     let mut abs_bin_src_paths = vec![];
+    let mut src_contents = vec![];
     let mut urls_to_open = vec![];
     let mut bin_names_by_url = hashmap!();
     let mut bin_aliases_by_url = hashmap!();
 
     for problem in &problems {
-        let CargoCompeteConfigAdd {
-            target_kind,
-            bin_name,
-            bin_alias,
-            bin_src_path,
-            ..
-        } = cargo_compete_config_add;
-
-        let bin_name = &*bin_name.render(&object!({
-            "args": &args,
-            "url": &problem.url,
-        }))?;
-        let bin_alias = &*bin_alias.render(&object!({
-            "args": &args,
-            "url": &problem.url,
-            "bin_name": bin_name,
-        }))?;
+        let (target_kind, bin_name, bin_alias, bin_src_path) =
+            if let Some(cargo_compete_config_add) = cargo_compete_config_add {
+                let CargoCompeteConfigAdd {
+                    target_kind,
+                    bin_name,
+                    bin_alias,
+                    bin_src_path,
+                    ..
+                } = cargo_compete_config_add;
+
+                let bin_name = bin_name.render(&object!({
+                    "args": &args,
+                    "url": &problem.url,
+                }))?;
+                let bin_alias = bin_alias.render(&object!({
+                    "args": &args,
+                    "url": &problem.url,
+                    "bin_name": &bin_name,
+                }))?;
+                (*target_kind, bin_name, bin_alias, bin_src_path.as_ref())
+            } else {
+                let bin_name = bin_name_from_url(&problem.url, problem.index.as_deref());
+                let bin_alias = bin_name.clone();
+                (BinLikeTargetKind::Bin, bin_name, bin_alias, None)
+            };
+        let (bin_name, bin_alias) = (&*bin_name, &*bin_alias);
+
         let bin_src_path = &*bin_src_path
-            .as_ref()
             .unwrap_or_else(|| {
-                return match *target_kind {
+                return match target_kind {
                     BinLikeTargetKind::Bin => &DEFAULT_BIN_PATH,
                     BinLikeTargetKind::ExampleBin => &DEFAULT_EXAMPLE_PATH,
                 };
@@ -231,16 +239,30 @@ pub(crate) fn run(opt: OptCompeteAdd, ctx: crate::Context<'_>) -> anyhow::Result
         {
             bail!("binary `{}` already exists", bin_name);
         }
+        if bin_names_by_url.values().any(|name| name == bin_name) {
+            bail!(
+                "binary `{}` is generated for more than one of the problems. set `add` in `{}` to \
+                 name them",
+                bin_name,
+                cargo_compete_config_path,
+            );
+        }
 
         let abs_bin_src_path = member.manifest_path.with_file_name("").join(bin_src_path);
-        crate::fs::create_dir_all(abs_bin_src_path.with_file_name(""))?;
-        crate::fs::write(&abs_bin_src_path, src_content)?;
+        let src_content = src_template.render(
+            template.as_deref(),
+            bin_name,
+            bin_alias,
+            &problem.url,
+            &problem.test_suite,
+        )?;
+        src_contents.push(src_content);
         abs_bin_src_paths.push(abs_bin_src_path);
         urls_to_open.push(problem.url.clone());
         bin_names_by_url.insert(problem.url.clone(), bin_name.to_owned());
         bin_aliases_by_url.insert(problem.url.clone(), bin_alias.to_owned());
 
-        let target_kind = match cargo_compete_config_add.target_kind {
+        let target_kind = match target_kind {
             BinLikeTargetKind::Bin => "bin",
             BinLikeTargetKind::ExampleBin => "example",
         };
@@ -285,6 +307,11 @@ pub(crate) fn run(opt: OptCompeteAdd, ctx: crate::Context<'_>) -> anyhow::Result
         )?;
     }
 
+    // Nothing is written until all of the names are checked.
+    for (abs_bin_src_path, src_content) in itertools::zip_eq(&abs_bin_src_paths, src_contents) {
+        crate::fs::create_dir_all(abs_bin_src_path.with_file_name(""))?;
+        crate::fs::write(abs_bin_src_path, src_content)?;
+    }
     crate::fs::write(&member.manifest_path, manifest.to_string())?;
 
     let file_paths = itertools::zip_eq(
@@ -305,12 +332,182 @@ pub(crate) fn run(opt: OptCompeteAdd, ctx: crate::Context<'_>) -> anyhow::Result
     if open {
         crate::open::open(
             &urls_to_open,
-            cargo_compete_config.open,
+            cargo_compete_config.open.as_deref(),
             &file_paths,
             member.manifest_dir(),
             &cargo_compete_config_path.with_file_name(""),
             shell,
         )?;
     }
+
+    for (url, (src_path, test_suite_path)) in itertools::zip_eq(&urls_to_open, &file_paths) {
+        crate::hooks::run(
+            &cargo_compete_config.hooks,
+            Hook::PostAdd,
+            &object!({
+                "manifest_dir": member.manifest_dir().as_str(),
+                "contest": crate::web::url::contest(url),
+                "bin_name": &bin_names_by_url[url],
+                "bin_alias": &bin_aliases_by_url[url],
+                "problem_url": url.as_str(),
+                "src_path": src_path.as_str(),
+                "test_suite_path": test_suite_path.as_str(),
+            }),
+            &metadata.workspace_root,
+            shell,
+        )?;
+    }
     Ok(())
 }
+
+/// Retrieves the problems. `is_contest` is `None` if it is not known from the URL.
+#[allow(clippy::too_many_arguments)]
+fn retrieve(
+    url: Url,
+    is_contest: Option<bool>,
+    full: bool,
+    workspace_root: &Utf8Path,
+    cookies_path: &Path,
+    profile: Option<&str>,
+    cache_dir: &Path,
+    cache: &ProblemCache,
+    shell: &mut Shell,
+) -> anyhow::Result<Vec<crate::web::retrieve_testcases::Problem<Option<String>>>> {
+    let problems = match PlatformKind::from_url(&url) {
+        Ok(PlatformKind::Atcoder) => crate::web::retrieve_testcases::dl_from_atcoder(
+            if is_contest == Some(true) {
+                ProblemsInContest::Indexes {
+                    contest: crate::web::url::atcoder_contest(&url)?,
+                    problems: None,
+                }
+            } else {
+                ProblemsInContest::Urls {
+                    urls: btreeset!(url),
+                }
+            },
+            full,
+            cookies_path,
+            profile,
+            cache,
+            shell,
+        )?
+        .into_iter()
+        .map(crate::web::retrieve_testcases::Problem::<Option<String>>::from)
+        .collect::<Vec<_>>(),
+
+        Ok(PlatformKind::Codeforces) => crate::web::retrieve_testcases::dl_from_codeforces(
+            if is_contest == Some(true) {
+                ProblemsInContest::Indexes {
+                    contest: crate::web::url::codeforces_contest(&url)?,
+                    problems: None,
+                }
+            } else {
+                ProblemsInContest::Urls {
+                    urls: btreeset!(url),
+                }
+            },
+            cookies_path,
+            profile,
+            cache,
+            shell,
+        )?
+        .into_iter()
+        .map(crate::web::retrieve_testcases::Problem::<Option<String>>::from)
+        .collect::<Vec<_>>(),
+
+        Ok(PlatformKind::Yukicoder) => crate::web::retrieve_testcases::dl_from_yukicoder(
+            if is_contest == Some(true) {
+                YukicoderRetrieveTestCasesTargets::Contest(
+                    crate::web::url::yukicoder_contest(&url)?,
+                    None,
+                )
+            } else {
+                YukicoderRetrieveTestCasesTargets::Urls(btreeset!(url))
+            },
+            full,
+            cookies_path,
+            profile,
+            cache,
+            shell,
+        )?
+        .into_iter()
+        .map(crate::web::retrieve_testcases::Problem::<Option<String>>::from)
+        .collect::<Vec<_>>(),
+
+        Err(_) => match is_contest {
+            Some(true) => oj_api::get_contest(&url, workspace_root, Some(cache), shell)?,
+            Some(false) => vec![(url, None)],
+            // Not known from the URL. Ask oj-api whether it is a contest.
+            None => match oj_api::get_contest(&url, workspace_root, Some(cache), shell) {
+                Ok(problems) => problems,
+                Err(err) if oj_api::is_unsupported_url(&err) => vec![(url, None)],
+                Err(err) => {
+                    shell.warn(format!(
+                        "could not ask oj-api whether {url} is a contest. adding it as a \
+                         problem: {err}",
+                    ))?;
+                    vec![(url, None)]
+                }
+            },
+        }
+        .into_iter()
+        .map(|(problem_url, alphabet)| {
+            let mut problem = crate::web::retrieve_testcases::dl_from_other(
+                &problem_url,
+                full,
+                workspace_root,
+                cache_dir,
+                Some(cache),
+                shell,
+            )?;
+            if let Some(problem_index) = alphabet {
+                problem.index = Some(problem_index);
+            }
+            Ok(problem)
+        })
+        .collect::<anyhow::Result<_>>()?,
+    };
+    Ok(problems)
+}
+
+/// Whether the URL is of a contest rather than a problem. `None` for unknown platforms.
+fn is_contest_url(url: &Url) -> Option<bool> {
+    if library_checker::is_problem_url(url) || aoj::is_problem_url(url) {
+        return Some(false);
+    }
+
+    let segments = url
+        .path_segments()?
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+
+    match PlatformKind::from_url(url).ok()? {
+        PlatformKind::Atcoder => Some(!matches!(*segments, ["contests", _, "tasks", _])),
+        // Problems are at `/{contest,gym}/{contest}/problem/{index}` and
+        // `/problemset/problem/{contest}/{index}`.
+        PlatformKind::Codeforces => Some(matches!(
+            *segments,
+            ["contest" | "gym", _] | ["contest" | "gym", _, "problems"],
+        )),
+        PlatformKind::Yukicoder => Some(segments.first() == Some(&"contests")),
+    }
+}
+
+/// `bin` name for a problem added without `add` in `compete.toml`, such as `abc100-a`.
+fn bin_name_from_url(url: &Url, index: Option<&str>) -> String {
+    let index = index
+        .map(ToOwned::to_owned)
+        .or_else(|| Some(url.path_segments()?.rfind(|s| !s.is_empty())?.to_owned()))
+        .unwrap_or_else(|| "main".to_owned())
+        .to_kebab_case();
+
+    let name = match crate::web::url::contest(url) {
+        Some(contest) => format!("{}-{}", contest.to_kebab_case(), index),
+        None => index,
+    };
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("problem{name}")
+    } else {
+        name
+    }
+}
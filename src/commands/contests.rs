@@ -0,0 +1,230 @@
+use crate::{
+    commands::new::OptCompeteNew,
+    config::CargoCompeteConfigNew,
+    shell::{ColorChoice, Shell},
+    web::{contests::Contest, http},
+};
+use anyhow::bail;
+use camino::Utf8PathBuf;
+use chrono::{Local, Utc};
+use prettytable::{
+    format::{FormatBuilder, LinePosition, LineSeparator},
+    row, Table,
+};
+use snowchains_core::web::PlatformKind;
+use std::{borrow::BorrowMut as _, cell::RefCell};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteContests {
+    /// Platform
+    #[structopt(
+        long,
+        value_name("PLATFORM"),
+        possible_values(&["atcoder", "codeforces", "yukicoder"])
+    )]
+    pub platform: Option<PlatformKind>,
+
+    /// Participate in a contest chosen from the list
+    #[structopt(long)]
+    pub participate: bool,
+
+    /// Create a package for a contest chosen from the list as soon as it starts
+    #[structopt(long)]
+    pub new: bool,
+
+    /// Path to `compete.toml` for `--new`
+    #[structopt(long, value_name("PATH"))]
+    pub config: Option<Utf8PathBuf>,
+
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+}
+
+pub(crate) fn run(opt: OptCompeteContests, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteContests {
+        platform,
+        participate,
+        new,
+        config,
+        profile,
+        color,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        cookies_path,
+        cache_dir,
+        replay_dir,
+        profile: profile_name,
+        shell,
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    // Fail before sending requests.
+    let new_platform = if new {
+        let config_path = crate::config::locate(&cwd, config.as_ref())?;
+        match crate::config::load(&config_path, shell)?.new {
+            CargoCompeteConfigNew::CargoCompete { platform, .. } => Some(platform),
+            _ => bail!(
+                "`--new` requires `new.kind = \"cargo-compete\"`: {}",
+                config_path
+            ),
+        }
+    } else {
+        None
+    };
+
+    let platforms = match platform.or(new_platform) {
+        Some(platform) => vec![platform],
+        None => vec![
+            PlatformKind::Atcoder,
+            PlatformKind::Codeforces,
+            PlatformKind::Yukicoder,
+        ],
+    };
+
+    let mut contests = vec![];
+    for platform in platforms {
+        let shell = RefCell::new(shell.borrow_mut());
+        contests.extend(http::with_retries(&shell, || {
+            crate::web::contests::list(platform, &cookies_path)
+        })?);
+    }
+    contests.sort_by_key(|c| c.start);
+
+    print_contests(&contests, shell)?;
+
+    if !(participate || new) {
+        return Ok(());
+    }
+
+    let contest = choose(&contests, shell)?;
+
+    if let Some(new_platform) = new_platform {
+        if contest.platform != new_platform {
+            bail!(
+                "`new.platform` is `{}`, but {} is on {}",
+                new_platform.to_kebab_case_str(),
+                contest.id,
+                contest.platform.to_kebab_case_str(),
+            );
+        }
+    }
+
+    if participate {
+        match contest.platform {
+            PlatformKind::Atcoder | PlatformKind::Codeforces => {
+                crate::commands::participate::participate(
+                    contest.platform,
+                    contest.id.clone(),
+                    &cookies_path,
+                    profile_name.as_deref(),
+                    shell,
+                )?;
+            }
+            PlatformKind::Yukicoder => shell.warn("registration is not needed for yukicoder")?,
+        }
+    }
+
+    if new {
+        crate::commands::new::run(
+            OptCompeteNew {
+                full: false,
+                open: false,
+                problems: None,
+                template: None,
+                append: false,
+                offline: false,
+                wait: true,
+                virtual_contest: None,
+                from_list: None,
+                name: None,
+                config,
+                profile,
+                color,
+                contest: Some(contest.id.clone()),
+            },
+            crate::Context {
+                cwd,
+                cookies_path,
+                cache_dir,
+                replay_dir,
+                profile: profile_name,
+                shell,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn print_contests(contests: &[Contest], shell: &mut Shell) -> anyhow::Result<()> {
+    let now = Utc::now();
+
+    let mut table = Table::new();
+    *table.get_format() = FormatBuilder::new()
+        .padding(1, 1)
+        .column_separator('│')
+        .borders('│')
+        .separator(LinePosition::Top, LineSeparator::new('─', '┬', '┌', '┐'))
+        .separator(LinePosition::Title, LineSeparator::new('─', '┼', '├', '┤'))
+        .separator(LinePosition::Bottom, LineSeparator::new('─', '┴', '└', '┘'))
+        .build();
+    table.set_titles(row![
+        "#",
+        "Platform",
+        "ID",
+        "Name",
+        "Start",
+        "Duration",
+        "Status",
+        "Registered",
+    ]);
+
+    for (i, contest) in contests.iter().enumerate() {
+        let minutes = contest.duration.num_minutes();
+        table.add_row(row![
+            r->i + 1,
+            contest.platform.to_kebab_case_str(),
+            contest.id,
+            contest.name,
+            contest.start.with_timezone(&Local).format("%Y-%m-%d %a %H:%M"),
+            r->format!("{:02}:{:02}", minutes / 60, minutes % 60),
+            if contest.start <= now { "Running" } else { "Upcoming" },
+            match contest.registered {
+                Some(true) => "Yes",
+                Some(false) => "No",
+                None => "-",
+            },
+        ]);
+    }
+
+    write!(shell.out(), "{table}")?;
+    shell.out().flush().map_err(Into::into)
+}
+
+fn choose<'a>(contests: &'a [Contest], shell: &mut Shell) -> anyhow::Result<&'a Contest> {
+    if contests.is_empty() {
+        bail!("no contests to choose");
+    }
+
+    loop {
+        let reply = shell.read_reply(&format!("1..{}: ", contests.len()))?;
+        match reply.trim().parse::<usize>() {
+            Ok(n) if (1..=contests.len()).contains(&n) => break Ok(&contests[n - 1]),
+            _ => writeln!(shell.err(), "Choose 1..{}.", contests.len())?,
+        }
+    }
+}
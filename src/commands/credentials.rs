@@ -0,0 +1,165 @@
+use crate::{
+    shell::ColorChoice,
+    web::credentials::{CredentialStore, Token},
+};
+use std::env;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteCredentialsList {
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+}
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteCredentialsSet {
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Token to set
+    #[structopt(possible_values(Token::VARIANTS))]
+    pub token: Token,
+}
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteCredentialsRemove {
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Token to remove
+    #[structopt(possible_values(Token::VARIANTS))]
+    pub token: Token,
+}
+
+pub(crate) fn run_list(
+    opt: OptCompeteCredentialsList,
+    ctx: crate::Context<'_>,
+) -> anyhow::Result<()> {
+    let OptCompeteCredentialsList { profile: _, color } = opt;
+
+    let crate::Context {
+        cwd: _,
+        cookies_path,
+        cache_dir: _,
+        replay_dir: _,
+        profile,
+        shell,
+    } = ctx;
+
+    let profile = profile.as_deref();
+
+    shell.set_color_choice(color);
+
+    let store = CredentialStore::load(&cookies_path, profile)?;
+    shell.status("Store", store.describe())?;
+
+    for &token in Token::ALL {
+        let status = if store.get(token, shell)?.is_some() {
+            "stored"
+        } else {
+            "not stored"
+        };
+        let overridden = token
+            .env_vars(profile)
+            .iter()
+            .filter(|name| env::var_os(name).is_some())
+            .map(|name| format!(" (overridden by ${name})"))
+            .collect::<String>();
+        writeln!(shell.out(), "{token}: {status}{overridden}")?;
+    }
+    shell.out().flush()?;
+    Ok(())
+}
+
+pub(crate) fn run_set(
+    opt: OptCompeteCredentialsSet,
+    ctx: crate::Context<'_>,
+) -> anyhow::Result<()> {
+    let OptCompeteCredentialsSet {
+        profile: _,
+        color,
+        token,
+    } = opt;
+
+    let crate::Context {
+        cwd: _,
+        cookies_path,
+        cache_dir: _,
+        replay_dir: _,
+        profile,
+        shell,
+    } = ctx;
+
+    let profile = profile.as_deref();
+
+    shell.set_color_choice(color);
+
+    let store = CredentialStore::load(&cookies_path, profile)?;
+    let content = token.prompt(shell)?;
+    store.set(token, &content, shell)?;
+    shell.status("Saved", format!("`{token}` to {}", store.describe()))?;
+    Ok(())
+}
+
+pub(crate) fn run_remove(
+    opt: OptCompeteCredentialsRemove,
+    ctx: crate::Context<'_>,
+) -> anyhow::Result<()> {
+    let OptCompeteCredentialsRemove {
+        profile: _,
+        color,
+        token,
+    } = opt;
+
+    let crate::Context {
+        cwd: _,
+        cookies_path,
+        cache_dir: _,
+        replay_dir: _,
+        profile,
+        shell,
+    } = ctx;
+
+    let profile = profile.as_deref();
+
+    shell.set_color_choice(color);
+
+    let store = CredentialStore::load(&cookies_path, profile)?;
+    if store.remove(token, shell)? {
+        shell.status("Removed", format!("`{token}` from {}", store.describe()))?;
+    } else {
+        shell.warn(format!("`{token}` is not stored in {}", store.describe()))?;
+    }
+    Ok(())
+}
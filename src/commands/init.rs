@@ -47,6 +47,9 @@ pub(crate) fn run(opt: OptCompeteInit, ctx: crate::Context<'_>) -> anyhow::Resul
     let crate::Context {
         cwd,
         cookies_path: _,
+        cache_dir: _,
+        replay_dir: _,
+        profile: _,
         shell,
     } = ctx;
 
@@ -0,0 +1,256 @@
+use crate::{
+    shell::{ColorChoice, Shell},
+    web::{credentials, http},
+};
+use anyhow::{bail, Context as _};
+use camino::{Utf8Path, Utf8PathBuf};
+use indexmap::IndexMap;
+use regex::Regex;
+use snowchains_core::web::{
+    Atcoder, AtcoderRetrieveLanguagesCredentials, AtcoderRetrieveLanguagesTarget, Codeforces,
+    CodeforcesRetrieveLanguagesCredentials, CodeforcesRetrieveLanguagesTarget, CookieStorage,
+    PlatformKind, RetrieveLanguages, Yukicoder,
+};
+use std::{borrow::BorrowMut as _, cell::RefCell};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use url::Url;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteLanguages {
+    /// Write the suggested language ID to `submit.language_id` in `compete.toml`
+    #[structopt(long)]
+    pub write: bool,
+
+    /// Path to `compete.toml`
+    #[structopt(long, value_name("PATH"))]
+    pub config: Option<Utf8PathBuf>,
+
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Platform (`atcoder`, `codeforces`, or `yukicoder`) or URL of a contest/problem
+    pub platform_or_url: String,
+}
+
+pub(crate) fn run(opt: OptCompeteLanguages, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteLanguages {
+        write,
+        config,
+        profile: _,
+        color,
+        platform_or_url,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        cookies_path,
+        cache_dir: _,
+        replay_dir: _,
+        profile,
+        shell,
+    } = ctx;
+
+    let profile = profile.as_deref();
+
+    shell.set_color_choice(color);
+
+    let (platform, url) = if let Ok(url) = platform_or_url.parse::<Url>() {
+        (PlatformKind::from_url(&url)?, Some(url))
+    } else if let Ok(platform) = platform_or_url.parse() {
+        (platform, None)
+    } else {
+        bail!(
+            "expected one of {:?} or a URL, got {:?}",
+            PlatformKind::KEBAB_CASE_VARIANTS,
+            platform_or_url,
+        );
+    };
+
+    let timeout = http::timeout();
+
+    let outcome = match platform {
+        PlatformKind::Atcoder => {
+            let contest = url
+                .as_ref()
+                .map(crate::web::url::atcoder_contest)
+                .transpose()?;
+
+            let shell = RefCell::new(shell.borrow_mut());
+
+            // The language list is shared by the problems in a contest, but the first task is not
+            // necessarily `A`.
+            let contest_and_problem = contest
+                .map(|contest| {
+                    let problem = http::with_retries(&shell, || {
+                        crate::web::contests::atcoder_first_task(&contest, &cookies_path)
+                    })?;
+                    Ok::<_, anyhow::Error>((contest, problem))
+                })
+                .transpose()?;
+
+            let username_and_password = &mut credentials::username_and_password(
+                &shell,
+                profile,
+                PlatformKind::Atcoder,
+                "Username: ",
+                "Password: ",
+            );
+
+            http::with_retries(&shell, || {
+                http::with_env(|| {
+                    Atcoder::exec(RetrieveLanguages {
+                        target: AtcoderRetrieveLanguagesTarget {
+                            contest_and_problem: contest_and_problem.clone(),
+                        },
+                        credentials: AtcoderRetrieveLanguagesCredentials {
+                            username_and_password,
+                        },
+                        cookie_storage: CookieStorage::with_jsonl(&cookies_path)?,
+                        timeout,
+                        shell: &shell,
+                    })
+                })
+            })?
+        }
+        PlatformKind::Codeforces => {
+            let contest = url
+                .as_ref()
+                .map(crate::web::url::codeforces_contest)
+                .transpose()?
+                .with_context(|| "specify a contest/problem URL for Codeforces")?;
+
+            let shell = RefCell::new(shell.borrow_mut());
+
+            let username_and_password = &mut credentials::username_and_password(
+                &shell,
+                profile,
+                PlatformKind::Codeforces,
+                "Handle/Email: ",
+                "Password: ",
+            );
+
+            http::with_retries(&shell, || {
+                http::with_env(|| {
+                    Codeforces::exec(RetrieveLanguages {
+                        target: CodeforcesRetrieveLanguagesTarget {
+                            contest: contest.clone(),
+                        },
+                        credentials: CodeforcesRetrieveLanguagesCredentials {
+                            username_and_password,
+                        },
+                        cookie_storage: CookieStorage::with_jsonl(&cookies_path)?,
+                        timeout,
+                        shell: &shell,
+                    })
+                })
+            })?
+        }
+        PlatformKind::Yukicoder => {
+            let shell = RefCell::new(shell.borrow_mut());
+
+            http::with_retries(&shell, || {
+                http::with_env(|| {
+                    Yukicoder::exec(RetrieveLanguages {
+                        target: (),
+                        credentials: (),
+                        cookie_storage: (),
+                        timeout,
+                        shell: &shell,
+                    })
+                })
+            })?
+        }
+    };
+
+    write!(shell.out(), "{}", outcome.to_table())?;
+    shell.out().flush()?;
+
+    let (id, name) = newest_rust(&outcome.names_by_id).with_context(|| "could not find Rust")?;
+    shell.status("Suggested", format!("{id} ({name})"))?;
+
+    if write {
+        let cargo_compete_config_path = crate::config::locate(&cwd, config)?;
+        write_language_id(&cargo_compete_config_path, id, shell)?;
+    }
+    Ok(())
+}
+
+/// Picks the Rust entry with the highest version number.
+fn newest_rust(names_by_id: &IndexMap<String, String>) -> Option<(&str, &str)> {
+    let version = Regex::new(r"([0-9]+)\.([0-9]+)(\.([0-9]+))?").unwrap();
+
+    names_by_id
+        .iter()
+        .filter(|(_, name)| name.to_lowercase().contains("rust"))
+        .max_by_key(|(_, name)| {
+            version.captures(name).map(|caps| {
+                [1, 2, 4].map(|i| {
+                    caps.get(i)
+                        .and_then(|m| m.as_str().parse::<u64>().ok())
+                        .unwrap_or(0)
+                })
+            })
+        })
+        .map(|(id, name)| (&**id, &**name))
+}
+
+fn write_language_id(
+    cargo_compete_config_path: &Utf8Path,
+    language_id: &str,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let mut cargo_compete_config = crate::fs::read_to_string(cargo_compete_config_path)?
+        .parse::<toml_edit::Document>()
+        .with_context(|| {
+            format!("could not parse the TOML file at `{cargo_compete_config_path}`")
+        })?;
+
+    let submit = &mut cargo_compete_config["submit"];
+    if submit.is_none() {
+        bail!("`submit` is not set in `{cargo_compete_config_path}`");
+    }
+    let submit = if submit.get("transpile").is_some() {
+        &mut submit["transpile"]
+    } else {
+        submit
+    };
+    submit["language_id"] = toml_edit::value(language_id);
+
+    crate::fs::write(cargo_compete_config_path, cargo_compete_config.to_string())?;
+    shell.status(
+        "Wrote",
+        format!("`language_id = {language_id:?}` to `{cargo_compete_config_path}`"),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn newest_rust() {
+        let names_by_id = indexmap!(
+            "4050".to_owned() => "Rust (1.42.0)".to_owned(),
+            "5054".to_owned() => "Rust (rustc 1.70.0)".to_owned(),
+            "5055".to_owned() => "C++ 20 (gcc 12.2)".to_owned(),
+            "4999".to_owned() => "Rust (1.9.0)".to_owned(),
+        );
+        assert_eq!(
+            Some(("5054", "Rust (rustc 1.70.0)")),
+            super::newest_rust(&names_by_id),
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     shell::{ColorChoice, Shell},
-    web::credentials,
+    web::{credentials, http},
 };
 use snowchains_core::web::{
     Atcoder, AtcoderLoginCredentials, Codeforces, CodeforcesLoginCredentials, CookieStorage, Login,
@@ -13,6 +13,10 @@ use termcolor::Color;
 
 #[derive(StructOpt, Debug)]
 pub struct OptCompeteLogin {
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
     /// Coloring
     #[structopt(
         long,
@@ -28,18 +32,27 @@ pub struct OptCompeteLogin {
 }
 
 pub(crate) fn run(opt: OptCompeteLogin, ctx: crate::Context<'_>) -> anyhow::Result<()> {
-    let OptCompeteLogin { color, platform } = opt;
+    let OptCompeteLogin {
+        profile: _,
+        color,
+        platform,
+    } = opt;
 
     let crate::Context {
         cwd: _,
         cookies_path,
+        cache_dir: _,
+        replay_dir: _,
+        profile,
         shell,
     } = ctx;
 
+    let profile = profile.as_deref();
+
     shell.set_color_choice(color);
 
-    let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
-    let timeout = crate::web::TIMEOUT;
+    let cookie_storage = CookieStorage::with_jsonl(&cookies_path)?;
+    let timeout = http::timeout();
 
     match platform {
         PlatformKind::Atcoder => {
@@ -49,16 +62,20 @@ pub(crate) fn run(opt: OptCompeteLogin, ctx: crate::Context<'_>) -> anyhow::Resu
                 let credentials = AtcoderLoginCredentials {
                     username_and_password: &mut credentials::username_and_password(
                         &shell,
+                        profile,
+                        PlatformKind::Atcoder,
                         "Username: ",
                         "Password: ",
                     ),
                 };
 
-                Atcoder::exec(Login {
-                    credentials,
-                    cookie_storage,
-                    timeout,
-                    shell: &shell,
+                http::with_env(|| {
+                    Atcoder::exec(Login {
+                        credentials,
+                        cookie_storage,
+                        timeout,
+                        shell: &shell,
+                    })
                 })?
             };
 
@@ -71,22 +88,27 @@ pub(crate) fn run(opt: OptCompeteLogin, ctx: crate::Context<'_>) -> anyhow::Resu
                 let credentials = CodeforcesLoginCredentials {
                     username_and_password: &mut credentials::username_and_password(
                         &shell,
+                        profile,
+                        PlatformKind::Codeforces,
                         "Handle/Email: ",
                         "Password: ",
                     ),
                 };
 
-                Codeforces::exec(Login {
-                    credentials,
-                    cookie_storage,
-                    timeout,
-                    shell: &shell,
+                http::with_env(|| {
+                    Codeforces::exec(Login {
+                        credentials,
+                        cookie_storage,
+                        timeout,
+                        shell: &shell,
+                    })
                 })?
             };
 
             status(shell, outcome)?;
 
-            let (api_key, api_secret) = credentials::codeforces_api_key_and_secret(shell)?;
+            let (api_key, api_secret) =
+                credentials::codeforces_api_key_and_secret(&cookies_path, profile, shell)?;
 
             writeln!(
                 shell.err(),
@@ -97,7 +119,7 @@ pub(crate) fn run(opt: OptCompeteLogin, ctx: crate::Context<'_>) -> anyhow::Resu
             shell.err().flush()?;
         }
         PlatformKind::Yukicoder => {
-            let api_key = credentials::yukicoder_api_key(shell)?;
+            let api_key = credentials::yukicoder_api_key(&cookies_path, profile, shell)?;
 
             writeln!(
                 shell.err(),
@@ -0,0 +1,73 @@
+use crate::{
+    shell::ColorChoice,
+    web::{credentials::CredentialStore, session},
+};
+use snowchains_core::web::PlatformKind;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteLogout {
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Platform to logout
+    #[structopt(possible_values(PlatformKind::KEBAB_CASE_VARIANTS))]
+    pub platform: PlatformKind,
+}
+
+pub(crate) fn run(opt: OptCompeteLogout, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteLogout {
+        profile: _,
+        color,
+        platform,
+    } = opt;
+
+    let crate::Context {
+        cwd: _,
+        cookies_path,
+        cache_dir: _,
+        replay_dir: _,
+        profile,
+        shell,
+    } = ctx;
+
+    let profile = profile.as_deref();
+
+    shell.set_color_choice(color);
+
+    let num_cookies = session::remove_cookies(platform, &cookies_path)?;
+    if num_cookies > 0 {
+        shell.status(
+            "Removed",
+            format!(
+                "{num_cookies} cookie(s) for {platform} from {}",
+                cookies_path.display()
+            ),
+        )?;
+    }
+
+    let mut removed_token = false;
+    if let Some(token) = super::whoami::api_key(platform) {
+        let store = CredentialStore::load(&cookies_path, profile)?;
+        if store.remove(token, shell)? {
+            shell.status("Removed", format!("`{token}` from {}", store.describe()))?;
+            removed_token = true;
+        }
+    }
+
+    if num_cookies == 0 && !removed_token {
+        shell.warn(format!("not logged in to {platform}"))?;
+    }
+    Ok(())
+}
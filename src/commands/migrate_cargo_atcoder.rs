@@ -57,6 +57,9 @@ pub(crate) fn run(
     let crate::Context {
         cwd,
         cookies_path: _,
+        cache_dir: _,
+        replay_dir: _,
+        profile: _,
         shell,
     } = ctx;
 
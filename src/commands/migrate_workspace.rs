@@ -0,0 +1,196 @@
+use crate::shell::ColorChoice;
+use anyhow::{anyhow, bail, Context as _};
+use camino::Utf8PathBuf;
+use ignore::{overrides::OverrideBuilder, WalkBuilder};
+use itertools::Itertools as _;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::Color;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteMigrateWorkspace {
+    /// Process glob patterns given with the `--glob` flag case insensitively
+    #[structopt(long)]
+    pub glob_case_insensitive: bool,
+
+    /// Include or exclude manifest paths. For more detail, see the help of ripgrep
+    #[structopt(short, long, value_name("GLOB"))]
+    pub glob: Vec<String>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Directory with `compete.toml`
+    #[structopt(default_value("."))]
+    pub path: PathBuf,
+}
+
+pub(crate) fn run(opt: OptCompeteMigrateWorkspace, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteMigrateWorkspace {
+        glob_case_insensitive,
+        glob,
+        color,
+        path,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        cookies_path: _,
+        cache_dir: _,
+        replay_dir: _,
+        profile: _,
+        shell,
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let path = Utf8PathBuf::from_path_buf(cwd.join(path.strip_prefix(".").unwrap_or(&path)))
+        .map_err(|p| anyhow!("invalid utf-8 path: {:?}", p))?;
+    let root_manifest_path = path.join("Cargo.toml");
+    let root_lock_path = path.join("Cargo.lock");
+
+    let manifest_paths = WalkBuilder::new(&path)
+        .follow_links(true)
+        .max_depth(Some(32))
+        .overrides({
+            let mut overrides = OverrideBuilder::new(&path);
+            for glob in glob {
+                overrides.add(&glob)?;
+            }
+            overrides.case_insensitive(glob_case_insensitive)?.build()?
+        })
+        .build()
+        .map(|entry| {
+            let manifest_path = entry?.into_path();
+            Ok(
+                if manifest_path.file_name() == Some("Cargo.toml".as_ref())
+                    && manifest_path != root_manifest_path
+                {
+                    Some(manifest_path)
+                } else {
+                    None
+                },
+            )
+        })
+        .flat_map(Result::transpose)
+        .collect::<Result<Vec<_>, ignore::Error>>()?;
+
+    let manifests = manifest_paths
+        .into_iter()
+        .sorted()
+        .map(|manifest_path| {
+            let manifest_path = Utf8PathBuf::from_path_buf(manifest_path)
+                .map_err(|p| anyhow!("invalid utf-8 path: {:?}", p))?;
+            let manifest = crate::fs::read_to_string(&manifest_path)?
+                .parse::<toml_edit::Document>()
+                .with_context(|| format!("could not parse the manifest at `{manifest_path}`"))?;
+            Ok((manifest_path, manifest))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let is_contest_package = |manifest: &toml_edit::Document| {
+        manifest
+            .get("package")
+            .and_then(|p| p.get("metadata"))
+            .and_then(|m| m.get("cargo-compete"))
+            .is_some()
+    };
+
+    // `profile` in a workspace member is ignored, and the root manifest can have only one. Checks
+    // them before modifying anything.
+    let mut profile = None::<(&Utf8PathBuf, toml::Value, &toml_edit::Table)>;
+    for (manifest_path, manifest) in &manifests {
+        if !is_contest_package(manifest) {
+            continue;
+        }
+        if let Some(member_profile) = manifest.get("profile").and_then(|p| p.as_table()) {
+            // Compared without the formatting.
+            let value = toml::from_str::<toml::Value>(&manifest.to_string())?["profile"].clone();
+            match &profile {
+                None => profile = Some((manifest_path, value, member_profile)),
+                Some((first_path, first_value, _)) if *first_value != value => bail!(
+                    "`profile` in `{}` differs from the one in `{}`. make them the same, or \
+                     remove either of them",
+                    manifest_path,
+                    first_path,
+                ),
+                Some(_) => {}
+            }
+        }
+    }
+    let profile = profile.map(|(_, _, profile)| profile.clone());
+
+    let mut members = vec![];
+    let mut exclude = vec![];
+    let mut resolver = None;
+
+    for (manifest_path, mut manifest) in manifests {
+        if !is_contest_package(&manifest) {
+            // Other packages under the workspace would be rejected by Cargo unless excluded.
+            shell.status_with_color("Excluding", format_args!("`{manifest_path}`"), Color::Cyan)?;
+            if manifest.get("workspace").is_none() {
+                exclude.push(manifest_path.with_file_name(""));
+            }
+            continue;
+        }
+        shell.status("Found", format_args!("`{manifest_path}`"))?;
+
+        if manifest["package"].get("edition").and_then(|e| e.as_str()) == Some("2021") {
+            resolver = Some("2");
+        }
+
+        if manifest.as_table_mut().remove("profile").is_some() {
+            crate::fs::write(&manifest_path, manifest.to_string())?;
+            shell.status("Modified", &manifest_path)?;
+        }
+
+        let lock_path = manifest_path.with_file_name("Cargo.lock");
+        if lock_path.exists() {
+            if root_lock_path.exists() {
+                crate::fs::remove_file(&lock_path)?;
+                shell.status("Removed", &lock_path)?;
+            } else {
+                crate::fs::rename(&lock_path, &root_lock_path)?;
+                shell.status("Moved", format!("`{lock_path}` to `{root_lock_path}`"))?;
+            }
+        }
+
+        members.push(manifest_path.with_file_name(""));
+    }
+
+    crate::project::add_workspace_members(
+        &path,
+        &members,
+        &exclude,
+        profile.as_ref(),
+        resolver,
+        shell,
+    )?;
+
+    shell.status("Updating", &root_lock_path)?;
+    if let Err(err) = crate::project::cargo_metadata(&root_manifest_path, &cwd) {
+        shell.warn(format!("could not update `{root_lock_path}`: {err}"))?;
+    }
+
+    let compete_toml_path = path.join("compete.toml");
+    if compete_toml_path.exists() {
+        let mut compete_toml = crate::fs::read_to_string(&compete_toml_path)?
+            .parse::<toml_edit::Document>()
+            .with_context(|| format!("could not parse `{compete_toml_path}`"))?;
+        if compete_toml.get("new").is_some_and(|n| n.is_table_like()) {
+            compete_toml["new"]["workspace"] = toml_edit::value(true);
+            crate::fs::write(&compete_toml_path, compete_toml.to_string())?;
+            shell.status("Modified", &compete_toml_path)?;
+        }
+    }
+
+    shell.status("Finished", "migrating")?;
+    Ok(())
+}
@@ -1,12 +1,21 @@
 pub(crate) mod add;
+pub(crate) mod contests;
+pub(crate) mod credentials;
 pub(crate) mod init;
+pub(crate) mod languages;
 pub(crate) mod login;
+pub(crate) mod logout;
 pub(crate) mod migrate_cargo_atcoder;
+pub(crate) mod migrate_workspace;
 pub(crate) mod new;
 pub(crate) mod open;
 pub(crate) mod participate;
+pub(crate) mod remove;
 pub(crate) mod retrieve_submission_summaries;
 pub(crate) mod retrieve_testcases;
+pub(crate) mod show;
+pub(crate) mod standings;
 pub(crate) mod submit;
 pub(crate) mod test;
 pub(crate) mod watch_submissions;
+pub(crate) mod whoami;
@@ -1,15 +1,31 @@
 use crate::{
-    config::{CargoCompeteConfig, CargoCompeteConfigNew},
+    config::{
+        CargoCompeteConfig, CargoCompeteConfigNew, CargoCompeteConfigTemplateSrc,
+        CargoCompeteConfigTest, CargoCompeteConfigTestProfile, Edition,
+    },
+    hooks::Hook,
     oj_api,
+    process::{Present, ProcessBuilder},
+    project::PackageMetadataCargoCompeteBinExample,
     shell::{ColorChoice, Shell},
+    web::cache::ProblemCache,
 };
 use anyhow::{bail, Context as _};
 use camino::{Utf8Path, Utf8PathBuf};
+use cargo_metadata as cm;
+use chrono::Utc;
 use heck::KebabCase as _;
+use indexmap::IndexMap;
 use itertools::Itertools as _;
 use liquid::object;
-use snowchains_core::web::{PlatformKind, ProblemsInContest, YukicoderRetrieveTestCasesTargets};
-use std::collections::BTreeMap;
+use snowchains_core::{
+    testsuite::TestSuite,
+    web::{PlatformKind, ProblemsInContest, YukicoderRetrieveTestCasesTargets},
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
 use structopt::StructOpt;
 use strum::VariantNames as _;
 use url::Url;
@@ -28,10 +44,51 @@ pub struct OptCompeteNew {
     #[structopt(long, value_name("INDEX"))]
     pub problems: Option<Vec<String>>,
 
+    /// Name of the template in `template.src` to use for all of the problems
+    #[structopt(long, value_name("NAME"))]
+    pub template: Option<String>,
+
+    /// Add the missing problems to the package if it already exists
+    #[structopt(long)]
+    pub append: bool,
+
+    /// Use only the cached problems without sending requests
+    #[structopt(long)]
+    pub offline: bool,
+
+    /// Wait for the contest to start, then retry until the problems are available
+    #[structopt(long, conflicts_with("offline"))]
+    pub wait: bool,
+
+    /// Create a package for a virtual contest of AtCoder Problems
+    #[structopt(
+        long("virtual"),
+        value_name("URL"),
+        conflicts_with_all(&["contest", "wait"])
+    )]
+    pub virtual_contest: Option<Url>,
+
+    /// Create a package for the problems listed in the file, one URL per line
+    #[structopt(
+        long,
+        value_name("PATH"),
+        requires("name"),
+        conflicts_with_all(&["contest", "problems", "wait", "virtual-contest"])
+    )]
+    pub from_list: Option<PathBuf>,
+
+    /// Name of the package for `--from-list`
+    #[structopt(long, value_name("NAME"), requires("from-list"))]
+    pub name: Option<String>,
+
     /// Path to `compete.toml`
     #[structopt(long, value_name("PATH"))]
     pub config: Option<Utf8PathBuf>,
 
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
     /// Coloring
     #[structopt(
         long,
@@ -50,7 +107,15 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
         full,
         open,
         problems,
+        template,
+        append,
+        offline,
+        wait,
+        virtual_contest,
+        from_list,
+        name,
         config,
+        profile: _,
         color,
         contest,
     } = opt;
@@ -58,16 +123,70 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
     let crate::Context {
         cwd,
         cookies_path,
+        cache_dir,
+        replay_dir: _,
+        profile,
         shell,
     } = ctx;
 
+    let profile = profile.as_deref();
+
     shell.set_color_choice(color);
 
+    let cache = &ProblemCache::new(&cache_dir, offline);
+
+    let from_list = from_list.map(|p| cwd.join(p));
+
     let cargo_compete_config_path = crate::config::locate(cwd, config)?;
     let cargo_compete_dir = cargo_compete_config_path.with_file_name("");
     let cargo_compete_config = crate::config::load(&cargo_compete_config_path, shell)?;
 
-    match &cargo_compete_config.new {
+    if let Some(virtual_contest) = &virtual_contest {
+        if let CargoCompeteConfigNew::None = &cargo_compete_config.new {
+            bail!("`new` is `none`: {}", cargo_compete_config_path);
+        }
+        return new_for_virtual_contest(
+            virtual_contest,
+            full,
+            open,
+            problems,
+            template.as_deref(),
+            append,
+            &cargo_compete_config_path,
+            &cargo_compete_config,
+            &cookies_path,
+            profile,
+            cache,
+            shell,
+        );
+    }
+
+    if let (Some(from_list), Some(name)) = (from_list, name) {
+        if let CargoCompeteConfigNew::None = &cargo_compete_config.new {
+            bail!("`new` is `none`: {}", cargo_compete_config_path);
+        }
+        return new_from_list(
+            &from_list,
+            name,
+            full,
+            open,
+            template.as_deref(),
+            append,
+            &cargo_compete_config_path,
+            &cargo_compete_config,
+            &cookies_path,
+            profile,
+            &cache_dir,
+            cache,
+            shell,
+        );
+    }
+
+    if wait {
+        wait_for_contest_start(&cargo_compete_config.new, contest.as_deref(), shell)?;
+    }
+
+    let (group, problems) = match &cargo_compete_config.new {
         CargoCompeteConfigNew::None => {
             bail!("`new` is `none`: {}", cargo_compete_config_path)
         }
@@ -78,57 +197,28 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
             let contest = contest.with_context(|| "`contest` is required for AtCoder")?;
             let problems = problems.map(|ps| ps.into_iter().collect());
 
-            let outcome = crate::web::retrieve_testcases::dl_from_atcoder(
-                ProblemsInContest::Indexes { contest, problems },
-                full,
-                &cookies_path,
-                shell,
-            )?;
+            let outcome = retrieve(wait, shell, |shell| {
+                crate::web::retrieve_testcases::dl_from_atcoder(
+                    ProblemsInContest::Indexes {
+                        contest: contest.clone(),
+                        problems: problems.clone(),
+                    },
+                    full,
+                    &cookies_path,
+                    profile,
+                    cache,
+                    shell,
+                )
+            })?;
 
             let group = Group::Atcoder(crate::web::url::atcoder_contest(
                 outcome
-                    .get(0)
+                    .first()
                     .and_then(|p| p.contest_url.as_ref())
                     .with_context(|| "empty result")?,
             )?);
 
-            let problems = outcome.iter().map(|p| (&*p.index, &p.url)).collect();
-
-            let urls = urls(&outcome);
-
-            let (manifest_dir, src_paths) = create_new_package(
-                &cargo_compete_config_path,
-                &cargo_compete_config,
-                &group,
-                &problems,
-                shell,
-            )?;
-
-            let file_paths = itertools::zip_eq(
-                src_paths,
-                crate::web::retrieve_testcases::save_test_cases(
-                    &cargo_compete_dir,
-                    &manifest_dir,
-                    &cargo_compete_config.test_suite,
-                    true,
-                    outcome,
-                    |_, index| vec![group.package_name() + "-" + &index.to_kebab_case()],
-                    |_, index| vec![index.to_kebab_case()],
-                    shell,
-                )?,
-            )
-            .collect::<Vec<_>>();
-
-            if open {
-                crate::open::open(
-                    &urls,
-                    cargo_compete_config.open,
-                    &file_paths,
-                    &manifest_dir,
-                    &cargo_compete_dir,
-                    shell,
-                )?;
-            }
+            (group, outcome)
         }
         CargoCompeteConfigNew::CargoCompete {
             platform: PlatformKind::Codeforces,
@@ -137,78 +227,59 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
             let contest = contest.with_context(|| "`contest` is required for Codeforces")?;
             let problems = problems.map(|ps| ps.into_iter().collect());
 
-            let outcome = crate::web::retrieve_testcases::dl_from_codeforces(
-                ProblemsInContest::Indexes { contest, problems },
-                &cookies_path,
-                shell,
-            )?;
+            let outcome = retrieve(wait, shell, |shell| {
+                crate::web::retrieve_testcases::dl_from_codeforces(
+                    ProblemsInContest::Indexes {
+                        contest: contest.clone(),
+                        problems: problems.clone(),
+                    },
+                    &cookies_path,
+                    profile,
+                    cache,
+                    shell,
+                )
+            })?;
 
             let group = Group::Codeforces(crate::web::url::codeforces_contest(
                 outcome
-                    .get(0)
+                    .first()
                     .and_then(|p| p.contest_url.as_ref())
                     .with_context(|| "empty result")?,
             )?);
 
-            let problems = outcome.iter().map(|p| (&*p.index, &p.url)).collect();
-
-            let urls = urls(&outcome);
-
-            let (manifest_dir, src_paths) = create_new_package(
-                &cargo_compete_config_path,
-                &cargo_compete_config,
-                &group,
-                &problems,
-                shell,
-            )?;
-
-            let file_paths = itertools::zip_eq(
-                src_paths,
-                crate::web::retrieve_testcases::save_test_cases(
-                    &cargo_compete_dir,
-                    &manifest_dir,
-                    &cargo_compete_config.test_suite,
-                    true,
-                    outcome,
-                    |_, index| vec![group.package_name() + "-" + &index.to_kebab_case()],
-                    |_, index| vec![index.to_kebab_case()],
-                    shell,
-                )?,
-            )
-            .collect::<Vec<_>>();
-
-            if open {
-                crate::open::open(
-                    &urls,
-                    cargo_compete_config.open,
-                    &file_paths,
-                    &manifest_dir,
-                    &cargo_compete_dir,
-                    shell,
-                )?;
-            }
+            (group, outcome)
         }
         CargoCompeteConfigNew::CargoCompete {
             platform: PlatformKind::Yukicoder,
             ..
         } => {
             let contest = contest.as_deref();
-            let problems = problems.map(|ps| ps.into_iter().collect());
+            let problems = problems.map(|ps| ps.into_iter().collect::<BTreeSet<_>>());
 
-            let outcome = crate::web::retrieve_testcases::dl_from_yukicoder(
-                if let Some(contest) = contest {
-                    YukicoderRetrieveTestCasesTargets::Contest(contest.to_owned(), problems)
-                } else if let Some(problems) = problems {
-                    YukicoderRetrieveTestCasesTargets::ProblemNos(problems)
-                } else {
-                    bail!("either of `<contest>` or `--problems` required for yukicoder");
-                },
-                full,
-                shell,
-            )?;
+            if contest.is_none() && problems.is_none() {
+                bail!("either of `<contest>` or `--problems` required for yukicoder");
+            }
+
+            let outcome = retrieve(wait, shell, |shell| {
+                crate::web::retrieve_testcases::dl_from_yukicoder(
+                    if let Some(contest) = contest {
+                        YukicoderRetrieveTestCasesTargets::Contest(
+                            contest.to_owned(),
+                            problems.clone(),
+                        )
+                    } else {
+                        YukicoderRetrieveTestCasesTargets::ProblemNos(problems.clone().unwrap())
+                    },
+                    full,
+                    &cookies_path,
+                    profile,
+                    cache,
+                    shell,
+                )
+            })?;
 
             let contest = outcome
-                .get(0)
+                .first()
                 .and_then(|p| p.contest_url.as_ref())
                 .map(crate::web::url::yukicoder_contest)
                 .transpose()?;
@@ -217,43 +288,7 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
                 Some(contest) => Group::YukicoderContest(contest),
             };
 
-            let problems = outcome.iter().map(|p| (&*p.index, &p.url)).collect();
-
-            let urls = urls(&outcome);
-
-            let (manifest_dir, src_paths) = create_new_package(
-                &cargo_compete_config_path,
-                &cargo_compete_config,
-                &group,
-                &problems,
-                shell,
-            )?;
-
-            let file_paths = itertools::zip_eq(
-                src_paths,
-                crate::web::retrieve_testcases::save_test_cases(
-                    &cargo_compete_dir,
-                    &manifest_dir,
-                    &cargo_compete_config.test_suite,
-                    true,
-                    outcome,
-                    |_, index| vec![group.package_name() + "-" + &index.to_kebab_case()],
-                    |_, index| vec![index.to_kebab_case()],
-                    shell,
-                )?,
-            )
-            .collect::<Vec<_>>();
-
-            if open {
-                crate::open::open(
-                    &urls,
-                    cargo_compete_config.open,
-                    &file_paths,
-                    &manifest_dir,
-                    &cargo_compete_dir,
-                    shell,
-                )?;
-            }
+            (group, outcome)
         }
         CargoCompeteConfigNew::OjApi {
             url: contest_url, ..
@@ -269,71 +304,425 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
                 }))?
                 .parse()?;
 
-            let outcome = oj_api::get_contest(contest_url, &cargo_compete_dir, shell)?
-                .into_iter()
-                .map(|(problem_url, alphabet)| {
-                    let problem =
-                        oj_api::get_problem(&problem_url, full, &cargo_compete_dir, shell)?;
-                    let mut problem =
-                        crate::web::retrieve_testcases::Problem::from_oj_api_with_alphabet(
-                            problem, full,
-                        )?;
-                    if let Some(index) = alphabet {
-                        problem.index = index;
-                    }
-                    Ok((problem_url, problem))
-                })
-                .collect::<anyhow::Result<BTreeMap<_, _>>>()?;
-
-            let group = &Group::OjApi(contest_id);
-
-            let (manifest_dir, src_paths) = create_new_package(
-                &cargo_compete_config_path,
-                &cargo_compete_config,
-                group,
-                &outcome.iter().map(|(u, p)| (&*p.index, u)).collect(),
-                shell,
-            )?;
-
-            let (urls, problems) = {
-                let (mut urls, mut problems) = (vec![], vec![]);
-                for (url, problem) in outcome {
-                    urls.push(url);
-                    problems.push(problem);
-                }
-                (urls, problems)
-            };
-
-            let file_paths = itertools::zip_eq(
-                src_paths,
-                crate::web::retrieve_testcases::save_test_cases(
-                    &cargo_compete_dir,
-                    &manifest_dir,
-                    &cargo_compete_config.test_suite,
-                    true,
-                    problems,
-                    |_, index| vec![group.package_name() + "-" + &index.to_kebab_case()],
-                    |_, index| vec![index.to_kebab_case()],
-                    shell,
-                )?,
-            )
-            .collect::<Vec<_>>();
-
-            if open {
-                crate::open::open(
-                    &urls,
-                    cargo_compete_config.open,
-                    &file_paths,
-                    &manifest_dir,
+            let outcome = retrieve(wait, shell, |shell| {
+                oj_api::get_contest(contest_url, &cargo_compete_dir, Some(cache), shell)
+            })?
+            .into_iter()
+            .map(|(problem_url, alphabet)| {
+                let mut problem = crate::web::retrieve_testcases::dl_from_other(
+                    &problem_url,
+                    full,
                     &cargo_compete_dir,
+                    &cache_dir,
+                    Some(cache),
                     shell,
                 )?;
+                if let Some(index) = alphabet {
+                    problem.index = Some(index);
+                }
+                let mut problem = crate::web::retrieve_testcases::Problem::with_index(problem)?;
+                problem.url = problem_url.clone();
+                Ok((problem_url, problem))
+            })
+            .collect::<anyhow::Result<BTreeMap<_, _>>>()?;
+
+            (Group::OjApi(contest_id), outcome.into_values().collect())
+        }
+    };
+
+    create_package_for_problems(
+        &group,
+        problems,
+        open,
+        template.as_deref(),
+        append,
+        &cargo_compete_config_path,
+        &cargo_compete_config,
+        &cookies_path,
+        cache,
+        shell,
+    )
+}
+
+/// The problems come from different AtCoder contests, so they are retrieved by the URLs and labeled
+/// in the order of the virtual contest.
+#[allow(clippy::too_many_arguments)]
+fn new_for_virtual_contest(
+    virtual_contest_url: &Url,
+    full: bool,
+    open: bool,
+    problems: Option<Vec<String>>,
+    template: Option<&str>,
+    append: bool,
+    cargo_compete_config_path: &Utf8Path,
+    cargo_compete_config: &CargoCompeteConfig,
+    cookies_path: &Path,
+    profile: Option<&str>,
+    cache: &ProblemCache,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let virtual_contest =
+        crate::web::atcoder_problems::get_virtual_contest(virtual_contest_url, cache, shell)?;
+
+    let virtual_problems = virtual_contest
+        .problems
+        .iter()
+        .filter(|p| problems.as_ref().is_none_or(|ps| ps.contains(&p.index)))
+        .collect::<Vec<_>>();
+    if virtual_problems.is_empty() {
+        bail!("no problems to retrieve in `{}`", virtual_contest.title);
+    }
+
+    let mut outcome = crate::web::retrieve_testcases::dl_from_atcoder(
+        ProblemsInContest::Urls {
+            urls: virtual_problems.iter().map(|p| p.url.clone()).collect(),
+        },
+        full,
+        cookies_path,
+        profile,
+        cache,
+        shell,
+    )?;
+
+    for problem in &mut outcome {
+        let task = problem.url.path_segments().and_then(Iterator::last);
+        problem.index = virtual_problems
+            .iter()
+            .find(|p| Some(&*p.id) == task)
+            .with_context(|| format!("unexpected problem: {}", problem.url))?
+            .index
+            .clone();
+    }
+    outcome.sort_by_key(|p| virtual_problems.iter().position(|v| v.index == p.index));
+
+    let group = Group::AtcoderProblemsVirtual {
+        id: virtual_contest.id,
+        url: virtual_contest_url.clone(),
+    };
+
+    create_package_for_problems(
+        &group,
+        outcome,
+        open,
+        template,
+        append,
+        cargo_compete_config_path,
+        cargo_compete_config,
+        cookies_path,
+        cache,
+        shell,
+    )
+}
+
+/// Creates a package for the problems in the list, which may be on different platforms.
+///
+/// The test cases are retrieved in the same way as `download`, with the `bin`s built from the list.
+#[allow(clippy::too_many_arguments)]
+fn new_from_list(
+    list_path: &Path,
+    name: String,
+    full: bool,
+    open: bool,
+    template: Option<&str>,
+    append: bool,
+    cargo_compete_config_path: &Utf8Path,
+    cargo_compete_config: &CargoCompeteConfig,
+    cookies_path: &Path,
+    profile: Option<&str>,
+    cache_dir: &Path,
+    cache: &ProblemCache,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let cargo_compete_dir = cargo_compete_config_path.with_file_name("");
+
+    let list = parse_problem_list(&crate::fs::read_to_string(list_path)?)
+        .with_context(|| format!("could not parse `{}`", list_path.display()))?;
+
+    let group = Group::ProblemSet(name);
+
+    let package_metadata_bin = list
+        .iter()
+        .map(|(alias, url)| {
+            let bin = PackageMetadataCargoCompeteBinExample {
+                alias: alias.to_kebab_case(),
+                problem: url.clone(),
+                language_id: None,
+            };
+            (bin_name(&group, alias), bin)
+        })
+        .collect::<IndexMap<_, _>>();
+    let package_metadata_example = IndexMap::new();
+
+    let mut retrieved = crate::web::retrieve_testcases::retrieve_for_targets(
+        &package_metadata_bin,
+        &package_metadata_example,
+        None,
+        None,
+        full,
+        &cargo_compete_dir,
+        &cargo_compete_config.statement,
+        cookies_path,
+        profile,
+        cache_dir,
+        cache,
+        shell,
+    )?
+    .problems;
+
+    // Labeled with the aliases, in the order of the list.
+    let problems = list
+        .iter()
+        .map(|(alias, url)| {
+            let i = retrieved
+                .iter()
+                .position(|p| p.url == *url)
+                .with_context(|| format!("could not retrieve {url}"))?;
+            let mut problem = retrieved.swap_remove(i);
+            problem.index = Some(alias.clone());
+            crate::web::retrieve_testcases::Problem::with_index(problem)
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    create_package_for_problems(
+        &group,
+        problems,
+        open,
+        template,
+        append,
+        cargo_compete_config_path,
+        cargo_compete_config,
+        cookies_path,
+        cache,
+        shell,
+    )
+}
+
+/// Creates the package for the retrieved problems, and saves their statements and test cases in
+/// it. Then opens them and runs `post-new`.
+///
+/// With `--append`, only the problems missing in the package are saved.
+#[allow(clippy::too_many_arguments)]
+fn create_package_for_problems(
+    group: &Group,
+    mut problems: Vec<crate::web::retrieve_testcases::Problem<String>>,
+    open: bool,
+    template: Option<&str>,
+    append: bool,
+    cargo_compete_config_path: &Utf8Path,
+    cargo_compete_config: &CargoCompeteConfig,
+    cookies_path: &Path,
+    cache: &ProblemCache,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let cargo_compete_dir = cargo_compete_config_path.with_file_name("");
+
+    let (manifest_dir, src_paths) = create_new_package(
+        cargo_compete_config_path,
+        cargo_compete_config,
+        group,
+        &problems
+            .iter()
+            .map(|p| (&*p.index, (&p.url, &p.test_suite)))
+            .collect(),
+        template,
+        append,
+        shell,
+    )?;
+
+    problems.retain(|p| src_paths.contains_key(&p.index));
+
+    crate::web::statement::retrieve_all(
+        &mut problems,
+        &cargo_compete_config.statement,
+        cookies_path,
+        cache,
+        shell,
+    )?;
+
+    let urls = urls(&problems);
+
+    let indexes = src_paths.keys().cloned().collect::<Vec<_>>();
+
+    let file_paths = itertools::zip_eq(
+        src_paths.into_values(),
+        crate::web::retrieve_testcases::save_test_cases(
+            &cargo_compete_dir,
+            &manifest_dir,
+            &cargo_compete_config.test_suite,
+            true,
+            problems,
+            |_, index| vec![bin_name(group, index)],
+            |_, index| vec![index.to_kebab_case()],
+            shell,
+        )?,
+    )
+    .collect::<Vec<_>>();
+
+    if open {
+        crate::open::open(
+            &urls,
+            cargo_compete_config.open.as_deref(),
+            &file_paths,
+            &manifest_dir,
+            &cargo_compete_dir,
+            shell,
+        )?;
+    }
+
+    run_post_new_hook(
+        cargo_compete_config,
+        group,
+        &manifest_dir,
+        &indexes,
+        &urls,
+        &file_paths,
+        &cargo_compete_dir,
+        shell,
+    )
+}
+
+/// Parses a list for `--from-list`. Each line is `<URL>` or `<ALIAS> <URL>`, and empty lines and
+/// lines starting with `#` are ignored.
+///
+/// Without an alias, one is made from the URL.
+fn parse_problem_list(list: &str) -> anyhow::Result<IndexMap<String, Url>> {
+    let mut problems = IndexMap::new();
+
+    for (i, line) in list.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (alias, url) = match *line.split_whitespace().collect::<Vec<_>>() {
+            [url] => {
+                let url = url
+                    .parse::<Url>()
+                    .with_context(|| format!("line {}: invalid URL: {:?}", i + 1, url))?;
+                (alias_from_url(&url), url)
             }
+            [alias, url] => {
+                let url = url
+                    .parse::<Url>()
+                    .with_context(|| format!("line {}: invalid URL: {:?}", i + 1, url))?;
+                (alias.to_kebab_case(), url)
+            }
+            _ => bail!("line {}: expected `<URL>` or `<ALIAS> <URL>`", i + 1),
+        };
+
+        if problems.values().any(|u| *u == url) {
+            bail!("line {}: {} is listed twice", i + 1, url);
+        }
+        if problems.contains_key(&alias) {
+            bail!(
+                "line {}: duplicate alias `{}`. give another one as `<ALIAS> <URL>`",
+                i + 1,
+                alias,
+            );
         }
+        problems.insert(alias, url);
+    }
+
+    if problems.is_empty() {
+        bail!("no problems in the list");
+    }
+    Ok(problems)
+}
+
+/// The last segment of the path, prefixed with the contest if it does not contain it, such as
+/// `abc100-a` and `1500-a`.
+fn alias_from_url(url: &Url) -> String {
+    let last = url
+        .path_segments()
+        .and_then(|mut ss| ss.rfind(|s| !s.is_empty()))
+        .unwrap_or("main")
+        .to_kebab_case();
+
+    match crate::web::url::contest(url).map(|c| c.to_kebab_case()) {
+        Some(contest) if !last.starts_with(&contest) => format!("{contest}-{last}"),
+        _ => last,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_post_new_hook(
+    cargo_compete_config: &CargoCompeteConfig,
+    group: &Group,
+    manifest_dir: &Utf8Path,
+    indexes: &[String],
+    urls: &[Url],
+    file_paths: &[(Utf8PathBuf, Utf8PathBuf)],
+    cargo_compete_dir: &Utf8Path,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    if indexes.is_empty() {
+        return Ok(());
+    }
+
+    let problems = itertools::izip!(indexes, urls, file_paths)
+        .map(|(index, url, (src_path, test_suite_path))| {
+            liquid::model::Value::Object(object!({
+                "bin_name": bin_name(group, index),
+                "bin_alias": index.to_kebab_case(),
+                "problem_url": url.as_str(),
+                "src_path": src_path.as_str(),
+                "test_suite_path": test_suite_path.as_str(),
+            }))
+        })
+        .collect::<Vec<_>>();
+
+    crate::hooks::run(
+        &cargo_compete_config.hooks,
+        Hook::PostNew,
+        &object!({
+            "manifest_dir": manifest_dir.as_str(),
+            "contest": group.contest(),
+            "package_name": group.package_name(),
+            "problems": problems,
+        }),
+        cargo_compete_dir,
+        shell,
+    )
+}
+
+/// Sleeps until the contest starts if its start time is available.
+fn wait_for_contest_start(
+    new: &CargoCompeteConfigNew,
+    contest: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let contest_url = match (new, contest) {
+        (CargoCompeteConfigNew::CargoCompete { platform, .. }, Some(contest)) => match platform {
+            PlatformKind::Atcoder => format!("https://atcoder.jp/contests/{contest}"),
+            PlatformKind::Codeforces => format!("https://codeforces.com/contest/{contest}"),
+            PlatformKind::Yukicoder => format!("https://yukicoder.me/contests/{contest}"),
+        },
+        _ => return Ok(()),
+    };
+
+    let (start, offset) = crate::web::schedule::contest_start_time(&contest_url.parse()?)?;
+    if start > Utc::now() + offset {
+        crate::web::schedule::wait_until(start, offset, shell)?;
     }
     Ok(())
 }
 
+/// With `--wait`, retries until the problems are available.
+fn retrieve<T>(
+    wait: bool,
+    shell: &mut Shell,
+    mut f: impl FnMut(&mut Shell) -> anyhow::Result<Vec<T>>,
+) -> anyhow::Result<Vec<T>> {
+    if !wait {
+        return f(shell);
+    }
+
+    crate::web::schedule::poll(shell, |shell| {
+        let problems = f(shell)?;
+        Ok(Some(problems).filter(|problems| !problems.is_empty()))
+    })
+}
+
 fn urls(outcome: &[crate::web::retrieve_testcases::Problem<impl Sized>]) -> Vec<Url> {
     outcome.iter().map(|p| p.url.clone()).collect()
 }
@@ -345,6 +734,12 @@ enum Group {
     YukicoderProblems,
     YukicoderContest(String),
     OjApi(String),
+    AtcoderProblemsVirtual {
+        id: String,
+        url: Url,
+    },
+    /// `--from-list`.
+    ProblemSet(String),
 }
 
 impl Group {
@@ -353,7 +748,9 @@ impl Group {
             Self::Atcoder(contest)
             | Self::Codeforces(contest)
             | Self::YukicoderContest(contest)
-            | Self::OjApi(contest) => Some(contest),
+            | Self::OjApi(contest)
+            | Self::AtcoderProblemsVirtual { id: contest, .. }
+            | Self::ProblemSet(contest) => Some(contest),
             Self::YukicoderProblems => None,
         }
     }
@@ -367,13 +764,17 @@ impl Group {
     }
 }
 
+/// Returns the directory of the package, and the source files of the problems that were added to it,
+/// keyed by the problem indexes.
 fn create_new_package(
     cargo_compete_config_path: &Utf8Path,
     cargo_compete_config: &CargoCompeteConfig,
     group: &Group,
-    problems: &BTreeMap<&str, &Url>,
+    problems: &IndexMap<&str, (&Url, &TestSuite)>,
+    template_name: Option<&str>,
+    append: bool,
     shell: &mut Shell,
-) -> anyhow::Result<(Utf8PathBuf, Vec<Utf8PathBuf>)> {
+) -> anyhow::Result<(Utf8PathBuf, IndexMap<String, Utf8PathBuf>)> {
     let template = cargo_compete_config.template(cargo_compete_config_path, shell)?;
     let template_new = template.new.as_ref().with_context(|| {
         format!("`template.new` is required for the command: {cargo_compete_config_path}",)
@@ -393,49 +794,34 @@ fn create_new_package(
         .join(manifest_dir.strip_prefix(".").unwrap_or(manifest_dir));
 
     let manifest_path = manifest_dir.join("Cargo.toml");
+    let workspace = cargo_compete_config.new.workspace();
+    let workspace_root = cargo_compete_config_path.with_file_name("");
 
     if manifest_dir.exists() {
-        bail!(
-            "could not create a new package. `{}` already exists",
-            manifest_dir,
-        );
+        if !append {
+            bail!(
+                "could not create a new package. `{}` already exists. run with `--append` to add \
+                 the missing problems to it",
+                manifest_dir,
+            );
+        }
+        let src_paths = append_to_package(
+            &manifest_dir,
+            &template.src,
+            template_name,
+            group,
+            problems,
+            shell,
+        )?;
+        return Ok((manifest_dir, src_paths));
     }
 
-    let mut package_metadata_cargo_compete_bin = problems
-        .keys()
-        .map(|problem_index| {
-            format!(
-                r#"{} = {{ alias = "", problem = "" }}
-"#,
-                escape_key(&format!(
-                    "{}-{}",
-                    group.package_name(),
-                    problem_index.to_kebab_case(),
-                )),
-            )
-        })
-        .join("")
-        .parse::<toml_edit::Document>()?;
-
-    for (problem_index, problem_url) in problems {
-        let bin_name = &format!("{}-{}", group.package_name(), problem_index.to_kebab_case());
-        let bin_alias = problem_index.to_kebab_case();
-        let problem_url = problem_url.as_str();
-        package_metadata_cargo_compete_bin[bin_name]["alias"] = toml_edit::value(bin_alias);
-        package_metadata_cargo_compete_bin[bin_name]["problem"] = toml_edit::value(problem_url);
-    }
+    let package_metadata_cargo_compete_bin = package_metadata_cargo_compete_bin(group, problems)?;
 
     let bin = toml_edit::Item::ArrayOfTables({
         let mut arr = toml_edit::ArrayOfTables::new();
         for problem_index in problems.keys() {
-            let mut tbl = toml_edit::Table::new();
-            tbl["name"] = toml_edit::value(format!(
-                "{}-{}",
-                group.package_name(),
-                problem_index.to_kebab_case(),
-            ));
-            tbl["path"] = toml_edit::value(format!("src/bin/{}.rs", problem_index.to_kebab_case()));
-            arr.push(tbl);
+            arr.push(bin_table(group, problem_index));
         }
         arr
     });
@@ -452,7 +838,8 @@ edition = ""
 [dev-dependencies]
 "#;
 
-    let mut manifest = if template_new.profile.as_table().is_empty() {
+    // `profile` in a workspace member is ignored. It goes to the root manifest instead.
+    let mut manifest = if workspace || template_new.profile.as_table().is_empty() {
         MANIFEST_TEMPLATE.to_owned()
     } else {
         let mut profile = (*template_new.profile).clone();
@@ -477,6 +864,10 @@ edition = ""
 
     set_implicit_table_if_none(&mut manifest["package"]["metadata"]);
     set_implicit_table_if_none(&mut manifest["package"]["metadata"]["cargo-compete"]);
+    if let Group::AtcoderProblemsVirtual { url, .. } = group {
+        manifest["package"]["metadata"]["cargo-compete"]["virtual-contest"] =
+            toml_edit::value(url.as_str());
+    }
     set_implicit_table_if_none(&mut manifest["package"]["metadata"]["cargo-compete"]["bin"]);
 
     for (key, val) in package_metadata_cargo_compete_bin.as_table().iter() {
@@ -493,60 +884,292 @@ edition = ""
         }
     }
 
-    if let Ok(new_manifest) = manifest
-        .to_string()
-        .replace("\"}", "\" }")
-        .parse::<toml_edit::Document>()
+    crate::fs::create_dir_all(manifest_dir.join("src").join("bin"))?;
+    crate::fs::write(&manifest_path, manifest.to_string())?;
+
+    let src_paths = write_src_files(
+        &manifest_dir,
+        &template.src,
+        template_name,
+        group,
+        problems,
+        shell,
+    )?;
+
+    for (from, to) in &template_new.copy_files {
+        let from = workspace_root.join(from);
+        if workspace && to == "Cargo.lock" {
+            // The lockfile is shared in the workspace.
+            let to = workspace_root.join(to);
+            if !to.exists() {
+                crate::fs::copy(from, to)?;
+            }
+        } else {
+            crate::fs::copy(from, manifest_dir.join(to))?;
+        }
+    }
+
+    shell.status(
+        "Created",
+        format!("`{}` package at {}", group.package_name(), manifest_dir),
+    )?;
+
+    if workspace {
+        crate::project::add_workspace_members(
+            &workspace_root,
+            std::slice::from_ref(&manifest_dir),
+            &[],
+            Some(template_new.profile.as_table()),
+            matches!(template_new.edition, Some(Edition::Edition2021)).then_some("2"),
+            shell,
+        )?;
+    }
+
+    if cargo_compete_config.test.prewarm {
+        prewarm(&manifest_path, cargo_compete_config, shell)?;
+    }
+
+    Ok((manifest_dir, src_paths))
+}
+
+/// Starts `cargo build` for a new package in the background, so that its dependencies are compiled
+/// before the first test.
+///
+/// The child is left running after `new` exits. `test` waits for it through the lock of the target
+/// directory.
+fn prewarm(
+    manifest_path: &Utf8Path,
+    cargo_compete_config: &CargoCompeteConfig,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let manifest_dir = manifest_path.with_file_name("");
+
+    // The packages share the artifacts via `build.target-dir`, so the log goes there too.
+    let metadata = match crate::project::cargo_metadata_no_deps(manifest_path, &manifest_dir) {
+        Ok(metadata) => metadata,
+        Err(err) => {
+            shell.warn(format!("could not pre-warm the build: {err}"))?;
+            return Ok(());
+        }
+    };
+    // In a workspace, `packages` has all of the members. `manifest_path` may contain `.`.
+    let canonical_manifest_path = manifest_path.canonicalize()?;
+    let package =
+        match metadata.packages.iter().find(|p| {
+            p.manifest_path.canonicalize().ok().as_ref() == Some(&canonical_manifest_path)
+        }) {
+            Some(package) => package,
+            None => {
+                shell.warn(format!(
+                    "could not pre-warm the build: `{manifest_path}` is not in the metadata",
+                ))?;
+                return Ok(());
+            }
+        };
+
+    if !package
+        .dependencies
+        .iter()
+        .any(|d| d.kind == cm::DependencyKind::Normal)
     {
-        manifest = new_manifest;
+        return Ok(());
+    }
+
+    let log_dir = metadata.target_directory.join("cargo-compete");
+    crate::fs::create_dir_all(&log_dir)?;
+    let log_path = log_dir.join(format!("prewarm-{}.log", package.name));
+
+    let cargo = prewarm_command(
+        &package.name,
+        manifest_path,
+        &metadata.workspace_root,
+        &cargo_compete_config.test,
+    )?;
+    cargo.spawn_with_log(&log_path)?;
+    shell.status(
+        "Prewarming",
+        format!("{cargo} in the background. see `{log_path}`"),
+    )?;
+    Ok(())
+}
+
+/// Builds the `bin`s of the package itself with `test.toolchain` and `test.profile`, so the
+/// dependencies are compiled with the same features as `test` resolves.
+fn prewarm_command(
+    package_name: &str,
+    manifest_path: &Utf8Path,
+    workspace_root: &Utf8Path,
+    test: &CargoCompeteConfigTest,
+) -> anyhow::Result<ProcessBuilder<Present>> {
+    let release = test.profile == CargoCompeteConfigTestProfile::Release;
+
+    let cargo = if let Some(toolchain) = &test.toolchain {
+        crate::process::process("rustup").args(&["run", toolchain, "cargo"])
+    } else {
+        crate::process::process(crate::process::cargo_exe()?)
     }
+    .args(&["build", "-p", package_name, "--bins"])
+    .args(if release { &["--release"] } else { &[] })
+    .arg("--manifest-path")
+    .arg(manifest_path)
+    .cwd(workspace_root);
+    Ok(cargo)
+}
 
-    let src_bin_dir = manifest_dir.join("src").join("bin");
+/// Adds the problems that are not in the existing package, keeping the rest of `Cargo.toml` as it is.
+fn append_to_package(
+    manifest_dir: &Utf8Path,
+    src: &CargoCompeteConfigTemplateSrc,
+    template_name: Option<&str>,
+    group: &Group,
+    problems: &IndexMap<&str, (&Url, &TestSuite)>,
+    shell: &mut Shell,
+) -> anyhow::Result<IndexMap<String, Utf8PathBuf>> {
+    let manifest_path = manifest_dir.join("Cargo.toml");
+    let mut manifest = crate::fs::read_to_string(&manifest_path)?
+        .parse::<toml_edit::Document>()
+        .with_context(|| format!("could not parse the manifest at `{manifest_path}`"))?;
 
-    crate::fs::create_dir_all(&src_bin_dir)?;
-    crate::fs::write(manifest_path, manifest.to_string())?;
+    set_implicit_table_if_none(&mut manifest["package"]["metadata"]);
+    set_implicit_table_if_none(&mut manifest["package"]["metadata"]["cargo-compete"]);
+    set_implicit_table_if_none(&mut manifest["package"]["metadata"]["cargo-compete"]["bin"]);
 
-    let src_paths = problems
-        .keys()
-        .map(|problem_index| {
-            src_bin_dir
-                .join(problem_index.to_kebab_case())
-                .with_extension("rs")
+    let existing = manifest["package"]["metadata"]["cargo-compete"]["bin"]
+        .as_table_like()
+        .with_context(|| {
+            format!("`package.metadata.cargo-compete.bin` is not a table: {manifest_path}")
+        })?
+        .iter()
+        .map(|(key, val)| {
+            let problem = val.get("problem");
+            let url = problem
+                .and_then(|p| p.as_str())
+                .or_else(|| problem?.get("url")?.as_str())
+                .and_then(|url| url.parse::<Url>().ok());
+            (key.to_owned(), url)
         })
         .collect::<Vec<_>>();
 
-    for src_path in &src_paths {
-        crate::fs::write(src_path, &template.src)?;
+    let problems = problems
+        .iter()
+        .filter(|&(&problem_index, &(problem_url, _))| {
+            let bin_name = bin_name(group, problem_index);
+            !existing
+                .iter()
+                .any(|(key, url)| *key == bin_name || url.as_ref() == Some(problem_url))
+        })
+        .map(|(&problem_index, &problem)| (problem_index, problem))
+        .collect::<IndexMap<_, _>>();
+
+    if problems.is_empty() {
+        shell.status(
+            "Finished",
+            format!("`{manifest_dir}` already has all of the problems"),
+        )?;
+        return Ok(IndexMap::new());
     }
 
-    for (from, to) in &template_new.copy_files {
-        let from = cargo_compete_config_path.with_file_name("").join(from);
-        let to = manifest_dir.join(to);
-        crate::fs::copy(from, to)?;
+    for (key, val) in package_metadata_cargo_compete_bin(group, &problems)?
+        .as_table()
+        .iter()
+    {
+        manifest["package"]["metadata"]["cargo-compete"]["bin"][key] = val.clone();
+    }
+
+    if manifest["bin"].is_none() {
+        manifest["bin"] = toml_edit::Item::ArrayOfTables(toml_edit::ArrayOfTables::new());
     }
+    let bin = manifest["bin"]
+        .as_array_of_tables_mut()
+        .with_context(|| format!("`bin` is not an array of tables: {manifest_path}"))?;
+    for problem_index in problems.keys() {
+        bin.push(bin_table(group, problem_index));
+    }
+
+    crate::fs::create_dir_all(manifest_dir.join("src").join("bin"))?;
+    crate::fs::write(&manifest_path, manifest.to_string())?;
+
+    let src_paths = write_src_files(manifest_dir, src, template_name, group, &problems, shell)?;
 
     shell.status(
-        "Created",
-        format!("`{}` package at {}", group.package_name(), manifest_dir),
+        "Added",
+        format!(
+            "{} to `{}` package at {}",
+            problems.keys().join(", "),
+            group.package_name(),
+            manifest_dir,
+        ),
     )?;
 
-    Ok((manifest_dir, src_paths))
+    Ok(src_paths)
+}
+
+/// Writes the source files from the template, without overwriting existing ones.
+fn write_src_files(
+    manifest_dir: &Utf8Path,
+    src: &CargoCompeteConfigTemplateSrc,
+    template_name: Option<&str>,
+    group: &Group,
+    problems: &IndexMap<&str, (&Url, &TestSuite)>,
+    shell: &mut Shell,
+) -> anyhow::Result<IndexMap<String, Utf8PathBuf>> {
+    let mut src_paths = IndexMap::new();
+
+    for (&problem_index, &(problem_url, test_suite)) in problems {
+        let src_path = src_path(manifest_dir, problem_index);
+        if src_path.exists() {
+            shell.warn(format!("`{src_path}` already exists. not overwriting it"))?;
+        } else {
+            let content = src.render(
+                template_name,
+                &bin_name(group, problem_index),
+                &problem_index.to_kebab_case(),
+                problem_url,
+                test_suite,
+            )?;
+            crate::fs::write(&src_path, content)?;
+        }
+        src_paths.insert(problem_index.to_owned(), src_path);
+    }
+    Ok(src_paths)
+}
+
+fn bin_name(group: &Group, problem_index: &str) -> String {
+    format!("{}-{}", group.package_name(), problem_index.to_kebab_case())
+}
+
+fn src_path(manifest_dir: &Utf8Path, problem_index: &str) -> Utf8PathBuf {
+    manifest_dir
+        .join("src")
+        .join("bin")
+        .join(problem_index.to_kebab_case())
+        .with_extension("rs")
 }
 
-fn escape_key(s: &str) -> String {
-    if s.chars().any(|c| c.is_whitespace() || c.is_control()) {
-        return toml::Value::String(s.to_owned()).to_string();
+/// Rows of `package.metadata.cargo-compete.bin`.
+fn package_metadata_cargo_compete_bin(
+    group: &Group,
+    problems: &IndexMap<&str, (&Url, &TestSuite)>,
+) -> anyhow::Result<toml_edit::Document> {
+    let mut package_metadata_cargo_compete_bin = toml_edit::Document::new();
+
+    for (problem_index, (problem_url, _)) in problems {
+        let mut row = toml_edit::InlineTable::new();
+        row.get_or_insert("alias", problem_index.to_kebab_case());
+        row.get_or_insert("problem", problem_url.as_str());
+        // `{ alias = "..", problem = ".." }`
+        row.fmt();
+        package_metadata_cargo_compete_bin[&bin_name(group, problem_index)] = toml_edit::value(row);
     }
+    Ok(package_metadata_cargo_compete_bin)
+}
 
-    let mut doc = toml_edit::Document::new();
-    doc[s] = toml_edit::value(0);
-    doc.to_string()
-        .trim_end()
-        .trim_end_matches('0')
-        .trim_end()
-        .trim_end_matches('=')
-        .trim_end()
-        .to_owned()
+/// An element of `[[bin]]`.
+fn bin_table(group: &Group, problem_index: &str) -> toml_edit::Table {
+    let mut tbl = toml_edit::Table::new();
+    tbl["name"] = toml_edit::value(bin_name(group, problem_index));
+    tbl["path"] = toml_edit::value(format!("src/bin/{}.rs", problem_index.to_kebab_case()));
+    tbl
 }
 
 fn set_implicit_table_if_none(item: &mut toml_edit::Item) {
@@ -558,3 +1181,76 @@ fn set_implicit_table_if_none(item: &mut toml_edit::Item) {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn alias_from_url() {
+        for (expected, url) in &[
+            (
+                "abc100-a",
+                "https://atcoder.jp/contests/abc100/tasks/abc100_a",
+            ),
+            ("1500-a", "https://codeforces.com/contest/1500/problem/A"),
+            ("1000", "https://yukicoder.me/problems/no/1000"),
+            (
+                "itp1-1-a",
+                "https://onlinejudge.u-aizu.ac.jp/problems/ITP1_1_A",
+            ),
+        ] {
+            assert_eq!(*expected, super::alias_from_url(&url.parse().unwrap()));
+        }
+    }
+
+    #[test]
+    fn parse_problem_list() {
+        let list = super::parse_problem_list(
+            "# comment\n\
+             https://atcoder.jp/contests/abc100/tasks/abc100_a\n\
+             \n\
+             aplusb https://judge.yosupo.jp/problem/aplusb\n",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                (
+                    "abc100-a",
+                    "https://atcoder.jp/contests/abc100/tasks/abc100_a"
+                ),
+                ("aplusb", "https://judge.yosupo.jp/problem/aplusb"),
+            ],
+            list.iter()
+                .map(|(alias, url)| (&**alias, url.as_str()))
+                .collect::<Vec<_>>(),
+        );
+
+        assert!(super::parse_problem_list(
+            "a https://atcoder.jp/contests/abc100/tasks/abc100_a\n\
+             a https://atcoder.jp/contests/abc100/tasks/abc100_b\n",
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn prewarm_command() {
+        let test = crate::config::CargoCompeteConfigTest {
+            toolchain: Some("1.42.0".to_owned()),
+            profile: crate::config::CargoCompeteConfigTestProfile::Release,
+            prewarm: true,
+        };
+        let cargo = super::prewarm_command(
+            "abc100",
+            "/ws/abc100/Cargo.toml".as_ref(),
+            "/ws".as_ref(),
+            &test,
+        )
+        .unwrap();
+        assert_eq!(
+            "`rustup run 1.42.0 cargo build -p abc100 --bins --release --manifest-path \
+             /ws/abc100/Cargo.toml`",
+            cargo.to_string(),
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     project::{MetadataExt as _, PackageExt as _, PackageMetadataCargoCompeteBinExample},
     shell::ColorChoice,
+    web::cache::ProblemCache,
 };
 use maplit::hashset;
 use std::{collections::HashSet, path::PathBuf};
@@ -29,6 +30,10 @@ pub struct OptCompeteOpen {
     #[structopt(long, value_name("PATH"))]
     pub manifest_path: Option<PathBuf>,
 
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
     /// Coloring
     #[structopt(
         long,
@@ -46,15 +51,21 @@ pub(crate) fn run(opt: OptCompeteOpen, ctx: crate::Context<'_>) -> anyhow::Resul
         example,
         package,
         manifest_path,
+        profile: _,
         color,
     } = opt;
 
     let crate::Context {
         cwd,
         cookies_path,
+        cache_dir,
+        replay_dir: _,
+        profile,
         shell,
     } = ctx;
 
+    let profile = profile.as_deref();
+
     shell.set_color_choice(color);
 
     let bin = bin.map(|bin| bin.into_iter().collect::<HashSet<_>>());
@@ -74,19 +85,20 @@ pub(crate) fn run(opt: OptCompeteOpen, ctx: crate::Context<'_>) -> anyhow::Resul
     let mut file_paths = vec![];
     let mut missing = [hashset!(), hashset!()];
 
-    for (i, (name, PackageMetadataCargoCompeteBinExample { alias, problem })) in itertools::chain(
-        package_metadata.bin.iter().filter(
-            |&(name, PackageMetadataCargoCompeteBinExample { alias, .. })| {
-                bin.map_or(true, |s| s.contains(name) || s.contains(alias))
-            },
-        ),
-        package_metadata.example.iter().filter(
-            |&(name, PackageMetadataCargoCompeteBinExample { alias, .. })| {
-                example.map_or(true, |s| s.contains(name) || s.contains(alias))
-            },
-        ),
-    )
-    .enumerate()
+    for (i, (name, PackageMetadataCargoCompeteBinExample { alias, problem, .. })) in
+        itertools::chain(
+            package_metadata.bin.iter().filter(
+                |&(name, PackageMetadataCargoCompeteBinExample { alias, .. })| {
+                    bin.is_none_or(|s| s.contains(name) || s.contains(alias))
+                },
+            ),
+            package_metadata.example.iter().filter(
+                |&(name, PackageMetadataCargoCompeteBinExample { alias, .. })| {
+                    example.is_none_or(|s| s.contains(name) || s.contains(alias))
+                },
+            ),
+        )
+        .enumerate()
     {
         urls.push(problem.clone());
 
@@ -125,7 +137,11 @@ pub(crate) fn run(opt: OptCompeteOpen, ctx: crate::Context<'_>) -> anyhow::Resul
             true,
             &metadata.workspace_root,
             &cargo_compete_config.test_suite,
+            &cargo_compete_config.statement,
             &cookies_path,
+            profile,
+            &cache_dir,
+            &ProblemCache::new(&cache_dir, false),
             shell,
         )?;
     }
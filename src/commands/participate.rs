@@ -1,18 +1,22 @@
 use crate::{
     shell::{ColorChoice, Shell},
-    web::credentials,
+    web::{credentials, http},
 };
 use snowchains_core::web::{
     Atcoder, AtcoderParticipateCredentials, AtcoderParticipateTarget, Codeforces,
     CodeforcesParticipateCredentials, CodeforcesParticipateTarget, CookieStorage, Participate,
     ParticipateOutcome, PlatformKind,
 };
-use std::{borrow::BorrowMut as _, cell::RefCell, io};
+use std::{borrow::BorrowMut as _, cell::RefCell, io, path::Path};
 use structopt::StructOpt;
 use strum::VariantNames as _;
 
 #[derive(StructOpt, Debug)]
 pub struct OptCompeteParticipate {
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
     /// Coloring
     #[structopt(
         long,
@@ -32,6 +36,7 @@ pub struct OptCompeteParticipate {
 
 pub(crate) fn run(opt: OptCompeteParticipate, ctx: crate::Context<'_>) -> anyhow::Result<()> {
     let OptCompeteParticipate {
+        profile: _,
         color,
         platform,
         contest,
@@ -40,13 +45,29 @@ pub(crate) fn run(opt: OptCompeteParticipate, ctx: crate::Context<'_>) -> anyhow
     let crate::Context {
         cwd: _,
         cookies_path,
+        cache_dir: _,
+        replay_dir: _,
+        profile,
         shell,
     } = ctx;
 
+    let profile = profile.as_deref();
+
     shell.set_color_choice(color);
 
+    participate(platform, contest, &cookies_path, profile, shell)
+}
+
+/// Registers to the contest on AtCoder or Codeforces.
+pub(crate) fn participate(
+    platform: PlatformKind,
+    contest: String,
+    cookies_path: &Path,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
     let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
-    let timeout = crate::web::TIMEOUT;
+    let timeout = http::timeout();
 
     match platform {
         PlatformKind::Atcoder => {
@@ -56,17 +77,21 @@ pub(crate) fn run(opt: OptCompeteParticipate, ctx: crate::Context<'_>) -> anyhow
                 let credentials = AtcoderParticipateCredentials {
                     username_and_password: &mut credentials::username_and_password(
                         &shell,
+                        profile,
+                        PlatformKind::Atcoder,
                         "Username: ",
                         "Password: ",
                     ),
                 };
 
-                Atcoder::exec(Participate {
-                    target: AtcoderParticipateTarget { contest },
-                    credentials,
-                    cookie_storage,
-                    timeout,
-                    shell: &shell,
+                http::with_env(|| {
+                    Atcoder::exec(Participate {
+                        target: AtcoderParticipateTarget { contest },
+                        credentials,
+                        cookie_storage,
+                        timeout,
+                        shell: &shell,
+                    })
                 })?
             };
 
@@ -79,17 +104,21 @@ pub(crate) fn run(opt: OptCompeteParticipate, ctx: crate::Context<'_>) -> anyhow
                 let credentials = CodeforcesParticipateCredentials {
                     username_and_password: &mut credentials::username_and_password(
                         &shell,
+                        profile,
+                        PlatformKind::Codeforces,
                         "Handle/Email: ",
                         "Password: ",
                     ),
                 };
 
-                Codeforces::exec(Participate {
-                    target: CodeforcesParticipateTarget { contest },
-                    credentials,
-                    cookie_storage,
-                    timeout,
-                    shell: &shell,
+                http::with_env(|| {
+                    Codeforces::exec(Participate {
+                        target: CodeforcesParticipateTarget { contest },
+                        credentials,
+                        cookie_storage,
+                        timeout,
+                        shell: &shell,
+                    })
                 })?
             };
 
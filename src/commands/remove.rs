@@ -0,0 +1,233 @@
+use crate::{
+    project::{MetadataExt as _, PackageExt as _},
+    shell::ColorChoice,
+};
+use anyhow::{bail, Context as _};
+use git2::{Repository, Status};
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteRemove {
+    /// Move the files to `archive/` in the package instead of deleting them
+    #[structopt(long)]
+    pub archive: bool,
+
+    /// Remove the files even if they have uncommitted changes
+    #[structopt(long)]
+    pub force: bool,
+
+    /// Package (see `cargo help pkgid`)
+    #[structopt(short, long, value_name("SPEC"))]
+    pub package: Option<String>,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Names or aliases for `bin`s/`example`s
+    #[structopt(required(true))]
+    pub names_or_aliases: Vec<String>,
+}
+
+pub(crate) fn run(opt: OptCompeteRemove, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteRemove {
+        archive,
+        force,
+        package,
+        manifest_path,
+        color,
+        names_or_aliases,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        cookies_path: _,
+        cache_dir: _,
+        replay_dir: _,
+        profile: _,
+        shell,
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let manifest_path = manifest_path
+        .map(|p| Ok(cwd.join(p.strip_prefix(".").unwrap_or(&p))))
+        .unwrap_or_else(|| crate::project::locate_project(&cwd))?;
+    let metadata = crate::project::cargo_metadata_no_deps(&manifest_path, &cwd)?;
+    let member = metadata.query_for_member(package.as_deref())?;
+    let manifest_dir = member.manifest_dir();
+    let package_metadata = member.read_package_metadata(shell)?;
+    let (cargo_compete_config, _) = crate::config::load_for_package(member, shell)?;
+
+    let mut manifest = crate::fs::read_to_string(&member.manifest_path)?
+        .parse::<toml_edit::Document>()
+        .with_context(|| format!("could not parse the manifest at `{}`", member.manifest_path))?;
+
+    let mut paths = Vec::<PathBuf>::new();
+
+    for name_or_alias in &names_or_aliases {
+        let (bin_name, pkg_md_bin_example) =
+            package_metadata.bin_like_by_name_or_alias(name_or_alias)?;
+        let kind = if package_metadata.bin.contains_key(bin_name) {
+            "bin"
+        } else {
+            "example"
+        };
+
+        paths.push(
+            member
+                .bin_like_target_by_name(bin_name)?
+                .src_path
+                .clone()
+                .into(),
+        );
+
+        let test_suite_path = crate::testing::test_suite_path(
+            &metadata.workspace_root,
+            manifest_dir,
+            &cargo_compete_config.test_suite,
+            bin_name,
+            &pkg_md_bin_example.alias,
+            &pkg_md_bin_example.problem,
+            shell,
+        )?;
+        // The directory next to the test suite has the test cases in text files and the statement.
+        paths.push(
+            test_suite_path
+                .with_file_name(&pkg_md_bin_example.alias)
+                .into(),
+        );
+        paths.push(test_suite_path.into());
+
+        if let Some(tbl) =
+            manifest["package"]["metadata"]["cargo-compete"][kind].as_table_like_mut()
+        {
+            let key = tbl
+                .iter()
+                .find(|(key, val)| {
+                    *key == bin_name || val.get("name").and_then(|v| v.as_str()) == Some(bin_name)
+                })
+                .map(|(key, _)| key.to_owned());
+            if let Some(key) = key {
+                tbl.remove(&key);
+            }
+        }
+
+        if let Some(arr) = manifest[kind].as_array_of_tables_mut() {
+            let i = arr
+                .iter()
+                .position(|tbl| tbl.get("name").and_then(|v| v.as_str()) == Some(bin_name));
+            if let Some(i) = i {
+                arr.remove(i);
+            }
+        }
+    }
+
+    let paths = paths.into_iter().filter(|p| p.exists()).collect::<Vec<_>>();
+
+    if !force {
+        if let Some(path) = uncommitted(manifest_dir.as_ref(), &paths)? {
+            bail!(
+                "`{}` has uncommitted changes. run with `--force` to remove it anyway",
+                path.display(),
+            );
+        }
+    }
+
+    let archive_paths = paths
+        .iter()
+        .map(|path| {
+            archive.then(|| {
+                manifest_dir.as_std_path().join("archive").join(
+                    path.strip_prefix(manifest_dir)
+                        .ok()
+                        .or_else(|| path.file_name().map(Path::new))
+                        .unwrap_or(path),
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // Checked before touching anything so that a failure does not leave the manifest and the files
+    // inconsistent.
+    if let Some(to) = archive_paths.iter().flatten().find(|to| to.exists()) {
+        bail!(
+            "`{}` already exists. move or remove it to archive anyway",
+            to.display(),
+        );
+    }
+
+    for (path, to) in paths.iter().zip(&archive_paths) {
+        if let Some(to) = to {
+            if let Some(parent) = to.parent() {
+                crate::fs::create_dir_all(parent)?;
+            }
+            crate::fs::rename(path, to)?;
+            shell.status(
+                "Archived",
+                format!("`{}` to `{}`", path.display(), to.display()),
+            )?;
+        } else {
+            if path.is_dir() {
+                crate::fs::remove_dir_all(path)?;
+            } else {
+                crate::fs::remove_file(path)?;
+            }
+            shell.status("Removed", format!("`{}`", path.display()))?;
+        }
+    }
+
+    // Written after the files so that the `bin`s stay in the manifest if any of them failed.
+    crate::fs::write(&member.manifest_path, manifest.to_string())?;
+    shell.status("Updated", format!("`{}`", member.manifest_path))?;
+    Ok(())
+}
+
+/// Returns the first of `paths` that has uncommitted changes. Nothing is checked if the package is
+/// not in a Git repository.
+fn uncommitted(manifest_dir: &Path, paths: &[PathBuf]) -> anyhow::Result<Option<PathBuf>> {
+    let repo = match Repository::discover(manifest_dir) {
+        Ok(repo) => repo,
+        Err(_) => return Ok(None),
+    };
+    let workdir = match repo.workdir() {
+        Some(workdir) => workdir.to_owned(),
+        None => return Ok(None),
+    };
+
+    let changed = repo
+        .statuses(None)?
+        .iter()
+        .filter(|entry| {
+            entry.status().intersects(
+                Status::INDEX_NEW
+                    | Status::INDEX_MODIFIED
+                    | Status::INDEX_RENAMED
+                    | Status::WT_NEW
+                    | Status::WT_MODIFIED
+                    | Status::WT_RENAMED,
+            )
+        })
+        .flat_map(|entry| entry.path().map(|p| workdir.join(p)))
+        .collect::<Vec<_>>();
+
+    Ok(paths
+        .iter()
+        .find(|path| {
+            changed
+                .iter()
+                .any(|p| p.starts_with(path) || path.starts_with(p))
+        })
+        .cloned())
+}
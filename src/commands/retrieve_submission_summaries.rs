@@ -1,7 +1,7 @@
 use crate::{
     project::{MetadataExt as _, PackageExt as _, PackageMetadataCargoCompeteBinExample},
     shell::ColorChoice,
-    web::credentials,
+    web::{credentials, http},
 };
 use anyhow::{bail, Context as _};
 use indexmap::indexset;
@@ -24,6 +24,10 @@ pub struct OptCompeteRetrieveSubmissionSummaries {
     #[structopt(long, value_name("PATH"))]
     pub manifest_path: Option<PathBuf>,
 
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
     /// Coloring
     #[structopt(
         long,
@@ -44,6 +48,7 @@ pub(crate) fn run(
     let OptCompeteRetrieveSubmissionSummaries {
         package,
         manifest_path,
+        profile: _,
         color,
         bin_name_or_alias,
     } = opt;
@@ -51,9 +56,14 @@ pub(crate) fn run(
     let crate::Context {
         cwd,
         cookies_path,
+        cache_dir: _,
+        replay_dir: _,
+        profile,
         shell,
     } = ctx;
 
+    let profile = profile.as_deref();
+
     shell.set_color_choice(color);
 
     let manifest_path = manifest_path
@@ -77,7 +87,7 @@ pub(crate) fn run(
     {
         if bin_name_or_alias
             .as_ref()
-            .map_or(true, |s| [bin_name, bin_alias].contains(&s))
+            .is_none_or(|s| [bin_name, bin_alias].contains(&s))
         {
             match PlatformKind::from_url(url).with_context(|| "unsupported platform")? {
                 PlatformKind::Atcoder => {
@@ -97,27 +107,34 @@ pub(crate) fn run(
         bail!("found multiple candicates. specify the target with argument");
     }
 
-    let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
-    let timeout = crate::web::TIMEOUT;
+    let timeout = http::timeout();
 
     if let Some(contest) = atcoder_targets.into_iter().next() {
         let outcome = {
             let shell = RefCell::new(shell.borrow_mut());
 
-            let credentials = AtcoderRetrieveSubmissionSummariesCredentials {
-                username_and_password: &mut credentials::username_and_password(
-                    &shell,
-                    "Username: ",
-                    "Password: ",
-                ),
-            };
-
-            Atcoder::exec(RetrieveSubmissionSummaries {
-                target: AtcoderRetrieveSubmissionSummariesTarget { contest },
-                credentials,
-                cookie_storage,
-                timeout,
-                shell: &shell,
+            let username_and_password = &mut credentials::username_and_password(
+                &shell,
+                profile,
+                PlatformKind::Atcoder,
+                "Username: ",
+                "Password: ",
+            );
+
+            http::with_retries(&shell, || {
+                http::with_env(|| {
+                    Atcoder::exec(RetrieveSubmissionSummaries {
+                        target: AtcoderRetrieveSubmissionSummariesTarget {
+                            contest: contest.clone(),
+                        },
+                        credentials: AtcoderRetrieveSubmissionSummariesCredentials {
+                            username_and_password,
+                        },
+                        cookie_storage: CookieStorage::with_jsonl(&cookies_path)?,
+                        timeout,
+                        shell: &shell,
+                    })
+                })
             })?
         };
 
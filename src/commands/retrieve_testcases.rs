@@ -1,6 +1,7 @@
 use crate::{
     project::{MetadataExt as _, PackageExt as _},
     shell::ColorChoice,
+    web::cache::ProblemCache,
 };
 use std::{collections::HashSet, path::PathBuf};
 use structopt::StructOpt;
@@ -16,6 +17,10 @@ pub struct OptCompeteRetrieveTestcases {
     #[structopt(long)]
     pub overwrite: bool,
 
+    /// Uses only the cached problems without sending requests
+    #[structopt(long)]
+    pub offline: bool,
+
     /// Retrieve only the problems for the binary target
     #[structopt(long, value_name("NAME_OR_ALIAS"))]
     pub bin: Option<Vec<String>>,
@@ -32,6 +37,10 @@ pub struct OptCompeteRetrieveTestcases {
     #[structopt(long, value_name("PATH"))]
     pub manifest_path: Option<PathBuf>,
 
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
     /// Coloring
     #[structopt(
         long,
@@ -46,19 +55,26 @@ pub(crate) fn run(opt: OptCompeteRetrieveTestcases, ctx: crate::Context<'_>) ->
     let OptCompeteRetrieveTestcases {
         full,
         overwrite,
+        offline,
         bin,
         example,
         package,
         manifest_path,
+        profile: _,
         color,
     } = opt;
 
     let crate::Context {
         cwd,
         cookies_path,
+        cache_dir,
+        replay_dir: _,
+        profile,
         shell,
     } = ctx;
 
+    let profile = profile.as_deref();
+
     shell.set_color_choice(color);
 
     let bin = bin.map(|s| s.into_iter().collect::<HashSet<_>>());
@@ -84,7 +100,11 @@ pub(crate) fn run(opt: OptCompeteRetrieveTestcases, ctx: crate::Context<'_>) ->
         overwrite,
         &metadata.workspace_root,
         &cargo_compete_config.test_suite,
+        &cargo_compete_config.statement,
         &cookies_path,
+        profile,
+        &cache_dir,
+        &ProblemCache::new(&cache_dir, offline),
         shell,
     )
 }
@@ -0,0 +1,205 @@
+use crate::{
+    project::{MetadataExt as _, PackageExt as _},
+    shell::ColorChoice,
+};
+use snowchains_core::{
+    color_spec,
+    testsuite::{DeterministicExpectedOutput, ExpectedOutput, TestSuite},
+};
+use std::{collections::HashSet, path::PathBuf};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteShow {
+    /// Package (see `cargo help pkgid`)
+    #[structopt(short, long, value_name("SPEC"))]
+    pub package: Option<String>,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Name or alias for a `bin`/`example`
+    pub name_or_alias: String,
+}
+
+pub(crate) fn run(opt: OptCompeteShow, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteShow {
+        package,
+        manifest_path,
+        color,
+        name_or_alias,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        cookies_path: _,
+        cache_dir: _,
+        replay_dir: _,
+        profile: _,
+        shell,
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let manifest_path = manifest_path
+        .map(|p| Ok(cwd.join(p.strip_prefix(".").unwrap_or(&p))))
+        .unwrap_or_else(|| crate::project::locate_project(&cwd))?;
+    let metadata = crate::project::cargo_metadata(manifest_path, &cwd)?;
+    let member = metadata.query_for_member(package.as_deref())?;
+    let package_metadata = member.read_package_metadata(shell)?;
+    let (cargo_compete_config, _) = crate::config::load_for_package(member, shell)?;
+
+    let (bin_name, pkg_md_bin_example) =
+        package_metadata.bin_like_by_name_or_alias(&name_or_alias)?;
+
+    let test_suite_path = crate::testing::test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir(),
+        &cargo_compete_config.test_suite,
+        bin_name,
+        &pkg_md_bin_example.alias,
+        &pkg_md_bin_example.problem,
+        shell,
+    )?;
+
+    let statement_path = test_suite_path
+        .with_file_name(&pkg_md_bin_example.alias)
+        .join(crate::web::statement::FILE_NAME);
+
+    let statement = if statement_path.exists() {
+        Some(crate::fs::read_to_string(&statement_path)?)
+    } else {
+        shell.warn(format!(
+            "`{statement_path}` not found. set `statement.save = true` in `compete.toml` and run \
+             `cargo compete retrieve testcases --overwrite`",
+        ))?;
+        None
+    };
+
+    let test_suite = if test_suite_path.exists() {
+        Some(crate::fs::read_yaml::<TestSuite, _>(&test_suite_path)?)
+    } else {
+        shell.warn(format!("`{test_suite_path}` not found"))?;
+        None
+    };
+
+    let out = shell.out();
+
+    out.set_color(color_spec!(Bold))?;
+    write!(out, "{}", pkg_md_bin_example.problem)?;
+    out.reset()?;
+    writeln!(out, "\n")?;
+
+    if let Some(statement) = &statement {
+        render_markdown(statement, out)?;
+        writeln!(out)?;
+    }
+
+    if let Some(test_suite) = test_suite {
+        // The memory limit is not recorded in the test suite. It is in the statement, if any.
+        write_heading("Judge", out)?;
+        match &test_suite {
+            TestSuite::Batch(test_suite) => match test_suite.timelimit {
+                Some(timelimit) => {
+                    writeln!(out, "Time limit: {}", humantime::format_duration(timelimit))?
+                }
+                None => writeln!(out, "Time limit: none")?,
+            },
+            TestSuite::Interactive(_) => writeln!(out, "Interactive problem")?,
+            TestSuite::Unsubmittable => writeln!(out, "Unsubmittable problem")?,
+        }
+
+        if let TestSuite::Batch(test_suite) = test_suite {
+            // System test cases are not shown.
+            let cases = test_suite.load_test_cases(
+                test_suite_path.parent().unwrap().as_ref(),
+                None::<HashSet<String>>,
+                |_| Ok(vec![]),
+            )?;
+
+            for (i, case) in cases.iter().enumerate() {
+                writeln!(out)?;
+                write_heading(
+                    &format!(
+                        "Sample {}{}",
+                        i + 1,
+                        case.name
+                            .as_ref()
+                            .map(|s| format!(" ({s})"))
+                            .unwrap_or_default(),
+                    ),
+                    out,
+                )?;
+                writeln!(out, "Input:")?;
+                write_indented(&case.input, out)?;
+                let output = match &case.output {
+                    ExpectedOutput::Deterministic(DeterministicExpectedOutput::Pass) => None,
+                    ExpectedOutput::Deterministic(
+                        DeterministicExpectedOutput::Exact { text }
+                        | DeterministicExpectedOutput::SplitWhitespace { text }
+                        | DeterministicExpectedOutput::Lines { text }
+                        | DeterministicExpectedOutput::Float { text, .. },
+                    ) => Some(&**text),
+                    ExpectedOutput::Checker { text, .. } => text.as_deref(),
+                };
+                if let Some(output) = output {
+                    writeln!(out, "Output:")?;
+                    write_indented(output, out)?;
+                }
+            }
+        }
+    }
+
+    out.flush().map_err(Into::into)
+}
+
+/// Writes headings in bold and code blocks indented. The rest is written as it is.
+fn render_markdown(markdown: &str, out: &mut dyn WriteColor) -> anyhow::Result<()> {
+    let mut in_code_block = false;
+
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            in_code_block = !in_code_block;
+        } else if in_code_block {
+            out.set_color(color_spec!(Fg(Color::Cyan)))?;
+            write!(out, "    {line}")?;
+            out.reset()?;
+            writeln!(out)?;
+        } else if let Some(heading) = line
+            .strip_prefix('#')
+            .map(|s| s.trim_start_matches('#'))
+            .and_then(|s| s.strip_prefix(' '))
+        {
+            write_heading(heading, out)?;
+        } else {
+            writeln!(out, "{line}")?;
+        }
+    }
+    Ok(())
+}
+
+fn write_heading(heading: &str, out: &mut dyn WriteColor) -> anyhow::Result<()> {
+    out.set_color(color_spec!(Bold, Fg(Color::Green)))?;
+    write!(out, "{heading}")?;
+    out.reset()?;
+    writeln!(out).map_err(Into::into)
+}
+
+fn write_indented(text: &str, out: &mut dyn WriteColor) -> anyhow::Result<()> {
+    for line in text.lines() {
+        writeln!(out, "    {line}")?;
+    }
+    Ok(())
+}
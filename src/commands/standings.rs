@@ -0,0 +1,331 @@
+use crate::{
+    project::{MetadataExt as _, PackageExt as _},
+    shell::{ColorChoice, Shell},
+    user_config::UserConfigStandingsUsers,
+    web::{cache::ProblemCache, http, standings::Standings},
+};
+use anyhow::{bail, Context as _};
+use chrono::{Local, Utc};
+use prettytable::{
+    format::{Alignment, FormatBuilder, LinePosition, LineSeparator},
+    Cell, Row, Table,
+};
+use snowchains_core::web::PlatformKind;
+use std::{
+    borrow::BorrowMut as _,
+    cell::RefCell,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use url::Url;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteStandings {
+    /// Refresh the standings periodically
+    #[structopt(long)]
+    pub watch: bool,
+
+    /// Interval for `--watch`
+    #[structopt(
+        long,
+        value_name("DURATION"),
+        default_value("30s"),
+        parse(try_from_str = humantime::parse_duration)
+    )]
+    pub interval: Duration,
+
+    /// Platform for a contest ID
+    #[structopt(
+        long,
+        value_name("PLATFORM"),
+        possible_values(&["atcoder", "codeforces", "yukicoder"])
+    )]
+    pub platform: Option<PlatformKind>,
+
+    /// Package to find the contest of (see `cargo help pkgid`)
+    #[structopt(short, long, value_name("SPEC"))]
+    pub package: Option<String>,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Contest ID or URL, including a virtual contest of AtCoder Problems. Defaults to the contest
+    /// of the package
+    pub contest: Option<String>,
+}
+
+pub(crate) fn run(opt: OptCompeteStandings, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteStandings {
+        watch,
+        interval,
+        platform,
+        package,
+        manifest_path,
+        profile: _,
+        color,
+        contest,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        cookies_path,
+        cache_dir,
+        replay_dir: _,
+        profile: _,
+        shell,
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let contest = match contest {
+        Some(contest) => match contest.parse::<Url>() {
+            Ok(url) if crate::web::atcoder_problems::is_virtual_contest_url(&url) => {
+                Contest::Virtual(url)
+            }
+            Ok(url) => contest_from_url(&url)?,
+            Err(_) => {
+                let platform =
+                    platform.with_context(|| "`--platform` is required for a contest ID")?;
+                Contest::Original(platform, contest)
+            }
+        },
+        None => {
+            let manifest_path = manifest_path
+                .map(|p| Ok(cwd.join(p.strip_prefix(".").unwrap_or(&p))))
+                .unwrap_or_else(|| crate::project::locate_project(&cwd))?;
+            let metadata = crate::project::cargo_metadata(manifest_path, &cwd)?;
+            let member = metadata.query_for_member(package.as_deref())?;
+            let package_metadata = member.read_package_metadata(shell)?;
+
+            if let Some(url) = package_metadata.virtual_contest {
+                Contest::Virtual(url)
+            } else {
+                let url = &package_metadata
+                    .bin
+                    .values()
+                    .chain(package_metadata.example.values())
+                    .next()
+                    .with_context(|| format!("no problems in `{}`", member.name))?
+                    .problem;
+                contest_from_url(url)?
+            }
+        }
+    };
+
+    let (platform, contest) = match contest {
+        Contest::Original(platform, contest) => (platform, contest),
+        Contest::Virtual(url) => {
+            return run_for_virtual_contest(&url, watch, interval, &cache_dir, shell);
+        }
+    };
+
+    let UserConfigStandingsUsers { username, friends } =
+        crate::user_config::load()?.standings.users(platform);
+
+    let username = match username {
+        Some(username) => Some(username),
+        None => crate::web::session::username(platform, &cookies_path)?,
+    };
+    if username.is_none() {
+        shell.warn(format!(
+            "could not find your username. set `standings.{}.username` in the user config",
+            platform.to_kebab_case_str(),
+        ))?;
+    }
+
+    let users = username.iter().chain(&friends).cloned().collect::<Vec<_>>();
+
+    let title = format!("{} {}", platform.to_kebab_case_str(), contest);
+
+    loop {
+        let standings = {
+            let shell = RefCell::new(shell.borrow_mut());
+            http::with_retries(&shell, || {
+                crate::web::standings::retrieve(platform, &contest, &users, &cookies_path)
+            })?
+        };
+
+        if watch {
+            shell.clear_out()?;
+        }
+        print_standings(&title, &standings, shell)?;
+
+        if !watch {
+            break Ok(());
+        }
+        crate::web::schedule::wait_until(
+            Utc::now() + chrono::Duration::from_std(interval)?,
+            chrono::Duration::zero(),
+            shell,
+        )?;
+    }
+}
+
+enum Contest {
+    Original(PlatformKind, String),
+    /// A virtual contest of AtCoder Problems.
+    Virtual(Url),
+}
+
+/// Shows all of the participants, since a virtual contest is usually among acquaintances.
+fn run_for_virtual_contest(
+    url: &Url,
+    watch: bool,
+    interval: Duration,
+    cache_dir: &Path,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    // Fetches the participants every time, as they may join later.
+    let cache = &ProblemCache::new(cache_dir, false);
+
+    loop {
+        let contest = crate::web::atcoder_problems::get_virtual_contest(url, cache, shell)?;
+        let standings = {
+            let shell = RefCell::new(shell.borrow_mut());
+            http::with_retries(&shell, || crate::web::standings::retrieve_virtual(&contest))?
+        };
+
+        if watch {
+            shell.clear_out()?;
+        }
+        print_standings(
+            &format!("atcoder-problems {}", contest.title),
+            &standings,
+            shell,
+        )?;
+
+        if !watch {
+            break Ok(());
+        }
+        crate::web::schedule::wait_until(
+            Utc::now() + chrono::Duration::from_std(interval)?,
+            chrono::Duration::zero(),
+            shell,
+        )?;
+    }
+}
+
+fn contest_from_url(url: &Url) -> anyhow::Result<Contest> {
+    let platform = PlatformKind::from_url(url)?;
+
+    let contest = match platform {
+        PlatformKind::Atcoder => crate::web::url::atcoder_contest(url)?,
+        PlatformKind::Codeforces => crate::web::url::codeforces_contest(url)
+            .ok()
+            .filter(|c| c.parse::<u64>().is_ok())
+            .with_context(|| format!("could not find the contest ID in {url}"))?,
+        PlatformKind::Yukicoder => {
+            if !url.path().starts_with("/contests/") {
+                bail!("{} is not a URL of a contest", url);
+            }
+            crate::web::url::yukicoder_contest(url)?
+        }
+    };
+    Ok(Contest::Original(platform, contest))
+}
+
+fn print_standings(title: &str, standings: &Standings, shell: &mut Shell) -> anyhow::Result<()> {
+    let mut table = Table::new();
+    *table.get_format() = FormatBuilder::new()
+        .padding(1, 1)
+        .column_separator('│')
+        .borders('│')
+        .separator(LinePosition::Top, LineSeparator::new('─', '┬', '┌', '┐'))
+        .separator(LinePosition::Title, LineSeparator::new('─', '┼', '├', '┤'))
+        .separator(LinePosition::Bottom, LineSeparator::new('─', '┴', '└', '┘'))
+        .build();
+
+    table.set_titles(Row::new(
+        ["Rank", "User", "Score", "Penalty"]
+            .iter()
+            .map(|&s| s.to_owned())
+            .chain(standings.problems.iter().cloned())
+            .map(|s| Cell::new(&s))
+            .collect(),
+    ));
+
+    for row in &standings.rows {
+        let penalty = match (row.penalty, row.elapsed) {
+            (Some(penalty), Some(elapsed)) => {
+                let secs = elapsed.as_secs();
+                format!("{} ({}:{:02})", penalty, secs / 60, secs % 60)
+            }
+            (Some(penalty), None) => penalty.to_string(),
+            (None, _) => "-".to_owned(),
+        };
+
+        let results = row
+            .results
+            .iter()
+            .map(|result| match (result.accepted, result.failures) {
+                (true, 0) => format_score(result.score),
+                (true, failures) => format!("{} ({})", format_score(result.score), failures),
+                (false, 0) => "".to_owned(),
+                (false, failures) => format!("({failures})"),
+            });
+
+        table.add_row(Row::new(
+            vec![
+                Cell::new_align(&row.rank.to_string(), Alignment::RIGHT),
+                Cell::new(&row.user),
+                Cell::new_align(&format_score(row.score), Alignment::RIGHT),
+                Cell::new(&penalty),
+            ]
+            .into_iter()
+            .chain(results.map(|s| Cell::new_align(&s, Alignment::RIGHT)))
+            .collect(),
+        ));
+    }
+
+    table.add_row(Row::new(
+        vec![
+            Cell::new(""),
+            Cell::new("Solved"),
+            Cell::new(""),
+            Cell::new(""),
+        ]
+        .into_iter()
+        .chain(
+            standings
+                .solved
+                .iter()
+                .map(|n| Cell::new_align(&n.to_string(), Alignment::RIGHT)),
+        )
+        .collect(),
+    ));
+
+    let out = shell.out();
+    writeln!(
+        out,
+        "{} ({} participants, updated at {})",
+        title,
+        standings.participants,
+        Local::now().format("%H:%M:%S"),
+    )?;
+    write!(out, "{table}")?;
+    out.flush().map_err(Into::into)
+}
+
+fn format_score(score: f64) -> String {
+    if score.fract() == 0.0 {
+        format!("{score:.0}")
+    } else {
+        score.to_string()
+    }
+}
@@ -1,34 +1,61 @@
 use crate::{
     config::{
-        CargoCompeteConfigSubmit, CargoCompeteConfigSubmitCommand, CargoCompeteConfigSubmitFile,
+        CargoCompeteConfig, CargoCompeteConfigSubmit, CargoCompeteConfigSubmitCommand,
+        CargoCompeteConfigSubmitFile, CargoCompeteConfigSubmitLimits,
     },
+    hooks::Hook,
     oj_api,
-    project::{MetadataExt as _, PackageExt as _},
+    project::{MetadataExt as _, PackageExt as _, PackageMetadataCargoCompeteBinExample},
     shell::{ColorChoice, Shell},
-    web::{credentials, ATCODER_RUST_LANG_ID, CODEFORCES_RUST_LANG_ID, YUKICODER_RUST_LANG_ID},
+    submission_history,
+    web::{
+        aoj, credentials, http, AOJ_RUST_LANG_ID, ATCODER_RUST_LANG_ID, ATCODER_SOURCE_SIZE_LIMIT,
+        ATCODER_SUBMIT_INTERVAL, CODEFORCES_RUST_LANG_ID, CODEFORCES_SOURCE_SIZE_LIMIT,
+        CODEFORCES_SUBMIT_INTERVAL, FORBIDDEN_PATTERN_FEATURE_ATTRIBUTE, OTHER_SUBMIT_INTERVAL,
+        YUKICODER_RUST_LANG_ID, YUKICODER_SUBMIT_INTERVAL,
+    },
 };
 use anyhow::{bail, Context as _};
-use human_size::Size;
+use camino::Utf8Path;
+use cargo_metadata as cm;
+use chrono::{DateTime, Utc};
+use git2::{Repository, Status};
+use human_size::{Byte, Size};
+use itertools::Itertools as _;
 use liquid::object;
 use prettytable::{
     format::{FormatBuilder, LinePosition, LineSeparator},
     row, Row, Table,
 };
+use regex::Regex;
+use serde::Deserialize;
 use snowchains_core::web::{
-    Atcoder, AtcoderSubmitCredentials, AtcoderWatchSubmissionsCredentials,
-    AtcoderWatchSubmissionsTarget, Codeforces, CodeforcesSubmitCredentials, CookieStorage,
-    PlatformKind, ProblemInContest, Submit, WatchSubmissions, Yukicoder,
-    YukicoderSubmitCredentials, YukicoderSubmitTarget,
+    Atcoder, AtcoderRetrieveSubmissionSummariesCredentials,
+    AtcoderRetrieveSubmissionSummariesTarget, AtcoderSubmitCredentials, Codeforces,
+    CodeforcesSubmitCredentials, CookieStorage, PlatformKind, ProblemInContest,
+    RetrieveSubmissionSummaries, Submit, Yukicoder, YukicoderSubmitCredentials,
+    YukicoderSubmitTarget,
+};
+use std::{
+    borrow::BorrowMut as _,
+    cell::RefCell,
+    collections::HashMap,
+    env, io, iter,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
 };
-use std::{borrow::BorrowMut as _, cell::RefCell, env, io, iter, path::PathBuf};
 use structopt::StructOpt;
 use strum::VariantNames as _;
 use url::Url;
 
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
 #[derive(StructOpt, Debug)]
 #[structopt(usage(
-    r"cargo compete submit [OPTIONS] <bin-name-or-alias>
-    cargo compete submit [OPTIONS] --src <PATH>",
+    r"cargo compete submit [OPTIONS] <bin-name-or-alias>...
+    cargo compete submit [OPTIONS] --src <PATH>
+    cargo compete submit [OPTIONS] --all-untested-changes",
 ))]
 pub struct OptCompeteSubmit {
     /// Do not test before submitting
@@ -39,15 +66,36 @@ pub struct OptCompeteSubmit {
     #[structopt(long)]
     pub no_watch: bool,
 
+    /// Refuse to submit code that is identical to a previous non-AC submission, instead of asking
+    #[structopt(long)]
+    pub no_duplicate: bool,
+
+    /// Wait until the time before testing and submitting (RFC 3339, `%Y-%m-%d %H:%M[:%S]`, or `%H:%M[:%S]`)
+    #[structopt(
+        long,
+        value_name("TIME"),
+        parse(try_from_str = crate::web::schedule::parse_time),
+        conflicts_with("at-contest-start")
+    )]
+    pub at: Option<DateTime<Utc>>,
+
+    /// Wait until the contest starts before testing and submitting
+    #[structopt(long)]
+    pub at_contest_start: bool,
+
     /// Path to the source code
     #[structopt(
         long,
         value_name("PATH"),
-        required_unless("name-or-alias"),
-        conflicts_with("name-or-alias")
+        required_unless_one(&["names-or-aliases", "all-untested-changes"]),
+        conflicts_with_all(&["names-or-aliases", "all-untested-changes"])
     )]
     pub src: Option<PathBuf>,
 
+    /// Submit all of the `bin`s/`example`s whose source files have uncommitted changes
+    #[structopt(long, conflicts_with("names-or-aliases"))]
+    pub all_untested_changes: bool,
+
     /// Test for only the test cases
     #[structopt(long, value_name("NAME"))]
     pub testcases: Option<Vec<String>>,
@@ -72,6 +120,10 @@ pub struct OptCompeteSubmit {
     #[structopt(long, value_name("PATH"))]
     pub manifest_path: Option<PathBuf>,
 
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
     /// Coloring
     #[structopt(
         long,
@@ -81,32 +133,42 @@ pub struct OptCompeteSubmit {
     )]
     pub color: ColorChoice,
 
-    #[structopt(required_unless("src"))]
-    /// Name or alias for a `bin`/`example`
-    pub name_or_alias: Option<String>,
+    #[structopt(required_unless_one(&["src", "all-untested-changes"]))]
+    /// Names or aliases for `bin`s/`example`s
+    pub names_or_aliases: Vec<String>,
 }
 
 pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Result<()> {
     let OptCompeteSubmit {
         no_test,
         no_watch,
+        no_duplicate,
+        at,
+        at_contest_start,
         src,
+        all_untested_changes,
         testcases,
         display_limit,
         package,
         debug,
         release,
         manifest_path,
+        profile: _,
         color,
-        name_or_alias,
+        names_or_aliases,
     } = opt;
 
     let crate::Context {
         cwd,
         cookies_path,
+        cache_dir: _,
+        replay_dir: _,
+        profile,
         shell,
     } = ctx;
 
+    let profile = profile.as_deref();
+
     shell.set_color_choice(color);
 
     let manifest_path = manifest_path
@@ -116,21 +178,31 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
     let member = metadata.query_for_member(package.as_deref())?;
     let package_metadata = member.read_package_metadata(shell)?;
 
-    let (bin, package_metadata_bin) = if let Some(src) = src {
+    let targets = if let Some(src) = src {
         let src = cwd.join(src.strip_prefix(".").unwrap_or(&src));
         let bin = member.bin_target_by_src_path(src)?;
         let (_, pkg_md_bin) = package_metadata.bin_like_by_name_or_alias(&bin.name)?;
-        (bin, pkg_md_bin)
-    } else if let Some(name_or_alias) = &name_or_alias {
-        let (bin_name, pkg_md_bin) = package_metadata.bin_like_by_name_or_alias(name_or_alias)?;
-        let bin = member.bin_like_target_by_name(bin_name)?;
-        (bin, pkg_md_bin)
+        vec![(bin, pkg_md_bin)]
+    } else if all_untested_changes {
+        let targets = changed_bin_likes(member, &package_metadata)?;
+        if targets.is_empty() {
+            bail!("no `bin`/`example` has uncommitted changes");
+        }
+        targets
     } else {
-        unreachable!()
+        names_or_aliases
+            .iter()
+            .collect::<indexmap::IndexSet<_>>()
+            .into_iter()
+            .map(|name_or_alias| {
+                let (bin_name, pkg_md_bin) =
+                    package_metadata.bin_like_by_name_or_alias(name_or_alias)?;
+                let bin = member.bin_like_target_by_name(bin_name)?;
+                Ok((bin, pkg_md_bin))
+            })
+            .collect::<anyhow::Result<_>>()?
     };
 
-    let backend = Backend::new(&package_metadata_bin.problem);
-
     let (cargo_compete_config, cargo_compete_config_path) =
         crate::config::load_for_package(member, shell)?;
 
@@ -161,37 +233,244 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
     };
 
     if language_id.is_none() {
-        shell.warn(format!(
-            "{cargo_compete_config_path}: Missing `submit.language_id`. Using {}",
-            match backend {
-                Backend::Builtin(_) => "a hardcoded value",
-                Backend::Oj => "an inferred value",
-            },
-        ))?;
+        for backend in targets
+            .iter()
+            .filter(|(_, pkg_md_bin)| pkg_md_bin.language_id.is_none())
+            .map(|(_, pkg_md_bin)| Backend::new(&pkg_md_bin.problem))
+            .map(|backend| matches!(backend, Backend::Builtin(_)))
+            .unique()
+        {
+            shell.warn(format!(
+                "{cargo_compete_config_path}: Missing `submit.language_id`. Using {}",
+                if backend {
+                    "a hardcoded value"
+                } else {
+                    "an inferred value"
+                },
+            ))?;
+        }
+    }
+
+    let interval = cargo_compete_config.submit.interval();
+
+    if let Some(at) = at {
+        let origin = targets[0].1.problem.join("/")?;
+        let offset = crate::web::schedule::server_clock_offset(&origin)?;
+        crate::web::schedule::wait_until(at, offset, shell)?;
+    } else if at_contest_start {
+        let problem_urls = targets
+            .iter()
+            .map(|(_, pkg_md_bin)| &pkg_md_bin.problem)
+            .unique()
+            .collect::<Vec<_>>();
+        let (start, offset) = problem_urls
+            .iter()
+            .copied()
+            .map(crate::web::schedule::contest_start_time)
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .into_iter()
+            .max_by_key(|&(start, _)| start)
+            .expect("`targets` should not be empty");
+        crate::web::schedule::wait_until(start, offset, shell)?;
+        for problem_url in problem_urls {
+            crate::web::schedule::wait_until_accessible(problem_url, shell)?;
+        }
     }
 
+    // Tests all of them first so that a failure does not leave some of them submitted.
     if !no_test {
-        crate::process::process(env::current_exe()?)
-            .args(&["compete", "t", "--src"])
-            .arg(&bin.src_path)
-            .args(&if let Some(testcases) = testcases {
-                iter::once("--testcases".into()).chain(testcases).collect()
-            } else {
-                vec![]
-            })
-            .args(&["--display-limit", &display_limit.to_string()])
-            .args(if debug {
-                &["--debug"]
-            } else if release {
-                &["--release"]
-            } else {
-                &[]
+        for (bin, _) in &targets {
+            crate::process::process(env::current_exe()?)
+                .args(&["compete", "t", "--src"])
+                .arg(&bin.src_path)
+                .args(&if let Some(testcases) = &testcases {
+                    iter::once("--testcases".to_owned())
+                        .chain(testcases.iter().cloned())
+                        .collect()
+                } else {
+                    vec![]
+                })
+                .args(&["--display-limit", &display_limit.to_string()])
+                .args(if debug {
+                    &["--debug"]
+                } else if release {
+                    &["--release"]
+                } else {
+                    &[]
+                })
+                .args(&["--manifest-path".as_ref(), member.manifest_path.as_os_str()])
+                .args(&["--color", &color.to_string()])
+                .cwd(&metadata.workspace_root)
+                .exec_with_shell_status(shell)?;
+        }
+    }
+
+    let mut submissions = vec![];
+
+    for (i, (bin, package_metadata_bin)) in targets.into_iter().enumerate() {
+        let interval =
+            interval.unwrap_or_else(|| Backend::new(&package_metadata_bin.problem).interval());
+
+        if i > 0 && !interval.is_zero() {
+            shell.status(
+                "Waiting",
+                format!(
+                    "{} before the next submission",
+                    humantime::format_duration(interval),
+                ),
+            )?;
+            thread::sleep(interval);
+        }
+
+        let hook_vars = object!({
+            "manifest_dir": member.manifest_dir().as_str(),
+            "contest": crate::web::url::contest(&package_metadata_bin.problem),
+            "bin_name": &bin.name,
+            "bin_alias": &package_metadata_bin.alias,
+            "problem_url": package_metadata_bin.problem.as_str(),
+            "src_path": bin.src_path.as_str(),
+        });
+
+        crate::hooks::run(
+            &cargo_compete_config.hooks,
+            Hook::PreSubmit,
+            &hook_vars,
+            &metadata.workspace_root,
+            shell,
+        )?;
+
+        let submission_url = submit(
+            &metadata,
+            member,
+            bin,
+            package_metadata_bin,
+            &cargo_compete_config,
+            &cargo_compete_config_path,
+            language_id,
+            no_duplicate,
+            &cookies_path,
+            profile,
+            shell,
+        )?;
+
+        crate::hooks::run(
+            &cargo_compete_config.hooks,
+            Hook::PostSubmit,
+            &{
+                let mut vars = hook_vars;
+                vars.insert(
+                    "submission_url".into(),
+                    liquid::model::Value::scalar(submission_url.to_string()),
+                );
+                vars
+            },
+            &metadata.workspace_root,
+            shell,
+        )?;
+
+        submissions.push((bin, package_metadata_bin, submission_url));
+    }
+
+    if submissions.len() > 1 {
+        let mut rows = vec![row!["Name", "Alias", "URL (detail)"]];
+        for (bin, package_metadata_bin, submission_url) in &submissions {
+            rows.push(row![bin.name, package_metadata_bin.alias, submission_url]);
+        }
+        print_status(shell, &rows)?;
+    }
+
+    if !no_watch {
+        let platforms = submissions
+            .iter()
+            .flat_map(|(_, package_metadata_bin, _)| {
+                PlatformKind::from_url(&package_metadata_bin.problem).ok()
             })
-            .args(&["--manifest-path".as_ref(), member.manifest_path.as_os_str()])
-            .args(&["--color", &color.to_string()])
-            .cwd(&metadata.workspace_root)
-            .exec_with_shell_status(shell)?;
+            .unique()
+            .collect::<Vec<_>>();
+
+        if platforms.contains(&PlatformKind::Codeforces) {
+            shell.warn("watching submissions for Codeforces is not implemented")?;
+        }
+        if platforms.contains(&PlatformKind::Yukicoder) {
+            shell.warn("watching submissions for yukicoder is not implemented")?;
+        }
+        if platforms.contains(&PlatformKind::Atcoder) {
+            let submissions = submissions
+                .iter()
+                .filter(|(_, package_metadata_bin, _)| {
+                    PlatformKind::from_url(&package_metadata_bin.problem).ok()
+                        == Some(PlatformKind::Atcoder)
+                })
+                .map(|(bin, package_metadata_bin, submission_url)| {
+                    (&*bin.name, &*package_metadata_bin.alias, submission_url)
+                })
+                .collect::<Vec<_>>();
+            watch_atcoder_submissions(&submissions, &cookies_path, profile, shell)?;
+        }
+    }
+    Ok(())
+}
+
+/// Shows the verdicts of the submissions in one table, redrawing it until all of them are judged.
+fn watch_atcoder_submissions(
+    submissions: &[(&str, &str, &Url)],
+    cookies_path: &Path,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let contests = submissions
+        .iter()
+        .map(|(_, _, submission_url)| snowchains_core::web::atcoder_contest_id(submission_url))
+        .collect::<anyhow::Result<indexmap::IndexSet<_>>>()?;
+
+    loop {
+        let mut verdicts = HashMap::new();
+        for contest in &contests {
+            verdicts.extend(atcoder_verdicts(
+                contest,
+                cookies_path,
+                profile,
+                true,
+                shell,
+            )?);
+        }
+
+        let mut rows = vec![row!["Name", "Alias", "Verdict", "URL (detail)"]];
+        let mut finished = true;
+        for &(name, alias, submission_url) in submissions {
+            let verdict = verdicts.get(submission_url).map(|v| &**v).unwrap_or("WJ");
+            finished &= submission_history::is_final_verdict(verdict);
+            rows.push(row![name, alias, verdict, submission_url]);
+        }
+
+        shell.clear_out()?;
+        write!(shell.out(), "{}", table(&rows))?;
+        shell.out().flush()?;
+
+        if finished {
+            break Ok(());
+        }
+        thread::sleep(WATCH_INTERVAL);
     }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn submit(
+    metadata: &cm::Metadata,
+    member: &cm::Package,
+    bin: &cm::Target,
+    package_metadata_bin: &PackageMetadataCargoCompeteBinExample,
+    cargo_compete_config: &CargoCompeteConfig,
+    cargo_compete_config_path: &Utf8Path,
+    language_id: Option<&str>,
+    no_duplicate: bool,
+    cookies_path: &Path,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<Url> {
+    let backend = Backend::new(&package_metadata_bin.problem);
+
+    let language_id = package_metadata_bin.language_id.as_deref().or(language_id);
 
     let code = match &cargo_compete_config.submit {
         CargoCompeteConfigSubmit::File(CargoCompeteConfigSubmitFile { path, .. }) => {
@@ -205,7 +484,7 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
                 Ok(PlatformKind::Yukicoder) => {
                     crate::web::url::yukicoder_contest(&package_metadata_bin.problem).ok()
                 }
-                _ => todo!(),
+                Err(_) => None,
             };
             let path = path.render(&object!({
                 "manifest_dir": member.manifest_dir(),
@@ -241,6 +520,26 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
 
     let source_code_len = code.len();
 
+    check_limits(
+        &code,
+        &backend,
+        cargo_compete_config.submit.limits(),
+        cargo_compete_config_path,
+    )?;
+
+    let code_sha256 = submission_history::sha256(&code);
+    let submission_history_path = cookies_path.with_file_name("submissions.jsonl");
+
+    check_duplicate(
+        &package_metadata_bin.problem,
+        &code_sha256,
+        no_duplicate,
+        &submission_history_path,
+        cookies_path,
+        profile,
+        shell,
+    )?;
+
     if let Backend::Builtin(platform) = backend {
         let language_id = language_id.unwrap_or(match platform {
             PlatformKind::Atcoder => ATCODER_RUST_LANG_ID,
@@ -248,8 +547,8 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
             PlatformKind::Yukicoder => YUKICODER_RUST_LANG_ID,
         });
 
-        let cookie_storage = CookieStorage::with_jsonl(&cookies_path)?;
-        let timeout = crate::web::TIMEOUT;
+        let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
+        let timeout = crate::web::http::timeout();
 
         let outcome = match platform {
             PlatformKind::Atcoder => {
@@ -258,32 +557,39 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
                 let credentials = AtcoderSubmitCredentials {
                     username_and_password: &mut credentials::username_and_password(
                         &shell,
+                        profile,
+                        PlatformKind::Atcoder,
                         "Username: ",
                         "Password: ",
                     ),
                 };
 
-                Atcoder::exec(Submit {
-                    target: ProblemInContest::Url {
-                        url: package_metadata_bin.problem.clone(),
-                    },
-                    credentials,
-                    language_id: language_id.to_owned(),
-                    code,
-                    watch_submission: false,
-                    cookie_storage,
-                    timeout,
-                    shell: &shell,
+                http::with_env(|| {
+                    Atcoder::exec(Submit {
+                        target: ProblemInContest::Url {
+                            url: package_metadata_bin.problem.clone(),
+                        },
+                        credentials,
+                        language_id: language_id.to_owned(),
+                        code,
+                        watch_submission: false,
+                        cookie_storage,
+                        timeout,
+                        shell: &shell,
+                    })
                 })?
             }
             PlatformKind::Codeforces => {
-                let (api_key, api_secret) = credentials::codeforces_api_key_and_secret(shell)?;
+                let (api_key, api_secret) =
+                    credentials::codeforces_api_key_and_secret(cookies_path, profile, shell)?;
 
                 let shell = RefCell::new(shell.borrow_mut());
 
                 let credentials = CodeforcesSubmitCredentials {
                     username_and_password: &mut credentials::username_and_password(
                         &shell,
+                        profile,
+                        PlatformKind::Codeforces,
                         "Username: ",
                         "Password: ",
                     ),
@@ -291,37 +597,51 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
                     api_secret,
                 };
 
-                Codeforces::exec(Submit {
-                    target: ProblemInContest::Url {
-                        url: package_metadata_bin.problem.clone(),
-                    },
-                    credentials,
-                    language_id: language_id.to_owned(),
-                    code,
-                    watch_submission: false,
-                    cookie_storage,
-                    timeout,
-                    shell: &shell,
+                http::with_env(|| {
+                    Codeforces::exec(Submit {
+                        target: ProblemInContest::Url {
+                            url: package_metadata_bin.problem.clone(),
+                        },
+                        credentials,
+                        language_id: language_id.to_owned(),
+                        code,
+                        watch_submission: false,
+                        cookie_storage,
+                        timeout,
+                        shell: &shell,
+                    })
                 })?
             }
             PlatformKind::Yukicoder => {
                 let credentials = YukicoderSubmitCredentials {
-                    api_key: credentials::yukicoder_api_key(shell)?,
+                    api_key: credentials::yukicoder_api_key(cookies_path, profile, shell)?,
                 };
 
-                Yukicoder::exec(Submit {
-                    target: YukicoderSubmitTarget::Url(package_metadata_bin.problem.clone()),
-                    credentials,
-                    language_id: language_id.to_owned(),
-                    code,
-                    watch_submission: false,
-                    cookie_storage: (),
-                    timeout,
-                    shell: shell.borrow_mut(),
+                http::with_env(|| {
+                    Yukicoder::exec(Submit {
+                        target: YukicoderSubmitTarget::Url(package_metadata_bin.problem.clone()),
+                        credentials,
+                        language_id: language_id.to_owned(),
+                        code,
+                        watch_submission: false,
+                        cookie_storage: (),
+                        timeout,
+                        shell: shell.borrow_mut(),
+                    })
                 })?
             }
         };
 
+        submission_history::append(
+            &submission_history_path,
+            &submission_history::Entry {
+                problem: package_metadata_bin.problem.clone(),
+                sha256: code_sha256,
+                submission: Some(outcome.submission_url.clone()),
+                verdict: None,
+            },
+        )?;
+
         print_status(
             shell,
             &[
@@ -333,41 +653,39 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
             ],
         )?;
 
-        if !no_watch {
-            let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
-            let timeout = crate::web::TIMEOUT;
+        Ok(outcome.submission_url)
+    } else if let Backend::Aoj = backend {
+        let language_id = language_id.unwrap_or(AOJ_RUST_LANG_ID);
 
-            match platform {
-                PlatformKind::Atcoder => {
-                    let contest =
-                        snowchains_core::web::atcoder_contest_id(&package_metadata_bin.problem)?;
+        let url = aoj::submit(
+            &package_metadata_bin.problem,
+            &code,
+            language_id,
+            profile,
+            shell,
+        )?;
 
-                    let shell = RefCell::new(shell);
+        submission_history::append(
+            &submission_history_path,
+            &submission_history::Entry {
+                problem: package_metadata_bin.problem.clone(),
+                sha256: code_sha256,
+                submission: Some(url.clone()),
+                verdict: None,
+            },
+        )?;
 
-                    let credentials = AtcoderWatchSubmissionsCredentials {
-                        username_and_password: &mut credentials::username_and_password(
-                            &shell,
-                            "Username: ",
-                            "Password: ",
-                        ),
-                    };
+        print_status(
+            shell,
+            &[
+                row!["Method", "cargo-compete"],
+                row!["Language ID", language_id],
+                row!["Size", source_code_len],
+                row!["URL (detail)", url],
+            ],
+        )?;
 
-                    Atcoder::exec(WatchSubmissions {
-                        target: AtcoderWatchSubmissionsTarget { contest },
-                        credentials,
-                        cookie_storage,
-                        timeout,
-                        shell: &shell,
-                    })?;
-                }
-                PlatformKind::Codeforces => {
-                    shell.warn("watching submissions for Codeforces is not implemented")?;
-                }
-                PlatformKind::Yukicoder => {
-                    shell.warn("watching submissions for yukicoder is not implemented")?;
-                }
-            }
-        }
+        Ok(url)
     } else {
         let tempdir = tempfile::Builder::new()
             .prefix("cargo-compete-submit-code-with-oj-api-")
@@ -397,6 +715,16 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
             shell,
         )?;
 
+        submission_history::append(
+            &submission_history_path,
+            &submission_history::Entry {
+                problem: package_metadata_bin.problem.clone(),
+                sha256: code_sha256,
+                submission: Some(url.clone()),
+                verdict: None,
+            },
+        )?;
+
         print_status(
             shell,
             &[
@@ -408,11 +736,457 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
         )?;
 
         tempdir.close()?;
+
+        Ok(url)
+    }
+}
+
+fn changed_bin_likes<'a>(
+    member: &'a cm::Package,
+    package_metadata: &'a crate::project::PackageMetadataCargoCompete,
+) -> anyhow::Result<Vec<(&'a cm::Target, &'a PackageMetadataCargoCompeteBinExample)>> {
+    let repo = Repository::discover(member.manifest_dir())
+        .with_context(|| format!("`{}` is not in a Git repository", member.manifest_dir()))?;
+    let workdir = repo
+        .workdir()
+        .with_context(|| "the Git repository is bare")?
+        .to_owned();
+
+    let changed = repo
+        .statuses(None)?
+        .iter()
+        .filter(|entry| {
+            entry.status().intersects(
+                Status::INDEX_NEW
+                    | Status::INDEX_MODIFIED
+                    | Status::INDEX_RENAMED
+                    | Status::WT_NEW
+                    | Status::WT_MODIFIED
+                    | Status::WT_RENAMED,
+            )
+        })
+        .flat_map(|entry| entry.path().map(|p| workdir.join(p)))
+        .collect::<Vec<_>>();
+
+    let mut targets = vec![];
+    for (name, pkg_md_bin) in itertools::chain(&package_metadata.bin, &package_metadata.example) {
+        let bin = member.bin_like_target_by_name(name)?;
+        if changed.iter().any(|p| *p == bin.src_path) {
+            targets.push((bin, pkg_md_bin));
+        }
+    }
+    Ok(targets)
+}
+
+fn check_limits(
+    code: &str,
+    backend: &Backend,
+    limits: &CargoCompeteConfigSubmitLimits,
+    cargo_compete_config_path: &Utf8Path,
+) -> anyhow::Result<()> {
+    let CargoCompeteConfigSubmitLimits {
+        source_size,
+        forbidden_patterns,
+        ..
+    } = limits;
+
+    let source_size = source_size.or_else(|| match backend {
+        Backend::Builtin(PlatformKind::Atcoder) => ATCODER_SOURCE_SIZE_LIMIT.parse().ok(),
+        Backend::Builtin(PlatformKind::Codeforces) => CODEFORCES_SOURCE_SIZE_LIMIT.parse().ok(),
+        Backend::Builtin(PlatformKind::Yukicoder) | Backend::Aoj | Backend::Oj => None,
+    });
+
+    if let Some(source_size) = source_size {
+        let max = source_size.into::<Byte>().value();
+        if code.len() as f64 > max {
+            bail!(
+                "the code is {} bytes, which exceeds the source size limit ({}). the judge would \
+                 reject it. to change the limit, set `submit.limits.source-size` in {}",
+                code.len(),
+                source_size,
+                cargo_compete_config_path,
+            );
+        }
+    }
+
+    let default_forbidden_patterns;
+    let forbidden_patterns = if let Some(forbidden_patterns) = forbidden_patterns {
+        forbidden_patterns
+    } else if let Backend::Builtin(_) = backend {
+        default_forbidden_patterns = vec![Regex::new(FORBIDDEN_PATTERN_FEATURE_ATTRIBUTE)?];
+        &default_forbidden_patterns
+    } else {
+        return Ok(());
+    };
+
+    let stripped = blank_comments_and_strings(code);
+
+    for (i, (line, stripped)) in code.lines().zip(stripped.lines()).enumerate() {
+        if let Some(pattern) = forbidden_patterns.iter().find(|p| p.is_match(stripped)) {
+            bail!(
+                "line {} of the code matches a forbidden pattern `{}`: `{}`. the judge would \
+                 reject it. to change the patterns, set `submit.limits.forbidden-patterns` in {}",
+                i + 1,
+                pattern,
+                line.trim(),
+                cargo_compete_config_path,
+            );
+        }
     }
     Ok(())
 }
 
+/// Replaces the comments and the string/character literals with spaces, keeping the line breaks so
+/// that the forbidden patterns are matched line by line only against the code.
+fn blank_comments_and_strings(code: &str) -> String {
+    let chars = code.chars().collect::<Vec<_>>();
+    let mut blanked = chars.clone();
+    let mut blank = |range: std::ops::Range<usize>| {
+        for c in &mut blanked[range] {
+            if *c != '\n' {
+                *c = ' ';
+            }
+        }
+    };
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let rest = &chars[i..];
+        let after_ident = i > 0 && is_ident(chars[i - 1]);
+
+        if rest.starts_with(&['/', '/']) {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            blank(start..i);
+        } else if rest.starts_with(&['/', '*']) {
+            let mut depth = 0;
+            while i < chars.len() {
+                if chars[i..].starts_with(&['/', '*']) {
+                    depth += 1;
+                    i += 2;
+                } else if chars[i..].starts_with(&['*', '/']) {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+            blank(start..i.min(chars.len()));
+        } else if let Some((prefix_len, hashes)) = raw_string_hashes(rest).filter(|_| !after_ident)
+        {
+            i += prefix_len;
+            while i < chars.len()
+                && !(chars[i] == '"'
+                    && chars[i + 1..].iter().take_while(|&&c| c == '#').count() >= hashes)
+            {
+                i += 1;
+            }
+            i = (i + 1 + hashes).min(chars.len());
+            blank(start..i);
+        } else if chars[i] == '"' {
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+            i = (i + 1).min(chars.len());
+            blank(start..i);
+        } else if chars[i] == '\'' && rest.get(1) == Some(&'\\') {
+            i += 3;
+            while i < chars.len() && chars[i] != '\'' {
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            blank(start..i);
+        } else if chars[i] == '\'' && rest.get(2) == Some(&'\'') {
+            i += 3;
+            blank(start..i);
+        } else {
+            i += 1;
+        }
+    }
+    return blanked.into_iter().collect();
+
+    /// `r"`, `r#"`, `br##"`, and so on.
+    fn raw_string_hashes(rest: &[char]) -> Option<(usize, usize)> {
+        let b = usize::from(rest.first() == Some(&'b'));
+        if rest.get(b) != Some(&'r') {
+            return None;
+        }
+        let hashes = rest[b + 1..].iter().take_while(|&&c| c == '#').count();
+        (rest.get(b + 1 + hashes) == Some(&'"')).then_some((b + 2 + hashes, hashes))
+    }
+}
+
+fn check_duplicate(
+    problem_url: &Url,
+    code_sha256: &str,
+    no_duplicate: bool,
+    submission_history_path: &Path,
+    cookies_path: &Path,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let mut history = submission_history::load(submission_history_path)?;
+
+    let is_duplicate = |entry: &submission_history::Entry| -> _ {
+        entry.problem == *problem_url && entry.sha256 == code_sha256
+    };
+
+    if !history.iter().any(is_duplicate) {
+        return Ok(());
+    }
+
+    let pending = history
+        .iter()
+        .filter(|entry| is_duplicate(entry) && entry.verdict.is_none())
+        .flat_map(|entry| entry.submission.as_ref())
+        .collect::<Vec<_>>();
+
+    if !pending.is_empty() {
+        // Only to tell the verdicts, so it never asks for credentials.
+        let verdicts = match previous_verdicts(problem_url, &pending, cookies_path, profile, shell)
+        {
+            Ok(verdicts) => verdicts,
+            Err(err) => {
+                shell.warn(format!(
+                    "could not retrieve the verdicts of the previous submissions: {err}",
+                ))?;
+                HashMap::new()
+            }
+        };
+
+        for entry in history.iter_mut().filter(|entry| is_duplicate(entry)) {
+            if let Some(verdict) = entry.submission.as_ref().and_then(|u| verdicts.get(u)) {
+                if submission_history::is_final_verdict(verdict) {
+                    entry.verdict = Some(verdict.clone());
+                }
+            }
+        }
+
+        submission_history::save(submission_history_path, &history)?;
+    }
+
+    let non_accepted = history
+        .iter()
+        .filter(|entry| is_duplicate(entry) && !entry.is_accepted())
+        .collect::<Vec<_>>();
+
+    if non_accepted.is_empty() {
+        return Ok(());
+    }
+
+    let message = format!(
+        "the same code has already been submitted to {} ({})",
+        problem_url,
+        non_accepted
+            .iter()
+            .map(
+                |submission_history::Entry {
+                     submission,
+                     verdict,
+                     ..
+                 }| {
+                    format!(
+                        "{}: {}",
+                        submission
+                            .as_ref()
+                            .map(|u| u.as_str())
+                            .unwrap_or("unknown submission"),
+                        verdict.as_deref().unwrap_or("verdict unknown"),
+                    )
+                }
+            )
+            .format(", "),
+    );
+
+    if no_duplicate {
+        bail!("{}", message);
+    }
+
+    shell.warn(message)?;
+
+    if !matches!(
+        &*shell
+            .read_reply("Submit anyway? [y/N] ")?
+            .trim()
+            .to_lowercase(),
+        "y" | "yes"
+    ) {
+        bail!("aborted");
+    }
+    Ok(())
+}
+
+/// Retrieves the verdicts of the previous submissions to the problem with the stored cookies and
+/// tokens.
+///
+/// The ones of AOJ and the platforms supported through `oj-api` are not retrieved.
+fn previous_verdicts(
+    problem_url: &Url,
+    submissions: &[&Url],
+    cookies_path: &Path,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<HashMap<Url, String>> {
+    match PlatformKind::from_url(problem_url) {
+        Ok(PlatformKind::Atcoder) => {
+            let contest = snowchains_core::web::atcoder_contest_id(problem_url)?;
+            atcoder_verdicts(&contest, cookies_path, profile, false, shell)
+        }
+        Ok(PlatformKind::Codeforces) => {
+            let contest = crate::web::url::codeforces_contest(problem_url)?;
+            let handle = crate::web::session::username(PlatformKind::Codeforces, cookies_path)?
+                .with_context(|| "not logged in to Codeforces")?;
+            let verdicts = codeforces_verdicts(&contest, &handle)?;
+            Ok(submissions
+                .iter()
+                .flat_map(|&url| {
+                    let id = url.path_segments()?.next_back()?.parse::<u64>().ok()?;
+                    Some((url.clone(), verdicts.get(&id)?.clone()))
+                })
+                .collect())
+        }
+        Ok(PlatformKind::Yukicoder) => {
+            let client = &http::reqwest_client()?;
+            submissions
+                .iter()
+                .map(|&url| {
+                    let html = crate::web::replay::send(client, client.get(url.clone()))
+                        .and_then(|res| res.error_for_status().map_err(Into::into))
+                        .and_then(|res| res.text().map_err(Into::into))
+                        .with_context(|| format!("could not get {url}"))?;
+                    Ok(yukicoder_verdict(&html).map(|verdict| (url.clone(), verdict)))
+                })
+                .flat_map(Result::transpose)
+                .collect()
+        }
+        Err(_) => Ok(HashMap::new()),
+    }
+}
+
+/// Retrieves the verdicts of the user's submissions in the contest, keyed by the submission IDs.
+///
+/// The ones being judged are omitted. The others are abbreviated like AtCoder's.
+fn codeforces_verdicts(contest: &str, handle: &str) -> anyhow::Result<HashMap<u64, String>> {
+    let mut url = "https://codeforces.com/api/contest.status".parse::<Url>()?;
+    url.query_pairs_mut()
+        .append_pair("contestId", contest)
+        .append_pair("handle", handle);
+
+    let Response { result } = http::get_json(&http::reqwest_client()?, url.as_str())?;
+    return Ok(codeforces_verdicts_in(result));
+
+    #[derive(Deserialize)]
+    struct Response {
+        result: Vec<CodeforcesSubmission>,
+    }
+}
+
+#[derive(Deserialize)]
+struct CodeforcesSubmission {
+    id: u64,
+    verdict: Option<String>,
+}
+
+fn codeforces_verdicts_in(submissions: Vec<CodeforcesSubmission>) -> HashMap<u64, String> {
+    submissions
+        .into_iter()
+        .flat_map(|CodeforcesSubmission { id, verdict }| {
+            let verdict = match verdict?.as_str() {
+                "TESTING" => return None,
+                "OK" => "AC".to_owned(),
+                "WRONG_ANSWER" => "WA".to_owned(),
+                "TIME_LIMIT_EXCEEDED" => "TLE".to_owned(),
+                "MEMORY_LIMIT_EXCEEDED" => "MLE".to_owned(),
+                "RUNTIME_ERROR" => "RE".to_owned(),
+                "COMPILATION_ERROR" => "CE".to_owned(),
+                verdict => verdict.to_owned(),
+            };
+            Some((id, verdict))
+        })
+        .collect()
+}
+
+/// Finds the verdict in a submission page of yukicoder. `None` while it is being judged.
+fn yukicoder_verdict(html: &str) -> Option<String> {
+    Regex::new(r#"<span class="label label-[a-z]+">(AC|WA|TLE|MLE|OLE|QLE|RE|CE|IE)</span>"#)
+        .unwrap()
+        .captures(html)
+        .map(|caps| caps[1].to_owned())
+}
+
+/// With `login = false`, it fails instead of asking for the username and password when the
+/// stored cookies are not for a logged-in session.
+fn atcoder_verdicts(
+    contest: &str,
+    cookies_path: &Path,
+    profile: Option<&str>,
+    login: bool,
+    shell: &mut Shell,
+) -> anyhow::Result<HashMap<Url, String>> {
+    let outcome = {
+        let shell = RefCell::new(shell.borrow_mut());
+
+        let mut prompt = credentials::username_and_password(
+            &shell,
+            profile,
+            PlatformKind::Atcoder,
+            "Username: ",
+            "Password: ",
+        );
+        let mut no_login = || bail!("not logged in to AtCoder");
+        let mut username_and_password: &mut dyn FnMut() -> anyhow::Result<(String, String)> =
+            if login { &mut prompt } else { &mut no_login };
+
+        http::with_retries(&shell, || {
+            http::with_env(|| {
+                Atcoder::exec(RetrieveSubmissionSummaries {
+                    target: AtcoderRetrieveSubmissionSummariesTarget {
+                        contest: contest.to_owned(),
+                    },
+                    credentials: AtcoderRetrieveSubmissionSummariesCredentials {
+                        username_and_password: &mut username_and_password,
+                    },
+                    cookie_storage: CookieStorage::with_jsonl(cookies_path)?,
+                    timeout: http::timeout(),
+                    shell: &shell,
+                })
+            })
+        })?
+    };
+
+    let Summaries { summaries } = serde_json::from_str(&outcome.to_json())?;
+
+    return Ok(summaries
+        .into_iter()
+        .map(|Summary { detail, status }| (detail, status))
+        .collect());
+
+    #[derive(Deserialize)]
+    struct Summaries {
+        summaries: Vec<Summary>,
+    }
+
+    #[derive(Deserialize)]
+    struct Summary {
+        detail: Url,
+        status: String,
+    }
+}
+
 fn print_status(shell: &mut Shell, rows: &[Row]) -> io::Result<()> {
+    write!(shell.err(), "{}", table(rows))?;
+    shell.err().flush()?;
+    shell.status("Successfully", "submitted the code")
+}
+
+fn table(rows: &[Row]) -> Table {
     let mut table = Table::new();
     *table.get_format() = FormatBuilder::new()
         .padding(1, 1)
@@ -424,13 +1198,12 @@ fn print_status(shell: &mut Shell, rows: &[Row]) -> io::Result<()> {
         .separator(LinePosition::Bottom, LineSeparator::new('─', '┴', '└', '┘'))
         .build();
     table.extend(rows.iter().cloned());
-    write!(shell.err(), "{table}")?;
-    shell.err().flush()?;
-    shell.status("Successfully", "submitted the code")
+    table
 }
 
 enum Backend {
     Builtin(PlatformKind),
+    Aoj,
     Oj,
 }
 
@@ -438,7 +1211,128 @@ impl Backend {
     fn new(url: &Url) -> Self {
         match PlatformKind::from_url(url) {
             Ok(platform) => Self::Builtin(platform),
+            Err(_) if aoj::is_problem_url(url) => Self::Aoj,
             Err(_) => Self::Oj,
         }
     }
+
+    /// Default of `submit.interval`.
+    fn interval(&self) -> Duration {
+        match self {
+            Self::Builtin(PlatformKind::Atcoder) => ATCODER_SUBMIT_INTERVAL,
+            Self::Builtin(PlatformKind::Codeforces) => CODEFORCES_SUBMIT_INTERVAL,
+            Self::Builtin(PlatformKind::Yukicoder) => YUKICODER_SUBMIT_INTERVAL,
+            Self::Aoj | Self::Oj => OTHER_SUBMIT_INTERVAL,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CodeforcesSubmission;
+    use crate::{shell::Shell, submission_history};
+    use maplit::hashmap;
+    use pretty_assertions::assert_eq;
+    use std::io;
+    use url::Url;
+
+    #[test]
+    fn check_duplicate() -> anyhow::Result<()> {
+        // Not of a builtin platform, so the verdicts are not retrieved.
+        let problem_url = &"https://judge.yosupo.jp/problem/aplusb".parse::<Url>()?;
+        let code_sha256 = &submission_history::sha256("fn main() {}\n");
+
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-check-duplicate-")
+            .tempdir()?;
+        let cookies_path = &tempdir.path().join("cookies.jsonl");
+        let history_path = &tempdir.path().join("submissions.jsonl");
+
+        let check = |verdict: Option<&str>, no_duplicate: bool, reply: &'static [u8]| {
+            submission_history::save(
+                history_path,
+                &[submission_history::Entry {
+                    problem: problem_url.clone(),
+                    sha256: code_sha256.clone(),
+                    submission: Some("https://judge.yosupo.jp/submission/1".parse().unwrap()),
+                    verdict: verdict.map(ToOwned::to_owned),
+                }],
+            )?;
+            super::check_duplicate(
+                problem_url,
+                code_sha256,
+                no_duplicate,
+                history_path,
+                cookies_path,
+                None,
+                &mut Shell::from_read_write(Box::new(reply), Box::new(io::sink())),
+            )
+        };
+
+        check(Some("AC"), true, b"")?;
+        check(Some("WA"), false, b"y\n")?;
+        assert_eq!(
+            "aborted",
+            check(Some("WA"), false, b"\n").unwrap_err().to_string(),
+        );
+        assert_eq!(
+            "the same code has already been submitted to https://judge.yosupo.jp/problem/aplusb \
+             (https://judge.yosupo.jp/submission/1: verdict unknown)",
+            check(None, true, b"").unwrap_err().to_string(),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn codeforces_verdicts_in() {
+        let submissions = [
+            (1, Some("OK")),
+            (2, Some("WRONG_ANSWER")),
+            (3, Some("TESTING")),
+            (4, None),
+        ]
+        .iter()
+        .map(|&(id, verdict)| CodeforcesSubmission {
+            id,
+            verdict: verdict.map(ToOwned::to_owned),
+        })
+        .collect();
+        assert_eq!(
+            hashmap!(1 => "AC".to_owned(), 2 => "WA".to_owned()),
+            super::codeforces_verdicts_in(submissions),
+        );
+    }
+
+    #[test]
+    fn blank_comments_and_strings() {
+        let code = r##"#![feature(test)] // #![feature(a)]
+/* #![feature(b)]
+   /* nested */ #![feature(c)] */ fn main() {
+    let _ = ("#![feature(d)]", b"\"", r#"#![feature("e")]"#, '"', '\'', 'x');
+    let _: &'static str = "";
+}
+"##;
+        assert_eq!(
+            r#"#![feature(test)]                  
+                 
+                                  fn main() {
+    let _ = (                , b    ,                      ,    ,     ,    );
+    let _: &'static str =   ;
+}
+"#,
+            super::blank_comments_and_strings(code),
+        );
+    }
+
+    #[test]
+    fn yukicoder_verdict() {
+        assert_eq!(
+            Some("TLE".to_owned()),
+            super::yukicoder_verdict(r#"<td><span class="label label-warning">TLE</span></td>"#),
+        );
+        assert_eq!(
+            None,
+            super::yukicoder_verdict(r#"<td><span class="label label-default">WJ</span></td>"#),
+        );
+    }
 }
@@ -43,10 +43,18 @@ pub struct OptCompeteTest {
     #[structopt(long)]
     pub release: bool,
 
+    /// Do not download the system test cases. Also enabled by `$CARGO_COMPETE_OFFLINE`
+    #[structopt(long)]
+    pub offline: bool,
+
     /// Path to Cargo.toml
     #[structopt(long, value_name("PATH"))]
     pub manifest_path: Option<PathBuf>,
 
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
     /// Coloring
     #[structopt(
         long,
@@ -69,7 +77,9 @@ pub(crate) fn run(opt: OptCompeteTest, ctx: crate::Context<'_>) -> anyhow::Resul
         package,
         debug,
         release,
+        offline,
         manifest_path,
+        profile: _,
         color,
         name_or_alias,
     } = opt;
@@ -77,9 +87,14 @@ pub(crate) fn run(opt: OptCompeteTest, ctx: crate::Context<'_>) -> anyhow::Resul
     let crate::Context {
         cwd,
         cookies_path,
+        cache_dir,
+        replay_dir: _,
+        profile,
         shell,
     } = ctx;
 
+    let profile = profile.as_deref();
+
     shell.set_color_choice(color);
 
     let manifest_path = manifest_path
@@ -121,7 +136,10 @@ pub(crate) fn run(opt: OptCompeteTest, ctx: crate::Context<'_>) -> anyhow::Resul
         },
         test_case_names: testcases.map(|ss| ss.into_iter().collect()),
         display_limit,
+        offline: crate::web::cache::is_offline(offline),
         cookies_path: &cookies_path,
+        profile,
+        cache_dir: &cache_dir,
         shell,
     })
 }
@@ -1,4 +1,7 @@
-use crate::{shell::ColorChoice, web::credentials};
+use crate::{
+    shell::ColorChoice,
+    web::{credentials, http},
+};
 use snowchains_core::web::{
     Atcoder, AtcoderWatchSubmissionsCredentials, AtcoderWatchSubmissionsTarget, CookieStorage,
     PlatformKind, WatchSubmissions,
@@ -9,6 +12,10 @@ use strum::VariantNames as _;
 
 #[derive(StructOpt, Debug)]
 pub struct OptCompeteWatchSubmissions {
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
     /// Coloring
     #[structopt(
         long,
@@ -28,6 +35,7 @@ pub struct OptCompeteWatchSubmissions {
 
 pub(crate) fn run(opt: OptCompeteWatchSubmissions, ctx: crate::Context<'_>) -> anyhow::Result<()> {
     let OptCompeteWatchSubmissions {
+        profile: _,
         color,
         platform,
         contest,
@@ -36,13 +44,18 @@ pub(crate) fn run(opt: OptCompeteWatchSubmissions, ctx: crate::Context<'_>) -> a
     let crate::Context {
         cwd: _,
         cookies_path,
+        cache_dir: _,
+        replay_dir: _,
+        profile,
         shell,
     } = ctx;
 
+    let profile = profile.as_deref();
+
     shell.set_color_choice(color);
 
-    let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
-    let timeout = crate::web::TIMEOUT;
+    let cookie_storage = CookieStorage::with_jsonl(&cookies_path)?;
+    let timeout = http::timeout();
 
     if platform == PlatformKind::Atcoder {
         let shell = RefCell::new(shell);
@@ -50,17 +63,21 @@ pub(crate) fn run(opt: OptCompeteWatchSubmissions, ctx: crate::Context<'_>) -> a
         let credentials = AtcoderWatchSubmissionsCredentials {
             username_and_password: &mut credentials::username_and_password(
                 &shell,
+                profile,
+                PlatformKind::Atcoder,
                 "Username: ",
                 "Password: ",
             ),
         };
 
-        Atcoder::exec(WatchSubmissions {
-            target: AtcoderWatchSubmissionsTarget { contest },
-            credentials,
-            cookie_storage,
-            timeout,
-            shell: &shell,
+        http::with_env(|| {
+            Atcoder::exec(WatchSubmissions {
+                target: AtcoderWatchSubmissionsTarget { contest },
+                credentials,
+                cookie_storage,
+                timeout,
+                shell: &shell,
+            })
         })
     } else {
         unreachable!()
@@ -0,0 +1,111 @@
+use crate::{
+    shell::ColorChoice,
+    web::{
+        credentials::{self, Token},
+        session,
+    },
+};
+use snowchains_core::web::PlatformKind;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteWhoami {
+    /// Profile to use the cookies and tokens of
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Platform to check. If omitted, all of the platforms are checked
+    #[structopt(possible_values(PlatformKind::KEBAB_CASE_VARIANTS))]
+    pub platform: Option<PlatformKind>,
+}
+
+pub(crate) fn run(opt: OptCompeteWhoami, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteWhoami {
+        profile: _,
+        color,
+        platform,
+    } = opt;
+
+    let crate::Context {
+        cwd: _,
+        cookies_path,
+        cache_dir: _,
+        replay_dir: _,
+        profile,
+        shell,
+    } = ctx;
+
+    let profile = profile.as_deref();
+
+    shell.set_color_choice(color);
+
+    let platforms = match platform {
+        Some(platform) => vec![platform],
+        None => vec![
+            PlatformKind::Atcoder,
+            PlatformKind::Codeforces,
+            PlatformKind::Yukicoder,
+        ],
+    };
+
+    for platform in platforms {
+        let username = || -> anyhow::Result<_> {
+            Ok(session::username(platform, &cookies_path)?
+                .unwrap_or_else(|| "not logged in".to_owned()))
+        };
+
+        let status = match platform {
+            PlatformKind::Atcoder => username()?,
+            PlatformKind::Codeforces => {
+                let api_key = match credentials::stored_codeforces_api_key_and_secret(
+                    &cookies_path,
+                    profile,
+                    shell,
+                )? {
+                    None => "not stored",
+                    Some((api_key, api_secret)) => {
+                        if session::codeforces_api_key_is_valid(&api_key, &api_secret)? {
+                            "valid"
+                        } else {
+                            "invalid"
+                        }
+                    }
+                };
+                format!("{}, API key {}", username()?, api_key)
+            }
+            PlatformKind::Yukicoder => {
+                let api_key =
+                    match credentials::stored_yukicoder_api_key(&cookies_path, profile, shell)? {
+                        None => "not stored".to_owned(),
+                        Some(api_key) => match session::yukicoder_username(&api_key)? {
+                            Some(username) => format!("valid ({username})"),
+                            None => "invalid".to_owned(),
+                        },
+                    };
+                format!("API key {api_key}")
+            }
+        };
+
+        writeln!(shell.out(), "{platform}: {status}")?;
+    }
+    shell.out().flush()?;
+    Ok(())
+}
+
+pub(crate) fn api_key(platform: PlatformKind) -> Option<Token> {
+    match platform {
+        PlatformKind::Atcoder => None,
+        PlatformKind::Codeforces => Some(Token::Codeforces),
+        PlatformKind::Yukicoder => Some(Token::Yukicoder),
+    }
+}
@@ -1,21 +1,30 @@
 use crate::{project::PackageExt as _, shell::Shell};
-use anyhow::{bail, Context as _};
+use anyhow::{anyhow, bail, Context as _};
 use camino::{Utf8Path, Utf8PathBuf};
 use cargo_metadata as cm;
 use derivative::Derivative;
 use heck::KebabCase as _;
-use indexmap::indexset;
+use human_size::Size;
+use indexmap::{indexset, IndexMap};
+use itertools::Itertools as _;
 use liquid::object;
 use maplit::btreemap;
+use regex::Regex;
 use serde::{de::Error as _, Deserialize, Deserializer};
-use snowchains_core::web::PlatformKind;
+use snowchains_core::{
+    testsuite::{BatchTestSuite, InteractiveTestSuite, TestSuite},
+    web::PlatformKind,
+};
 use std::{
     collections::BTreeMap,
     fmt::{self, Display},
+    iter,
     path::Path,
     str::{self, FromStr},
+    time::Duration,
 };
 use strum::{Display, EnumString};
+use url::Url;
 
 pub(crate) fn generate(
     template_new_edition: &str,
@@ -124,6 +133,17 @@ pub(crate) struct CargoCompeteConfig {
     #[serde(deserialize_with = "deserialize_liquid_template_with_custom_filter")]
     pub(crate) test_suite: liquid::Template,
     pub(crate) open: Option<String>,
+    /// Profile to use the cookies and tokens of, unless `--profile` or `$CARGO_COMPETE_PROFILE` is
+    /// given.
+    ///
+    /// The commands take it from `crate::profile::resolve` instead, since not all of them load
+    /// this struct.
+    pub(crate) profile: Option<String>,
+    /// Settings of the HTTP clients, taking precedence over `[http]` in the user-level config.
+    ///
+    /// Applied by `crate::web::http::init` before any command runs, for the same reason as
+    /// `profile`.
+    pub(crate) http: Option<crate::web::http::HttpConfig>,
     template: Option<CargoCompeteConfigTemplate>,
     #[serde(default)]
     pub(crate) new: CargoCompeteConfigNew,
@@ -132,6 +152,10 @@ pub(crate) struct CargoCompeteConfig {
     pub(crate) test: CargoCompeteConfigTest,
     #[serde(default)]
     pub(crate) submit: CargoCompeteConfigSubmit,
+    #[serde(default)]
+    pub(crate) statement: CargoCompeteConfigStatement,
+    #[serde(default)]
+    pub(crate) hooks: CargoCompeteConfigHooks,
 }
 
 impl CargoCompeteConfig {
@@ -190,7 +214,7 @@ impl CargoCompeteConfig {
                 .unwrap_or_default();
 
             Ok(CargoCompeteConfigTemplate {
-                src,
+                src: CargoCompeteConfigTemplateSrc::Plain(src),
                 new: Some(CargoCompeteConfigTemplateNew {
                     edition: None,
                     profile,
@@ -208,10 +232,101 @@ impl CargoCompeteConfig {
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) struct CargoCompeteConfigTemplate {
-    pub(crate) src: String,
+    pub(crate) src: CargoCompeteConfigTemplateSrc,
     pub(crate) new: Option<CargoCompeteConfigTemplateNew>,
 }
 
+/// `template.src`. A string is written as it is, and a table has named Liquid templates.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub(crate) enum CargoCompeteConfigTemplateSrc {
+    Plain(String),
+    Named(IndexMap<String, String>),
+}
+
+impl CargoCompeteConfigTemplateSrc {
+    /// Renders the source file for a problem.
+    ///
+    /// Unless `name` is given, the named template is chosen from `interactive` (for interactive
+    /// problems), the name of the platform, and `default` in this order.
+    pub(crate) fn render(
+        &self,
+        name: Option<&str>,
+        bin_name: &str,
+        bin_alias: &str,
+        problem_url: &Url,
+        test_suite: &TestSuite,
+    ) -> anyhow::Result<String> {
+        let templates = match self {
+            Self::Plain(content) => {
+                if let Some(name) = name {
+                    bail!(
+                        "template `{}` not found. `template.src` is not a table of named templates",
+                        name,
+                    );
+                }
+                return Ok(content.clone());
+            }
+            Self::Named(templates) => templates,
+        };
+
+        let platform = PlatformKind::from_url(problem_url).ok();
+
+        let (name, template) = if let Some(name) = name {
+            let template = templates.get(name).with_context(|| {
+                format!(
+                    "template `{}` not found. available: [{}]",
+                    name,
+                    templates.keys().format(", "),
+                )
+            })?;
+            (name, template)
+        } else {
+            let interactive = matches!(test_suite, TestSuite::Interactive(_));
+            interactive
+                .then_some("interactive")
+                .into_iter()
+                .chain(platform.map(PlatformKind::to_kebab_case_str))
+                .chain(iter::once("default"))
+                .find_map(|name| Some((name, templates.get(name)?)))
+                .with_context(|| "`template.src` is a table, but it has no `default`")?
+        };
+
+        let contest = match platform {
+            Some(PlatformKind::Atcoder) => {
+                Some(snowchains_core::web::atcoder_contest_id(problem_url)?)
+            }
+            Some(PlatformKind::Codeforces) => {
+                Some(snowchains_core::web::codeforces_contest_id(problem_url)?.to_string())
+            }
+            _ => None,
+        };
+
+        let timelimit = match test_suite {
+            TestSuite::Batch(BatchTestSuite { timelimit, .. })
+            | TestSuite::Interactive(InteractiveTestSuite { timelimit }) => *timelimit,
+            TestSuite::Unsubmittable => None,
+        };
+
+        liquid_template_with_custom_filter(template)
+            .map_err(|e| anyhow!("{}", e))
+            .and_then(|template| {
+                template
+                    .render(&object!({
+                        "contest": contest,
+                        "bin_name": bin_name,
+                        "bin_alias": bin_alias,
+                        "problem_url": problem_url.as_str(),
+                        "platform": platform.map(PlatformKind::to_kebab_case_str),
+                        "timelimit": timelimit.map(|t| humantime::format_duration(t).to_string()),
+                        "timelimit_ms": timelimit.map(|t| t.as_millis() as i64),
+                    }))
+                    .map_err(Into::into)
+            })
+            .with_context(|| format!("could not render `template.src.{name}`"))
+    }
+}
+
 #[derive(Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) struct CargoCompeteConfigTemplateNew {
@@ -248,14 +363,16 @@ where
         .transpose()
 }
 
-#[derive(Derivative)]
+#[derive(Derivative, Default)]
 #[derivative(Debug)]
 pub(crate) enum CargoCompeteConfigNew {
+    #[default]
     None,
     CargoCompete {
         platform: PlatformKind,
         #[derivative(Debug = "ignore")]
         path: liquid::Template,
+        workspace: bool,
         template: Option<CargoCompeteConfigNewTemplate>,
     },
     OjApi {
@@ -263,6 +380,7 @@ pub(crate) enum CargoCompeteConfigNew {
         url: liquid::Template,
         #[derivative(Debug = "ignore")]
         path: liquid::Template,
+        workspace: bool,
         template: Option<CargoCompeteConfigNewTemplate>,
     },
 }
@@ -275,6 +393,14 @@ impl CargoCompeteConfigNew {
         }
     }
 
+    /// Whether to add the new packages to the workspace next to `compete.toml`.
+    pub(crate) fn workspace(&self) -> bool {
+        match *self {
+            Self::None => false,
+            Self::CargoCompete { workspace, .. } | Self::OjApi { workspace, .. } => workspace,
+        }
+    }
+
     fn template(&self) -> Option<&CargoCompeteConfigNewTemplate> {
         match self {
             Self::None => None,
@@ -295,33 +421,39 @@ impl<'de> Deserialize<'de> for CargoCompeteConfigNew {
                     CargoCompete {
                         platform,
                         path,
+                        workspace,
                         template,
                     },
                 ..
             } => Ok(Self::CargoCompete {
                 platform,
                 path,
+                workspace,
                 template,
             }),
             WithExplicitTag::OjApi {
                 url,
                 path,
+                workspace,
                 template,
                 ..
             } => Ok(Self::OjApi {
                 url,
                 path,
+                workspace,
                 template,
             }),
             WithExplicitTag::Other(value) => {
                 let CargoCompete {
                     platform,
                     path,
+                    workspace,
                     template,
                 } = value.try_into().map_err(D::Error::custom)?;
                 Ok(Self::CargoCompete {
                     platform,
                     path,
+                    workspace,
                     template,
                 })
             }
@@ -346,6 +478,8 @@ impl<'de> Deserialize<'de> for CargoCompeteConfigNew {
                 url: liquid::Template,
                 #[serde(deserialize_with = "deserialize_liquid_template_with_custom_filter")]
                 path: liquid::Template,
+                #[serde(default)]
+                workspace: bool,
                 template: Option<CargoCompeteConfigNewTemplate>,
             },
             Other(toml::Value),
@@ -357,6 +491,8 @@ impl<'de> Deserialize<'de> for CargoCompeteConfigNew {
             platform: PlatformKind,
             #[serde(deserialize_with = "deserialize_liquid_template_with_custom_filter")]
             path: liquid::Template,
+            #[serde(default)]
+            workspace: bool,
             template: Option<CargoCompeteConfigNewTemplate>,
         }
 
@@ -403,12 +539,6 @@ impl<'de> Deserialize<'de> for CargoCompeteConfigNew {
     }
 }
 
-impl Default for CargoCompeteConfigNew {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) struct CargoCompeteConfigNewTemplate {
@@ -516,39 +646,88 @@ impl fmt::Debug for CargoCompeteConfigAdd {
     }
 }
 
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Default, Copy, Clone, Debug)]
 pub(crate) enum BinLikeTargetKind {
+    #[default]
     #[serde(rename = "bin")]
     Bin,
     #[serde(rename = "example")]
     ExampleBin,
 }
 
-impl Default for BinLikeTargetKind {
-    fn default() -> Self {
-        Self::Bin
-    }
-}
-
 #[derive(Deserialize, Default, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) struct CargoCompeteConfigTest {
     pub(crate) toolchain: Option<String>,
     #[serde(default)]
     pub(crate) profile: CargoCompeteConfigTestProfile,
+    #[serde(default)]
+    pub(crate) prewarm: bool,
 }
 
-#[derive(Deserialize, Debug, Copy, Clone, PartialEq)]
+#[derive(Deserialize, Default, Debug, Copy, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) enum CargoCompeteConfigTestProfile {
+    #[default]
     Dev,
     Release,
 }
 
-impl Default for CargoCompeteConfigTestProfile {
-    fn default() -> Self {
-        Self::Dev
-    }
+/// Commands run before or after the commands. Each of them is a list of Liquid templates.
+#[derive(Deserialize, Default, Derivative)]
+#[derivative(Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct CargoCompeteConfigHooks {
+    #[derivative(Debug = "ignore")]
+    #[serde(default, deserialize_with = "deserialize_liquid_template_commands")]
+    pub(crate) post_new: Vec<Vec<liquid::Template>>,
+    #[derivative(Debug = "ignore")]
+    #[serde(default, deserialize_with = "deserialize_liquid_template_commands")]
+    pub(crate) post_add: Vec<Vec<liquid::Template>>,
+    #[derivative(Debug = "ignore")]
+    #[serde(default, deserialize_with = "deserialize_liquid_template_commands")]
+    pub(crate) pre_submit: Vec<Vec<liquid::Template>>,
+    #[derivative(Debug = "ignore")]
+    #[serde(default, deserialize_with = "deserialize_liquid_template_commands")]
+    pub(crate) post_submit: Vec<Vec<liquid::Template>>,
+}
+
+fn deserialize_liquid_template_commands<'de, D>(
+    deserializer: D,
+) -> Result<Vec<Vec<liquid::Template>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<Vec<String>>::deserialize(deserializer)?
+        .iter()
+        .map(|args| {
+            if args.is_empty() {
+                return Err(D::Error::custom("empty command"));
+            }
+            args.iter()
+                .map(|arg| liquid_template_with_custom_filter(arg).map_err(D::Error::custom))
+                .collect()
+        })
+        .collect()
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct CargoCompeteConfigStatement {
+    #[serde(default)]
+    pub(crate) save: bool,
+    #[serde(default)]
+    pub(crate) lang: CargoCompeteConfigStatementLang,
+}
+
+/// Language of the statements on AtCoder.
+#[derive(Deserialize, Default, Debug, Copy, Clone, PartialEq, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub(crate) enum CargoCompeteConfigStatementLang {
+    #[default]
+    En,
+    Ja,
 }
 
 #[derive(Debug)]
@@ -558,12 +737,37 @@ pub(crate) enum CargoCompeteConfigSubmit {
     DeprecatedTranspileCommand(CargoCompeteConfigSubmitCommand),
 }
 
+impl CargoCompeteConfigSubmit {
+    pub(crate) fn limits(&self) -> &CargoCompeteConfigSubmitLimits {
+        match self {
+            Self::File(CargoCompeteConfigSubmitFile { limits, .. })
+            | Self::Command(CargoCompeteConfigSubmitCommand { limits, .. })
+            | Self::DeprecatedTranspileCommand(CargoCompeteConfigSubmitCommand {
+                limits, ..
+            }) => limits,
+        }
+    }
+
+    pub(crate) fn interval(&self) -> Option<Duration> {
+        match self {
+            Self::File(CargoCompeteConfigSubmitFile { interval, .. })
+            | Self::Command(CargoCompeteConfigSubmitCommand { interval, .. })
+            | Self::DeprecatedTranspileCommand(CargoCompeteConfigSubmitCommand {
+                interval, ..
+            }) => *interval,
+        }
+    }
+}
+
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub(crate) struct CargoCompeteConfigSubmitFile {
     #[derivative(Debug = "ignore")]
     pub(crate) path: liquid::Template,
     pub(crate) language_id: Option<String>,
+    pub(crate) limits: CargoCompeteConfigSubmitLimits,
+    /// Between the submissions when submitting multiple problems at once.
+    pub(crate) interval: Option<Duration>,
 }
 
 #[derive(Derivative)]
@@ -572,6 +776,31 @@ pub(crate) struct CargoCompeteConfigSubmitCommand {
     #[derivative(Debug = "ignore")]
     pub(crate) args: Vec<liquid::Template>,
     pub(crate) language_id: Option<String>,
+    pub(crate) limits: CargoCompeteConfigSubmitLimits,
+    /// Between the submissions when submitting multiple problems at once.
+    pub(crate) interval: Option<Duration>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct CargoCompeteConfigSubmitLimits {
+    #[serde(default, deserialize_with = "deser_option_fromstr")]
+    pub(crate) source_size: Option<Size>,
+    #[serde(default, deserialize_with = "deserialize_option_regexes")]
+    pub(crate) forbidden_patterns: Option<Vec<Regex>>,
+}
+
+fn deserialize_option_regexes<'de, D>(deserializer: D) -> Result<Option<Vec<Regex>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Vec<String>>::deserialize(deserializer)?
+        .map(|ss| {
+            ss.iter()
+                .map(|s| Regex::new(s).map_err(D::Error::custom))
+                .collect()
+        })
+        .transpose()
 }
 
 impl Default for CargoCompeteConfigSubmit {
@@ -583,6 +812,8 @@ impl Default for CargoCompeteConfigSubmit {
                 .parse("{{ src_path }}")
                 .unwrap(),
             language_id: None,
+            limits: CargoCompeteConfigSubmitLimits::default(),
+            interval: None,
         })
     }
 }
@@ -594,17 +825,35 @@ impl<'de> Deserialize<'de> for CargoCompeteConfigSubmit {
     {
         let repr = Repr::deserialize(deserializer)?;
         return Ok(match repr {
-            Repr::CurrentForm(CurrentForm::File { path, language_id }) => {
-                Self::File(CargoCompeteConfigSubmitFile { path, language_id })
-            }
-            Repr::CurrentForm(CurrentForm::Command { args, language_id }) => {
-                Self::Command(CargoCompeteConfigSubmitCommand { args, language_id })
-            }
+            Repr::CurrentForm(CurrentForm::File {
+                path,
+                language_id,
+                limits,
+                interval,
+            }) => Self::File(CargoCompeteConfigSubmitFile {
+                path,
+                language_id,
+                limits,
+                interval,
+            }),
+            Repr::CurrentForm(CurrentForm::Command {
+                args,
+                language_id,
+                limits,
+                interval,
+            }) => Self::Command(CargoCompeteConfigSubmitCommand {
+                args,
+                language_id,
+                limits,
+                interval,
+            }),
             Repr::Deprecated(Deprecated {
                 transpile: DeprecatedSubmit::Command { args, language_id },
             }) => Self::DeprecatedTranspileCommand(CargoCompeteConfigSubmitCommand {
                 args,
                 language_id,
+                limits: CargoCompeteConfigSubmitLimits::default(),
+                interval: None,
             }),
         });
 
@@ -622,11 +871,19 @@ impl<'de> Deserialize<'de> for CargoCompeteConfigSubmit {
                 #[serde(deserialize_with = "deserialize_liquid_template")]
                 path: liquid::Template,
                 language_id: Option<String>,
+                #[serde(default)]
+                limits: CargoCompeteConfigSubmitLimits,
+                #[serde(default, with = "humantime_serde")]
+                interval: Option<Duration>,
             },
             Command {
                 #[serde(deserialize_with = "deserialize_liquid_templates")]
                 args: Vec<liquid::Template>,
                 language_id: Option<String>,
+                #[serde(default)]
+                limits: CargoCompeteConfigSubmitLimits,
+                #[serde(default, with = "humantime_serde")]
+                interval: Option<Duration>,
             },
         }
 
@@ -722,11 +979,19 @@ fn liquid_template_with_custom_filter(text: &str) -> Result<liquid::Template, St
 
 #[cfg(test)]
 mod tests {
-    use crate::{config::CargoCompeteConfig, web::ATCODER_RUST_LANG_ID};
+    use crate::{
+        config::{CargoCompeteConfig, CargoCompeteConfigHooks, CargoCompeteConfigTemplateSrc},
+        web::ATCODER_RUST_LANG_ID,
+    };
     use itertools::iproduct;
     use liquid::object;
     use pretty_assertions::assert_eq;
-    use snowchains_core::web::PlatformKind;
+    use serde::Deserialize;
+    use snowchains_core::{
+        testsuite::{BatchTestSuite, InteractiveTestSuite, Match, TestSuite},
+        web::PlatformKind,
+    };
+    use std::time::Duration;
 
     #[test]
     fn generate() -> anyhow::Result<()> {
@@ -756,6 +1021,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn submit_limits() -> anyhow::Result<()> {
+        let submit = toml::from_str::<super::CargoCompeteConfigSubmit>(
+            r#"kind = "file"
+path = "{{ src_path }}"
+limits = { source-size = "64KiB", forbidden-patterns = ['unsafe\s*\{'] }
+interval = "10s"
+"#,
+        )?;
+        let limits = submit.limits();
+        assert_eq!(Some("64KiB".parse()?), limits.source_size);
+        assert_eq!(
+            vec![r"unsafe\s*\{"],
+            limits
+                .forbidden_patterns
+                .iter()
+                .flatten()
+                .map(|r| r.as_str())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(Some(Duration::from_secs(10)), submit.interval());
+        Ok(())
+    }
+
     #[test]
     fn liquid_template_with_custom_filter() -> anyhow::Result<()> {
         let output = super::liquid_template_with_custom_filter("{{ s | kebabcase }}")
@@ -764,4 +1053,78 @@ mod tests {
         assert_eq!("foo-bar-baz", output);
         Ok(())
     }
+
+    #[test]
+    fn hooks() -> anyhow::Result<()> {
+        let hooks = toml::from_str::<CargoCompeteConfigHooks>(
+            r#"
+post-new = [["git", "add", "{{ manifest_dir }}"], ["echo", "{{ contest | kebabcase }}"]]
+pre-submit = [["cargo", "clippy", "--bin", "{{ bin_name }}"]]
+"#,
+        )?;
+
+        assert_eq!(2, hooks.post_new.len());
+        assert!(hooks.post_add.is_empty());
+        assert_eq!(1, hooks.pre_submit.len());
+        assert!(hooks.post_submit.is_empty());
+
+        let args = hooks.post_new[1]
+            .iter()
+            .map(|t| t.render(&object!({ "contest": "ABC100" })))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(["echo", "abc100"], *args);
+
+        assert!(toml::from_str::<CargoCompeteConfigHooks>("post-add = [[]]").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn template_src_named() -> anyhow::Result<()> {
+        let src = toml::from_str::<CargoCompeteTemplateSrcWrapper>(
+            r#"
+src.default = "// {{ bin_name }} ({{ bin_alias }})\n"
+src.atcoder = "// {{ contest }} {{ timelimit }} {{ timelimit_ms }}\n"
+src.interactive = "// {{ platform }} {{ problem_url }}\n"
+"#,
+        )?
+        .src;
+
+        let batch = TestSuite::Batch(BatchTestSuite {
+            timelimit: Some(Duration::from_secs(2)),
+            r#match: Match::Lines,
+            cases: vec![],
+            extend: vec![],
+        });
+        let interactive = TestSuite::Interactive(InteractiveTestSuite {
+            timelimit: Some(Duration::from_secs(2)),
+        });
+        let atcoder = &"https://atcoder.jp/contests/practice/tasks/practice_1".parse()?;
+        let codeforces = &"https://codeforces.com/contest/1000/problem/A".parse()?;
+
+        assert_eq!(
+            "// practice 2s 2000\n",
+            src.render(None, "practice-a", "a", atcoder, &batch)?,
+        );
+        assert_eq!(
+            "// codeforces https://codeforces.com/contest/1000/problem/A\n",
+            src.render(None, "1000-a", "a", codeforces, &interactive)?,
+        );
+        assert_eq!(
+            "// 1000-a (a)\n",
+            src.render(None, "1000-a", "a", codeforces, &batch)?,
+        );
+        assert_eq!(
+            "// 1000-a (a)\n",
+            src.render(Some("default"), "1000-a", "a", codeforces, &interactive)?,
+        );
+        assert!(src
+            .render(Some("nonexistent"), "1000-a", "a", codeforces, &batch)
+            .is_err());
+        return Ok(());
+
+        #[derive(Deserialize)]
+        struct CargoCompeteTemplateSrcWrapper {
+            src: CargoCompeteConfigTemplateSrc,
+        }
+    }
 }
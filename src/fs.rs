@@ -46,3 +46,19 @@ pub(crate) fn read_dir(path: impl AsRef<Path>) -> anyhow::Result<Vec<PathBuf>> {
         .collect::<Result<_, _>>()
         .with_context(|| format!("could not list files in `{}`", path.display()))
 }
+
+pub(crate) fn remove_file(path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    std::fs::remove_file(path).with_context(|| format!("could not remove `{}`", path.display()))
+}
+
+pub(crate) fn remove_dir_all(path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    std::fs::remove_dir_all(path).with_context(|| format!("could not remove `{}`", path.display()))
+}
+
+pub(crate) fn rename(from: impl AsRef<Path>, to: impl AsRef<Path>) -> anyhow::Result<()> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    std::fs::rename(from, to)
+        .with_context(|| format!("failed to move `{}` to `{}`", from.display(), to.display()))
+}
@@ -0,0 +1,43 @@
+use crate::{config::CargoCompeteConfigHooks, shell::Shell};
+use anyhow::Context as _;
+use camino::Utf8Path;
+use strum::Display;
+
+#[derive(Clone, Copy, Debug, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub(crate) enum Hook {
+    PostNew,
+    PostAdd,
+    PreSubmit,
+    PostSubmit,
+}
+
+/// Renders and runs the commands for `hooks.<hook>` in order. A failing command is an error.
+pub(crate) fn run(
+    hooks: &CargoCompeteConfigHooks,
+    hook: Hook,
+    vars: &liquid::Object,
+    cwd: &Utf8Path,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let commands = match hook {
+        Hook::PostNew => &hooks.post_new,
+        Hook::PostAdd => &hooks.post_add,
+        Hook::PreSubmit => &hooks.pre_submit,
+        Hook::PostSubmit => &hooks.post_submit,
+    };
+
+    for command in commands {
+        let args = command
+            .iter()
+            .map(|arg| arg.render(vars))
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("could not render `hooks.{hook}`"))?;
+
+        crate::process::with_which(&args[0], cwd)?
+            .args(&args[1..])
+            .exec_with_shell_status(shell)
+            .with_context(|| format!("`hooks.{hook}` failed"))?;
+    }
+    Ok(())
+}
@@ -4,25 +4,47 @@
 mod commands;
 mod config;
 mod fs;
+mod hooks;
 mod oj_api;
 mod open;
 mod process;
+mod profile;
 mod project;
 pub mod shell;
+mod submission_history;
 mod testing;
+mod user_config;
 mod web;
 
 use crate::{
     commands::{
-        add::OptCompeteAdd, init::OptCompeteInit, login::OptCompeteLogin,
-        migrate_cargo_atcoder::OptCompeteMigrateCargoAtcoder, new::OptCompeteNew,
-        open::OptCompeteOpen, participate::OptCompeteParticipate,
+        add::OptCompeteAdd,
+        contests::OptCompeteContests,
+        credentials::{
+            OptCompeteCredentialsList, OptCompeteCredentialsRemove, OptCompeteCredentialsSet,
+        },
+        init::OptCompeteInit,
+        languages::OptCompeteLanguages,
+        login::OptCompeteLogin,
+        logout::OptCompeteLogout,
+        migrate_cargo_atcoder::OptCompeteMigrateCargoAtcoder,
+        migrate_workspace::OptCompeteMigrateWorkspace,
+        new::OptCompeteNew,
+        open::OptCompeteOpen,
+        participate::OptCompeteParticipate,
+        remove::OptCompeteRemove,
         retrieve_submission_summaries::OptCompeteRetrieveSubmissionSummaries,
-        retrieve_testcases::OptCompeteRetrieveTestcases, submit::OptCompeteSubmit,
-        test::OptCompeteTest, watch_submissions::OptCompeteWatchSubmissions,
+        retrieve_testcases::OptCompeteRetrieveTestcases,
+        show::OptCompeteShow,
+        standings::OptCompeteStandings,
+        submit::OptCompeteSubmit,
+        test::OptCompeteTest,
+        watch_submissions::OptCompeteWatchSubmissions,
+        whoami::OptCompeteWhoami,
     },
     shell::Shell,
 };
+use anyhow::Context as _;
 use std::path::PathBuf;
 use structopt::{clap::AppSettings, StructOpt};
 
@@ -52,6 +74,22 @@ pub enum OptCompete {
     #[structopt(author, visible_alias("l"))]
     Login(OptCompeteLogin),
 
+    /// Remove the cookies and API keys for a platform
+    #[structopt(author)]
+    Logout(OptCompeteLogout),
+
+    /// Show which account you are logged in as
+    #[structopt(author)]
+    Whoami(OptCompeteWhoami),
+
+    /// List upcoming and running contests
+    #[structopt(author)]
+    Contests(OptCompeteContests),
+
+    /// Show the standings of a contest
+    #[structopt(author)]
+    Standings(OptCompeteStandings),
+
     /// Register to a contest
     #[structopt(author, visible_alias("p"))]
     Participate(OptCompeteParticipate),
@@ -63,6 +101,10 @@ pub enum OptCompete {
     #[structopt(author, visible_alias("a"))]
     Add(OptCompeteAdd),
 
+    /// Remove `bin`s/`example`s with their source files and test cases
+    #[structopt(author)]
+    Remove(OptCompeteRemove),
+
     /// Retrieve data
     #[structopt(author, visible_alias("r"))]
     Retrieve(OptCompeteRetrieve),
@@ -79,6 +121,10 @@ pub enum OptCompete {
     #[structopt(author, visible_alias("o"))]
     Open(OptCompeteOpen),
 
+    /// Show the saved statement, limits, and samples of a problem
+    #[structopt(author)]
+    Show(OptCompeteShow),
+
     /// Test your code
     #[structopt(author, visible_alias("t"))]
     Test(OptCompeteTest),
@@ -86,6 +132,14 @@ pub enum OptCompete {
     /// Submit your code
     #[structopt(author, visible_alias("s"))]
     Submit(OptCompeteSubmit),
+
+    /// List the available languages and suggest the newest Rust
+    #[structopt(author)]
+    Languages(OptCompeteLanguages),
+
+    /// Manage tokens for Dropbox, yukicoder, and Codeforces
+    #[structopt(author)]
+    Credentials(OptCompeteCredentials),
 }
 
 #[derive(StructOpt, Debug)]
@@ -93,6 +147,10 @@ pub enum OptCompeteMigrate {
     /// Migrate existing packages
     #[structopt(author, visible_alias("c"))]
     CargoAtcoder(OptCompeteMigrateCargoAtcoder),
+
+    /// Put the existing packages into a Cargo workspace
+    #[structopt(author, visible_alias("w"))]
+    Workspace(OptCompeteMigrateWorkspace),
 }
 
 #[derive(StructOpt, Debug)]
@@ -106,6 +164,21 @@ pub enum OptCompeteRetrieve {
     SubmissionSummaries(OptCompeteRetrieveSubmissionSummaries),
 }
 
+#[derive(StructOpt, Debug)]
+pub enum OptCompeteCredentials {
+    /// List the stored tokens
+    #[structopt(author)]
+    List(OptCompeteCredentialsList),
+
+    /// Store a token
+    #[structopt(author)]
+    Set(OptCompeteCredentialsSet),
+
+    /// Remove a token
+    #[structopt(author)]
+    Remove(OptCompeteCredentialsRemove),
+}
+
 #[derive(StructOpt, Debug)]
 pub enum OptCompeteWatch {
     /// Watch submissions
@@ -113,22 +186,154 @@ pub enum OptCompeteWatch {
     Submissions(OptCompeteWatchSubmissions),
 }
 
+impl OptCompete {
+    /// `--profile`. `None` for the commands that do not use the cookies or the tokens.
+    fn profile(&self) -> Option<Option<&str>> {
+        let profile = match self {
+            Self::Init(_) | Self::Migrate(_) | Self::Remove(_) | Self::Show(_) => return None,
+            Self::Login(opt) => &opt.profile,
+            Self::Logout(opt) => &opt.profile,
+            Self::Whoami(opt) => &opt.profile,
+            Self::Contests(opt) => &opt.profile,
+            Self::Standings(opt) => &opt.profile,
+            Self::Participate(opt) => &opt.profile,
+            Self::New(opt) => &opt.profile,
+            Self::Add(opt) => &opt.profile,
+            Self::Retrieve(OptCompeteRetrieve::Testcases(opt)) | Self::Download(opt) => {
+                &opt.profile
+            }
+            Self::Retrieve(OptCompeteRetrieve::SubmissionSummaries(opt)) => &opt.profile,
+            Self::Watch(OptCompeteWatch::Submissions(opt)) => &opt.profile,
+            Self::Open(opt) => &opt.profile,
+            Self::Test(opt) => &opt.profile,
+            Self::Submit(opt) => &opt.profile,
+            Self::Languages(opt) => &opt.profile,
+            Self::Credentials(OptCompeteCredentials::List(opt)) => &opt.profile,
+            Self::Credentials(OptCompeteCredentials::Set(opt)) => &opt.profile,
+            Self::Credentials(OptCompeteCredentials::Remove(opt)) => &opt.profile,
+        };
+        Some(profile.as_deref())
+    }
+
+    /// `--offline`. `false` for the commands that do not have it.
+    fn offline(&self) -> bool {
+        match self {
+            Self::New(opt) => opt.offline,
+            Self::Add(opt) => opt.offline,
+            Self::Retrieve(OptCompeteRetrieve::Testcases(opt)) | Self::Download(opt) => opt.offline,
+            Self::Test(opt) => opt.offline,
+            _ => false,
+        }
+    }
+
+    /// Name of the command if it always sends requests, which fails in offline mode.
+    fn requires_network(&self) -> Option<&'static str> {
+        match self {
+            Self::Login(_) => Some("login"),
+            Self::Whoami(_) => Some("whoami"),
+            Self::Contests(_) => Some("contests"),
+            Self::Standings(_) => Some("standings"),
+            Self::Participate(_) => Some("participate"),
+            Self::Retrieve(OptCompeteRetrieve::SubmissionSummaries(_)) => {
+                Some("retrieve submission-summaries")
+            }
+            Self::Watch(OptCompeteWatch::Submissions(_)) => Some("watch submissions"),
+            Self::Submit(_) => Some("submit"),
+            Self::Languages(_) => Some("languages"),
+            _ => None,
+        }
+    }
+}
+
 pub struct Context<'s> {
     pub cwd: PathBuf,
     pub cookies_path: PathBuf,
+    cache_dir: PathBuf,
+    replay_dir: Option<PathBuf>,
+    /// Resolved once in [`run`].
+    profile: Option<String>,
     pub shell: &'s mut Shell,
 }
 
+impl<'s> Context<'s> {
+    /// Creates a context that uses `{ cache directory }/cargo-compete` for the cache.
+    pub fn new(cwd: PathBuf, cookies_path: PathBuf, shell: &'s mut Shell) -> anyhow::Result<Self> {
+        let cache_dir = dirs_next::cache_dir()
+            .with_context(|| "could not find the cache directory")?
+            .join("cargo-compete");
+
+        Ok(Self {
+            cwd,
+            cookies_path,
+            cache_dir,
+            replay_dir: None,
+            profile: None,
+            shell,
+        })
+    }
+
+    /// Uses `cache_dir` for the cache instead.
+    pub fn cache_dir(self, cache_dir: PathBuf) -> Self {
+        Self { cache_dir, ..self }
+    }
+
+    /// Replays the responses recorded in `replay_dir` with `$CARGO_COMPETE_RECORD_DIR` instead of
+    /// sending requests.
+    pub fn replay_dir(self, replay_dir: PathBuf) -> Self {
+        Self {
+            replay_dir: Some(replay_dir),
+            ..self
+        }
+    }
+
+    /// Selects the profile, and uses the cookies and the tokens of it.
+    fn with_profile(self, cli_opt: Option<&str>) -> anyhow::Result<Self> {
+        let profile = profile::resolve(cli_opt, &self.cwd)?;
+        let cookies_path = match &profile {
+            Some(profile) => profile::cookies_path(&self.cookies_path, profile),
+            None => self.cookies_path,
+        };
+        Ok(Self {
+            cookies_path,
+            profile,
+            ..self
+        })
+    }
+}
+
 pub fn run(opt: OptCompete, ctx: Context<'_>) -> anyhow::Result<()> {
+    if let Some(command) = opt.requires_network() {
+        if web::cache::is_offline(opt.offline()) {
+            anyhow::bail!("`{}` is not available in offline mode", command);
+        }
+    }
+
+    web::replay::init(ctx.replay_dir.clone());
+
+    web::http::init(&ctx.cwd)?;
+
+    let ctx = match opt.profile() {
+        Some(profile) => ctx.with_profile(profile)?,
+        None => ctx,
+    };
+
     match opt {
         OptCompete::Init(opt) => commands::init::run(opt, ctx),
         OptCompete::Migrate(OptCompeteMigrate::CargoAtcoder(opt)) => {
             commands::migrate_cargo_atcoder::run(opt, ctx)
         }
+        OptCompete::Migrate(OptCompeteMigrate::Workspace(opt)) => {
+            commands::migrate_workspace::run(opt, ctx)
+        }
         OptCompete::Login(opt) => commands::login::run(opt, ctx),
+        OptCompete::Logout(opt) => commands::logout::run(opt, ctx),
+        OptCompete::Whoami(opt) => commands::whoami::run(opt, ctx),
+        OptCompete::Contests(opt) => commands::contests::run(opt, ctx),
+        OptCompete::Standings(opt) => commands::standings::run(opt, ctx),
         OptCompete::Participate(opt) => commands::participate::run(opt, ctx),
         OptCompete::New(opt) => commands::new::run(opt, ctx),
         OptCompete::Add(opt) => commands::add::run(opt, ctx),
+        OptCompete::Remove(opt) => commands::remove::run(opt, ctx),
         OptCompete::Retrieve(OptCompeteRetrieve::Testcases(opt)) | OptCompete::Download(opt) => {
             commands::retrieve_testcases::run(opt, ctx)
         }
@@ -139,7 +344,18 @@ pub fn run(opt: OptCompete, ctx: Context<'_>) -> anyhow::Result<()> {
             commands::watch_submissions::run(opt, ctx)
         }
         OptCompete::Open(opt) => commands::open::run(opt, ctx),
+        OptCompete::Show(opt) => commands::show::run(opt, ctx),
         OptCompete::Test(opt) => commands::test::run(opt, ctx),
         OptCompete::Submit(opt) => commands::submit::run(opt, ctx),
+        OptCompete::Languages(opt) => commands::languages::run(opt, ctx),
+        OptCompete::Credentials(OptCompeteCredentials::List(opt)) => {
+            commands::credentials::run_list(opt, ctx)
+        }
+        OptCompete::Credentials(OptCompeteCredentials::Set(opt)) => {
+            commands::credentials::run_set(opt, ctx)
+        }
+        OptCompete::Credentials(OptCompeteCredentials::Remove(opt)) => {
+            commands::credentials::run_remove(opt, ctx)
+        }
     }
 }
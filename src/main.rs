@@ -20,13 +20,7 @@ fn main() {
             .join("cargo-compete")
             .join("cookies.jsonl");
 
-        let ctx = Context {
-            cwd,
-            cookies_path,
-            shell: &mut shell,
-        };
-
-        cargo_compete::run(opt, ctx)
+        cargo_compete::run(opt, Context::new(cwd, cookies_path, &mut shell)?)
     })();
 
     if let Err(err) = result {
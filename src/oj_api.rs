@@ -1,33 +1,35 @@
-use crate::shell::Shell;
-use anyhow::{bail, Context as _};
+use crate::{shell::Shell, web::cache::ProblemCache};
+use anyhow::Context as _;
 use camino::Utf8Path;
 use itertools::Itertools as _;
 use serde::{
     de::{DeserializeOwned, Error as _},
     Deserialize, Deserializer,
 };
-use std::{env, ffi::OsStr, path::Path};
+use std::{env, ffi::OsStr, fmt, iter, path::Path};
 use url::Url;
 
 pub(crate) fn get_problem(
     url: &Url,
     system: bool,
     cwd: &Utf8Path,
+    cache: Option<&ProblemCache>,
     shell: &mut Shell,
 ) -> anyhow::Result<Problem> {
     let args = &mut vec!["get-problem", url.as_ref()];
     if system {
         args.push("--system".as_ref());
     }
-    call(args, cwd, shell)
+    call(args, cwd, cache, shell)
 }
 
 pub(crate) fn get_contest(
     url: &Url,
     cwd: &Utf8Path,
+    cache: Option<&ProblemCache>,
     shell: &mut Shell,
 ) -> anyhow::Result<Vec<(Url, Option<String>)>> {
-    let Contest { problems } = call(&["get-contest", url.as_ref()], cwd, shell)?;
+    let Contest { problems } = call(&["get-contest", url.as_ref()], cwd, cache, shell)?;
     return Ok(problems
         .into_iter()
         .map(|ContestProblem { url, context }| (url, context.alphabet))
@@ -50,6 +52,13 @@ pub(crate) fn get_contest(
     }
 }
 
+/// Whether `oj-api` failed because it does not support the URL, such as `get-contest` for a
+/// problem.
+pub(crate) fn is_unsupported_url(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<ReturnedError>()
+        .is_some_and(|err| err.messages.iter().any(|m| m.contains("unsupported URL")))
+}
+
 pub(crate) fn guess_language_id(
     url: &Url,
     file: &Path,
@@ -64,6 +73,7 @@ pub(crate) fn guess_language_id(
             file.as_ref(),
         ],
         cwd,
+        None,
         shell,
     )
     .map(|GuessLanguageId { id }| id);
@@ -91,6 +101,7 @@ pub(crate) fn submit_code(
             language.as_ref(),
         ],
         cwd,
+        None,
         shell,
     )
     .map(|SubmitCode { url }| url);
@@ -101,21 +112,41 @@ pub(crate) fn submit_code(
     }
 }
 
+/// Only the successful results are cached.
 fn call<T: DeserializeOwned, S: AsRef<OsStr>>(
     args: &[S],
     cwd: &Utf8Path,
+    cache: Option<&ProblemCache>,
     shell: &mut Shell,
 ) -> anyhow::Result<T> {
+    let result = if let Some(cache) = cache {
+        let key = iter::once("oj-api".into())
+            .chain(args.iter().map(|a| a.as_ref().to_string_lossy()))
+            .join(" ");
+        cache.get_or_retrieve(&key, || exec(args, cwd, shell))?
+    } else {
+        exec(args, cwd, shell)?
+    };
+
+    serde_json::from_value(result).with_context(|| "could not parse the output from `oj-api`")
+}
+
+fn exec<S: AsRef<OsStr>>(
+    args: &[S],
+    cwd: &Utf8Path,
+    shell: &mut Shell,
+) -> anyhow::Result<serde_json::Value> {
     let oj_api_exe = which::which_in("oj-api", env::var_os("PATH"), cwd)
         .with_context(|| "`oj-api` not found")?;
 
     let output = crate::process::process(oj_api_exe)
         .args(args)
+        .envs(crate::web::http::env_vars())
         .cwd(cwd)
         .display_cwd()
         .read_with_shell_status(shell)?;
 
-    let Outcome { result, messages } = serde_json::from_str(&output)
+    let Outcome::<serde_json::Value> { result, messages } = serde_json::from_str(&output)
         .with_context(|| "could not parse the output from `oj-api`")?;
 
     return if let Ok(result) = result {
@@ -124,10 +155,7 @@ fn call<T: DeserializeOwned, S: AsRef<OsStr>>(
         }
         Ok(result)
     } else {
-        bail!(
-            "`oj-api` returned error:\n{}",
-            messages.iter().map(|s| format!("- {s}\n")).join(""),
-        );
+        Err(ReturnedError { messages }.into())
     };
 
     struct Outcome<T> {
@@ -170,6 +198,24 @@ fn call<T: DeserializeOwned, S: AsRef<OsStr>>(
     }
 }
 
+/// `"status": "error"`.
+#[derive(Debug)]
+struct ReturnedError {
+    messages: Vec<String>,
+}
+
+impl fmt::Display for ReturnedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "`oj-api` returned error:")?;
+        for message in &self.messages {
+            writeln!(f, "- {message}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ReturnedError {}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct Problem {
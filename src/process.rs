@@ -18,6 +18,7 @@ pub(crate) struct ProcessBuilder<C: Presence<Utf8PathBuf>> {
     cwd: C::Value,
     display_cwd: bool,
     pipe_input: Option<Vec<u8>>,
+    envs: Vec<(OsString, OsString)>,
 }
 
 impl<C: Presence<Utf8PathBuf>> ProcessBuilder<C> {
@@ -38,6 +39,7 @@ impl<C: Presence<Utf8PathBuf>> ProcessBuilder<C> {
             cwd: cwd.as_ref().to_owned(),
             display_cwd: self.display_cwd,
             pipe_input: self.pipe_input,
+            envs: self.envs,
         }
     }
 
@@ -48,6 +50,17 @@ impl<C: Presence<Utf8PathBuf>> ProcessBuilder<C> {
         }
     }
 
+    pub(crate) fn envs(
+        mut self,
+        envs: impl IntoIterator<Item = (impl AsRef<OsStr>, impl AsRef<OsStr>)>,
+    ) -> Self {
+        self.envs.extend(
+            envs.into_iter()
+                .map(|(k, v)| (k.as_ref().to_owned(), v.as_ref().to_owned())),
+        );
+        self
+    }
+
     pub(crate) fn pipe_input(mut self, pipe_input: Option<impl Into<Vec<u8>>>) -> Self {
         self.pipe_input = pipe_input.map(Into::into);
         self
@@ -81,6 +94,24 @@ impl ProcessBuilder<Present> {
         String::from_utf8(stdout).with_context(|| "non UTF-8 output")
     }
 
+    /// Spawns the process without waiting for it, writing the stdout and the stderr to `log_path`.
+    pub(crate) fn spawn_with_log(
+        &self,
+        log_path: &Utf8Path,
+    ) -> anyhow::Result<std::process::Child> {
+        let log = std::fs::File::create(log_path)
+            .with_context(|| format!("could not create `{log_path}`"))?;
+        std::process::Command::new(&self.program)
+            .args(&self.args)
+            .current_dir(&self.cwd)
+            .envs(self.envs.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .spawn()
+            .with_context(|| format!("could not execute {self}"))
+    }
+
     pub(crate) fn read_with_shell_status(&self, shell: &mut Shell) -> anyhow::Result<String> {
         shell.status("Running", self)?;
         self.read()
@@ -90,6 +121,7 @@ impl ProcessBuilder<Present> {
         let mut child = std::process::Command::new(&self.program)
             .args(&self.args)
             .current_dir(&self.cwd)
+            .envs(self.envs.iter().map(|(k, v)| (k, v)))
             .stdin(if self.pipe_input.is_some() {
                 Stdio::piped()
             } else {
@@ -152,6 +184,7 @@ pub(crate) fn process(program: impl AsRef<Path>) -> ProcessBuilder<NotPresent> {
         cwd: (),
         display_cwd: false,
         pipe_input: None,
+        envs: vec![],
     }
 }
 
@@ -168,6 +201,7 @@ pub(crate) fn with_which(
         cwd,
         display_cwd: false,
         pipe_input: None,
+        envs: vec![],
     })
 }
 
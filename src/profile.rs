@@ -0,0 +1,87 @@
+use anyhow::{bail, Context as _};
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+/// Selects a profile with `--profile`, `$CARGO_COMPETE_PROFILE`, or `profile` in the
+/// `compete.toml` found in `cwd` or its parents, in this order.
+pub(crate) fn resolve(cli_opt: Option<&str>, cwd: &Path) -> anyhow::Result<Option<String>> {
+    let profile = if let Some(profile) = cli_opt {
+        Some(profile.to_owned())
+    } else if let Some(profile) = env::var_os("CARGO_COMPETE_PROFILE") {
+        Some(
+            profile
+                .into_string()
+                .map_err(|_| anyhow::anyhow!("$CARGO_COMPETE_PROFILE is not valid UTF-8"))?,
+        )
+    } else {
+        from_config(cwd)?
+    };
+
+    if let Some(profile) = &profile {
+        if profile.is_empty()
+            || !profile
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            bail!(
+                "invalid profile name: {:?}. use only ASCII alphanumerics, `-`, and `_`",
+                profile,
+            );
+        }
+    }
+    Ok(profile)
+}
+
+/// Reads only `profile` so that the commands not for a package work with a broken
+/// `compete.toml`.
+fn from_config(cwd: &Path) -> anyhow::Result<Option<String>> {
+    let path = match cwd
+        .ancestors()
+        .map(|p| p.join("compete.toml"))
+        .find(|p| p.exists())
+    {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+
+    let config = crate::fs::read_to_string(&path)?
+        .parse::<toml::Value>()
+        .with_context(|| format!("could not read a TOML file at `{}`", path.display()))?;
+
+    match config.get("profile") {
+        None => Ok(None),
+        Some(toml::Value::String(profile)) => Ok(Some(profile.clone())),
+        Some(_) => bail!("`profile` in `{}` must be a string", path.display()),
+    }
+}
+
+/// `{ cookies directory }/profiles/{ profile }/cookies.jsonl`.
+///
+/// The tokens and `submissions.jsonl` are placed next to the cookies.
+pub(crate) fn cookies_path(default: &Path, profile: &str) -> PathBuf {
+    let file_name = default
+        .file_name()
+        .unwrap_or_else(|| "cookies.jsonl".as_ref());
+    default
+        .with_file_name("profiles")
+        .join(profile)
+        .join(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+
+    #[test]
+    fn cookies_path() {
+        let default = Path::new("/home/user/.local/share/cargo-compete/cookies.jsonl");
+        let path = super::cookies_path(default, "practice");
+        assert_eq!(
+            Path::new("/home/user/.local/share/cargo-compete/profiles/practice/cookies.jsonl"),
+            path,
+        );
+    }
+}
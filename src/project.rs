@@ -24,6 +24,8 @@ pub(crate) struct PackageMetadataCargoCompete {
     pub(crate) bin: IndexMap<String, PackageMetadataCargoCompeteBinExample>,
     #[serde(default, deserialize_with = "deserialize_bin_example")]
     pub(crate) example: IndexMap<String, PackageMetadataCargoCompeteBinExample>,
+    /// Set by `new --virtual`.
+    pub(crate) virtual_contest: Option<Url>,
 }
 
 fn deserialize_bin_example<'de, D>(
@@ -42,6 +44,7 @@ where
                     name,
                     alias,
                     problem,
+                    language_id,
                 },
             )| {
                 let (name, alias) = if let Some(alias) = alias {
@@ -53,7 +56,11 @@ where
                 };
                 (
                     name,
-                    PackageMetadataCargoCompeteBinExample { alias, problem },
+                    PackageMetadataCargoCompeteBinExample {
+                        alias,
+                        problem,
+                        language_id,
+                    },
                 )
             },
         )
@@ -66,6 +73,7 @@ where
         alias: Option<String>,
         #[serde(deserialize_with = "deserialize_bin_problem")]
         problem: Url,
+        language_id: Option<String>,
     }
 
     fn deserialize_bin_problem<'de, D>(deserializer: D) -> Result<Url, D::Error>
@@ -112,6 +120,8 @@ impl PackageMetadataCargoCompete {
 pub(crate) struct PackageMetadataCargoCompeteBinExample {
     pub(crate) alias: String,
     pub(crate) problem: Url,
+    /// Overrides `submit.language_id` in `compete.toml`.
+    pub(crate) language_id: Option<String>,
 }
 
 #[ext(MetadataExt)]
@@ -317,6 +327,96 @@ pub(crate) fn set_cargo_config_build_target_dir(
     Ok(())
 }
 
+/// Adds `members` (and `exclude`) to `workspace.members` (and `workspace.exclude`) of `Cargo.toml` in
+/// `root_dir`, creating it if it does not exist. The arrays are kept sorted.
+pub(crate) fn add_workspace_members(
+    root_dir: &Utf8Path,
+    members: &[Utf8PathBuf],
+    exclude: &[Utf8PathBuf],
+    profile: Option<&toml_edit::Table>,
+    resolver: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let manifest_path = root_dir.join("Cargo.toml");
+    let is_new = !manifest_path.exists();
+
+    let mut manifest = if !is_new {
+        crate::fs::read_to_string(&manifest_path)?
+    } else {
+        "[workspace]\n".to_owned()
+    }
+    .parse::<toml_edit::Document>()
+    .with_context(|| format!("could not parse the manifest at `{manifest_path}`"))?;
+
+    if manifest.get("workspace").is_none() {
+        manifest["workspace"] = toml_edit::Item::Table(toml_edit::Table::new());
+    }
+    let workspace = manifest["workspace"]
+        .as_table_mut()
+        .with_context(|| format!("`workspace` is not a table: {manifest_path}"))?;
+
+    for (key, paths) in [("members", members), ("exclude", exclude)] {
+        if paths.is_empty() {
+            continue;
+        }
+
+        let mut values = workspace
+            .get(key)
+            .and_then(|m| m.as_array())
+            .map(|m| {
+                m.iter()
+                    .flat_map(|m| m.as_str())
+                    .map(ToOwned::to_owned)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        for path in paths {
+            let path = path
+                .strip_prefix(root_dir)
+                .with_context(|| format!("`{path}` is not under `{root_dir}`"))?
+                .components()
+                .join("/");
+            if !values.contains(&path) {
+                values.push(path);
+            }
+        }
+        values.sort();
+
+        workspace[key] = toml_edit::value(
+            format!(
+                "[\n{}]",
+                values
+                    .iter()
+                    .map(|v| format!("    {},\n", toml_edit::Value::from(&**v)))
+                    .join(""),
+            )
+            .parse::<toml_edit::Value>()?,
+        );
+    }
+
+    if let Some(resolver) = resolver {
+        if workspace.get("resolver").is_none() {
+            workspace["resolver"] = toml_edit::value(resolver);
+        }
+    }
+
+    if let Some(profile) = profile {
+        if manifest.get("profile").is_none() && !profile.is_empty() {
+            let mut profile = profile.clone();
+            profile.set_implicit(true);
+            manifest["profile"] = toml_edit::Item::Table(profile);
+        }
+    }
+
+    crate::fs::write(&manifest_path, manifest.to_string())?;
+    shell.status(
+        if is_new { "Created" } else { "Updated" },
+        format!("workspace at `{manifest_path}`"),
+    )?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::project::{PackageMetadataCargoCompete, PackageMetadataCargoCompeteBinExample};
@@ -334,15 +434,18 @@ mod tests {
                     problem: "https://atcoder.jp/contests/practice/tasks/practice_1"
                         .parse()
                         .unwrap(),
+                    language_id: None,
                 },
                 "practice-b".to_owned() => PackageMetadataCargoCompeteBinExample {
                     alias: "b".to_owned(),
                     problem: "https://atcoder.jp/contests/practice/tasks/practice_2"
                         .parse()
                         .unwrap(),
+                    language_id: Some("5054".to_owned()),
                 },
             ),
             example: indexmap!(),
+            virtual_contest: None,
         };
 
         assert_eq!(
@@ -350,7 +453,7 @@ mod tests {
             toml! {
                 [bin]
                 practice-a = { alias = "a", problem = "https://atcoder.jp/contests/practice/tasks/practice_1" }
-                practice-b = { alias = "b", problem = "https://atcoder.jp/contests/practice/tasks/practice_2" }
+                practice-b = { alias = "b", problem = "https://atcoder.jp/contests/practice/tasks/practice_2", language-id = "5054" }
             }
             .try_into::<PackageMetadataCargoCompete>()?,
         );
@@ -361,9 +464,11 @@ mod tests {
                 "aplusb".to_owned() => PackageMetadataCargoCompeteBinExample {
                     alias: "aplusb".to_owned(),
                     problem: "https://judge.yosupo.jp/problem/aplusb".parse().unwrap(),
+                    language_id: None,
                 },
             ),
             example: indexmap!(),
+            virtual_contest: None,
         };
 
         assert_eq!(
@@ -382,8 +487,10 @@ mod tests {
                 "aplusb".to_owned() => PackageMetadataCargoCompeteBinExample {
                     alias: "aplusb".to_owned(),
                     problem: "https://judge.yosupo.jp/problem/aplusb".parse().unwrap(),
+                    language_id: None,
                 },
             ),
+            virtual_contest: None,
         };
 
         assert_eq!(
@@ -1,4 +1,5 @@
 use indicatif::ProgressDrawTarget;
+use reqwest::StatusCode;
 use snowchains_core::{color_spec, web::StatusCodeColor};
 use std::{
     fmt,
@@ -11,6 +12,7 @@ pub struct Shell {
     input: ShellIn,
     output: ShellOut,
     needs_clear: bool,
+    last_status: Option<StatusCode>,
 }
 
 impl Shell {
@@ -19,6 +21,7 @@ impl Shell {
             input: ShellIn::stdin(),
             output: ShellOut::stream(),
             needs_clear: false,
+            last_status: None,
         }
     }
 
@@ -27,6 +30,7 @@ impl Shell {
             input: ShellIn::Reader(rdr),
             output: ShellOut::Write(NoColor::new(wtr)),
             needs_clear: false,
+            last_status: None,
         }
     }
 
@@ -38,7 +42,7 @@ impl Shell {
         }
     }
 
-    pub(crate) fn out(&mut self) -> &mut dyn Write {
+    pub(crate) fn out(&mut self) -> &mut dyn WriteColor {
         self.output.stdout()
     }
 
@@ -46,6 +50,21 @@ impl Shell {
         self.output.stderr()
     }
 
+    /// Clears the screen to redraw the output. Does nothing if stdout is not a TTY.
+    pub(crate) fn clear_out(&mut self) -> io::Result<()> {
+        if self.output.stdout_tty() {
+            let stdout = self.out();
+            stdout.write_all(b"\x1b[2J\x1b[H")?;
+            stdout.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Status of the last response that `snowchains_core` received, which is not in its errors.
+    pub(crate) fn take_last_status(&mut self) -> Option<StatusCode> {
+        self.last_status.take()
+    }
+
     pub(crate) fn set_color_choice(&mut self, color: ColorChoice) {
         self.output.set_color_choice(color);
     }
@@ -175,9 +194,10 @@ impl snowchains_core::web::Shell for Shell {
 
     fn on_response(
         &mut self,
-        _: &reqwest::blocking::Response,
+        res: &reqwest::blocking::Response,
         _: StatusCodeColor,
     ) -> io::Result<()> {
+        self.last_status = Some(res.status());
         if self.needs_clear {
             self.err_erase_line();
         }
@@ -223,6 +243,7 @@ enum ShellOut {
     Stream {
         stdout: BufferedStandardStream,
         stderr: BufferedStandardStream,
+        stdout_tty: bool,
         stderr_tty: bool,
     },
 }
@@ -240,11 +261,12 @@ impl ShellOut {
             } else {
                 termcolor::ColorChoice::Never
             }),
+            stdout_tty: atty::is(atty::Stream::Stdout),
             stderr_tty: atty::is(atty::Stream::Stderr),
         }
     }
 
-    fn stdout(&mut self) -> &mut dyn Write {
+    fn stdout(&mut self) -> &mut dyn WriteColor {
         match self {
             Self::Write(wtr) => wtr,
             Self::Stream { stdout, .. } => stdout,
@@ -258,6 +280,13 @@ impl ShellOut {
         }
     }
 
+    fn stdout_tty(&self) -> bool {
+        match *self {
+            Self::Write(_) => false,
+            Self::Stream { stdout_tty, .. } => stdout_tty,
+        }
+    }
+
     fn stderr_tty(&self) -> bool {
         match *self {
             Self::Write(_) => false,
@@ -0,0 +1,85 @@
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use std::{fs::OpenOptions, io::Write as _, path::Path};
+use url::Url;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub(crate) struct Entry {
+    pub(crate) problem: Url,
+    pub(crate) sha256: String,
+    pub(crate) submission: Option<Url>,
+    pub(crate) verdict: Option<String>,
+}
+
+impl Entry {
+    pub(crate) fn is_accepted(&self) -> bool {
+        self.verdict.as_deref() == Some("AC")
+    }
+}
+
+pub(crate) fn sha256(code: &str) -> String {
+    format!("{:x}", Sha256::digest(code.as_bytes()))
+}
+
+pub(crate) fn load(path: &Path) -> anyhow::Result<Vec<Entry>> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    crate::fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .with_context(|| format!("could not parse a line in `{}`", path.display()))
+        })
+        .collect()
+}
+
+pub(crate) fn save(path: &Path, entries: &[Entry]) -> anyhow::Result<()> {
+    let content = entries
+        .iter()
+        .map(|entry| Ok(serde_json::to_string(entry)? + "\n"))
+        .collect::<anyhow::Result<String>>()?;
+    crate::fs::write(path, content)
+}
+
+pub(crate) fn append(path: &Path, entry: &Entry) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        crate::fs::create_dir_all(parent)?;
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", serde_json::to_string(entry)?))
+        .with_context(|| format!("could not write `{}`", path.display()))
+}
+
+/// Whether the verdict will not change anymore. (e.g. `"WJ"` and `"3/10 WA"` are not final)
+pub(crate) fn is_final_verdict(verdict: &str) -> bool {
+    !(["WJ", "WR"].contains(&verdict) || verdict.chars().next().is_some_and(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn sha256() {
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            super::sha256(""),
+        );
+    }
+
+    #[test]
+    fn is_final_verdict() {
+        assert!(super::is_final_verdict("AC"));
+        assert!(super::is_final_verdict("WA"));
+        assert!(!super::is_final_verdict("WJ"));
+        assert!(!super::is_final_verdict("3/10 WA"));
+    }
+}
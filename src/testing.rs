@@ -30,7 +30,10 @@ pub(crate) struct Args<'a> {
     pub(crate) release: bool,
     pub(crate) test_case_names: Option<HashSet<String>>,
     pub(crate) display_limit: Size,
+    pub(crate) offline: bool,
     pub(crate) cookies_path: &'a Path,
+    pub(crate) profile: Option<&'a str>,
+    pub(crate) cache_dir: &'a Path,
     pub(crate) shell: &'a mut Shell,
 }
 
@@ -46,7 +49,10 @@ pub(crate) fn test(args: Args<'_>) -> anyhow::Result<()> {
         release,
         test_case_names,
         display_limit,
+        offline,
         cookies_path,
+        profile,
+        cache_dir,
         shell,
     } = args;
 
@@ -95,8 +101,11 @@ pub(crate) fn test(args: Args<'_>) -> anyhow::Result<()> {
                 if !system_test_cases_dir.join("in").exists() {
                     crate::web::retrieve_testcases::dl_only_system_test_cases(
                         problem_url,
+                        offline,
                         cookies_path,
+                        profile,
                         &metadata.workspace_root,
+                        cache_dir,
                         shell,
                     )?;
                 }
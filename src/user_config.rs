@@ -0,0 +1,235 @@
+//! User-level configuration at `$XDG_CONFIG_HOME/cargo-compete/config.toml` (or the equivalent on
+//! other platforms), which is shared by all of the workspaces.
+
+use crate::web::http::HttpConfig;
+use anyhow::Context as _;
+use serde::Deserialize;
+use snowchains_core::web::PlatformKind;
+use std::{collections::BTreeMap, path::PathBuf};
+use strum::{EnumString, EnumVariantNames};
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct UserConfig {
+    #[serde(default)]
+    pub(crate) credentials: UserConfigCredentials,
+    #[serde(default)]
+    pub(crate) http: HttpConfig,
+    #[serde(default)]
+    pub(crate) standings: UserConfigStandings,
+    /// `[profiles.<name>.credentials]` for `--profile <name>`.
+    #[serde(default)]
+    pub(crate) profiles: BTreeMap<String, UserConfigProfile>,
+}
+
+impl UserConfig {
+    /// The logins in `credentials` are only for the default profile, so that a profile does not
+    /// login with the main account.
+    pub(crate) fn logins(mut self, profile: Option<&str>) -> UserConfigLogins {
+        match profile {
+            None => self.credentials.logins,
+            Some(profile) => self
+                .profiles
+                .remove(profile)
+                .map(|p| p.credentials)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct UserConfigCredentials {
+    /// Overridden by `$CARGO_COMPETE_CREDENTIAL_STORE`.
+    #[serde(default)]
+    pub(crate) store: CredentialStoreKind,
+    #[serde(flatten)]
+    pub(crate) logins: UserConfigLogins,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct UserConfigProfile {
+    #[serde(default)]
+    pub(crate) credentials: UserConfigLogins,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct UserConfigLogins {
+    /// Overridden by `$ATCODER_USERNAME` and `$ATCODER_PASSWORD`.
+    #[serde(default)]
+    pub(crate) atcoder: UserConfigLogin,
+    /// Overridden by `$CODEFORCES_USERNAME` and `$CODEFORCES_PASSWORD`.
+    #[serde(default)]
+    pub(crate) codeforces: UserConfigLogin,
+    /// Overridden by `$AOJ_USERNAME` and `$AOJ_PASSWORD`.
+    #[serde(default)]
+    pub(crate) aoj: UserConfigLogin,
+}
+
+impl UserConfigLogins {
+    pub(crate) fn login(self, platform: PlatformKind) -> UserConfigLogin {
+        match platform {
+            PlatformKind::Atcoder => self.atcoder,
+            PlatformKind::Codeforces => self.codeforces,
+            PlatformKind::Yukicoder => UserConfigLogin::default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct UserConfigLogin {
+    pub(crate) username: Option<String>,
+    pub(crate) password_command: Option<PasswordCommand>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct UserConfigStandings {
+    #[serde(default)]
+    pub(crate) atcoder: UserConfigStandingsUsers,
+    #[serde(default)]
+    pub(crate) codeforces: UserConfigStandingsUsers,
+    #[serde(default)]
+    pub(crate) yukicoder: UserConfigStandingsUsers,
+}
+
+impl UserConfigStandings {
+    pub(crate) fn users(self, platform: PlatformKind) -> UserConfigStandingsUsers {
+        match platform {
+            PlatformKind::Atcoder => self.atcoder,
+            PlatformKind::Codeforces => self.codeforces,
+            PlatformKind::Yukicoder => self.yukicoder,
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct UserConfigStandingsUsers {
+    /// Defaults to the user logged in with the cookies. Required for yukicoder.
+    pub(crate) username: Option<String>,
+    #[serde(default)]
+    pub(crate) friends: Vec<String>,
+}
+
+/// A string is run with `sh -c` (`cmd /C` on Windows). An array is run as it is.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub(crate) enum PasswordCommand {
+    Shell(String),
+    Args(Vec<String>),
+}
+
+#[derive(Deserialize, EnumString, EnumVariantNames, Default, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub(crate) enum CredentialStoreKind {
+    /// JSON files in `$XDG_DATA_HOME/cargo-compete/tokens`.
+    #[default]
+    Plaintext,
+    /// The OS keyring (Secret Service on Linux).
+    Keyring,
+    /// A passphrase-encrypted file in `$XDG_DATA_HOME/cargo-compete/tokens.enc`.
+    EncryptedFile,
+}
+
+pub(crate) fn load() -> anyhow::Result<UserConfig> {
+    let path = path()?;
+    if !path.exists() {
+        return Ok(UserConfig::default());
+    }
+    toml::from_str(&crate::fs::read_to_string(&path)?)
+        .with_context(|| format!("could not read a TOML file at `{}`", path.display()))
+}
+
+pub(crate) fn path() -> anyhow::Result<PathBuf> {
+    let config_dir =
+        dirs_next::config_dir().with_context(|| "could not find the config directory")?;
+    Ok(config_dir.join("cargo-compete").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CredentialStoreKind, PasswordCommand, UserConfig};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn deserialize() -> anyhow::Result<()> {
+        let UserConfig {
+            credentials,
+            standings,
+            ..
+        } = toml::from_str(
+            r#"[credentials]
+store = "encrypted-file"
+
+[credentials.atcoder]
+username = "qryxip"
+password-command = "pass show atcoder"
+
+[credentials.codeforces]
+password-command = ["pass", "show", "codeforces"]
+
+[standings.atcoder]
+friends = ["tourist"]
+
+[standings.yukicoder]
+username = "qryxip"
+"#,
+        )?;
+        assert_eq!(CredentialStoreKind::EncryptedFile, credentials.store);
+        assert_eq!(
+            Some("qryxip"),
+            credentials.logins.atcoder.username.as_deref()
+        );
+        assert_eq!(
+            Some(PasswordCommand::Shell("pass show atcoder".to_owned())),
+            credentials.logins.atcoder.password_command,
+        );
+        assert_eq!(
+            Some(PasswordCommand::Args(vec![
+                "pass".to_owned(),
+                "show".to_owned(),
+                "codeforces".to_owned(),
+            ])),
+            credentials.logins.codeforces.password_command,
+        );
+        assert_eq!(None, standings.atcoder.username);
+        assert_eq!(vec!["tourist".to_owned()], standings.atcoder.friends);
+        assert_eq!(Some("qryxip"), standings.yukicoder.username.as_deref());
+        assert!(standings.codeforces.friends.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn logins() -> anyhow::Result<()> {
+        let config = || -> anyhow::Result<UserConfig> {
+            toml::from_str(
+                r#"[credentials.atcoder]
+username = "main"
+
+[profiles.practice.credentials.atcoder]
+username = "sub"
+"#,
+            )
+            .map_err(Into::into)
+        };
+        assert_eq!(
+            Some("main"),
+            config()?.logins(None).atcoder.username.as_deref()
+        );
+        assert_eq!(
+            Some("sub"),
+            config()?
+                .logins(Some("practice"))
+                .atcoder
+                .username
+                .as_deref(),
+        );
+        assert_eq!(None, config()?.logins(Some("other")).atcoder.username);
+        Ok(())
+    }
+}
@@ -0,0 +1,257 @@
+//! [Aizu Online Judge](https://onlinejudge.u-aizu.ac.jp), through
+//! [its API](http://developers.u-aizu.ac.jp/index).
+
+use crate::{
+    shell::Shell,
+    web::{http, retrieve_testcases::Problem},
+};
+use anyhow::Context as _;
+use camino::Utf8Path;
+use indexmap::IndexMap;
+use itertools::Itertools as _;
+use reqwest::{
+    blocking::{Client, Response},
+    header::{CONTENT_TYPE, COOKIE, SET_COOKIE},
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+use snowchains_core::testsuite::{BatchTestSuite, Match, PartialBatchTestCase, TestSuite};
+use std::{cell::RefCell, time::Duration};
+use url::Url;
+
+static API_BASE_URL: &str = "https://judgeapi.u-aizu.ac.jp";
+static DAT_BASE_URL: &str = "https://judgedat.u-aizu.ac.jp";
+
+/// Whether the URL is of a problem.
+pub(crate) fn is_problem_url(url: &Url) -> bool {
+    problem_id(url).is_some()
+}
+
+/// Extracts the problem ID from the following forms.
+///
+/// - `https://onlinejudge.u-aizu.ac.jp/problems/{id}`
+/// - `https://onlinejudge.u-aizu.ac.jp/courses/{_}/{_}/{_}/{_}/{id}`
+/// - `https://judge.u-aizu.ac.jp/onlinejudge/description.jsp?id={id}`
+fn problem_id(url: &Url) -> Option<String> {
+    let segments = url.path_segments()?.collect::<Vec<_>>();
+    match (url.host_str()?, &*segments) {
+        ("onlinejudge.u-aizu.ac.jp", ["problems", id])
+        | ("onlinejudge.u-aizu.ac.jp", ["courses", _, _, _, _, id])
+            if !id.is_empty() =>
+        {
+            Some((*id).to_owned())
+        }
+        ("judge.u-aizu.ac.jp", ["onlinejudge", "description.jsp"]) => url
+            .query_pairs()
+            .find(|(k, _)| k == "id")
+            .map(|(_, v)| v.into_owned()),
+        _ => None,
+    }
+}
+
+/// Retrieves the sample test cases, or all of the test cases if `system` is `true`.
+pub(crate) fn get_problem(
+    url: &Url,
+    system: bool,
+    _: &Utf8Path,
+    shell: &mut Shell,
+) -> anyhow::Result<Problem<Option<String>>> {
+    let id = &problem_id(url).with_context(|| format!("not an AOJ problem: {url}"))?;
+
+    let client = &http::reqwest_client()?;
+    let shell = &RefCell::new(shell);
+
+    let Description { time_limit } = get_json_with_retries(
+        client,
+        &format!("{API_BASE_URL}/resources/descriptions/en/{id}"),
+        shell,
+    )?;
+
+    let (cases, text_files) = if system {
+        let Header { headers } = get_json_with_retries(
+            client,
+            &format!("{DAT_BASE_URL}/testcases/{id}/header"),
+            shell,
+        )?;
+        let mut text_files = IndexMap::new();
+        for HeaderEntry { serial, name } in headers {
+            let TestCase { input, output, .. } = get_json_with_retries(
+                client,
+                &format!("{DAT_BASE_URL}/testcases/{id}/{serial}"),
+                shell,
+            )?;
+            text_files.insert(format!("{serial:02}_{name}"), (input, Some(output)));
+        }
+        (vec![], text_files)
+    } else {
+        let samples: Vec<TestCase> = get_json_with_retries(
+            client,
+            &format!("{DAT_BASE_URL}/testcases/samples/{id}"),
+            shell,
+        )?;
+        let cases = samples
+            .into_iter()
+            .map(
+                |TestCase {
+                     serial,
+                     input,
+                     output,
+                 }| PartialBatchTestCase {
+                    name: Some(format!("sample{serial}")),
+                    r#in: input.into(),
+                    out: Some(output.into()),
+                    timelimit: None,
+                    r#match: None,
+                },
+            )
+            .collect();
+        (cases, IndexMap::new())
+    };
+
+    return Ok(Problem {
+        index: None,
+        url: url.clone(),
+        test_suite: TestSuite::Batch(BatchTestSuite {
+            timelimit: time_limit.map(Duration::from_secs),
+            r#match: Match::Lines,
+            cases,
+            extend: vec![],
+        }),
+        text_files,
+        contest_url: None,
+        statement: None,
+    });
+
+    #[derive(Deserialize)]
+    struct Description {
+        /// In seconds.
+        time_limit: Option<u64>,
+    }
+
+    #[derive(Deserialize)]
+    struct Header {
+        headers: Vec<HeaderEntry>,
+    }
+
+    #[derive(Deserialize)]
+    struct HeaderEntry {
+        serial: u64,
+        name: String,
+    }
+
+    #[derive(Deserialize)]
+    struct TestCase {
+        serial: u64,
+        #[serde(rename = "in")]
+        input: String,
+        #[serde(rename = "out")]
+        output: String,
+    }
+}
+
+/// Logs in, submits the code, and returns the URL of the submission.
+///
+/// The session is not saved, so this logs in every time.
+pub(crate) fn submit(
+    url: &Url,
+    code: &str,
+    language_id: &str,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<Url> {
+    let id = &problem_id(url).with_context(|| format!("not an AOJ problem: {url}"))?;
+    let (username, password) = crate::web::credentials::aoj_username_and_password(profile, shell)?;
+
+    let client = &http::reqwest_client()?;
+
+    let res = post_json(
+        client,
+        &format!("{API_BASE_URL}/session"),
+        &json!({ "id": &username, "password": password }),
+        "",
+    )
+    .with_context(|| "could not login to AOJ")?;
+
+    let cookie = res
+        .headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .flat_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';').next())
+        .join("; ");
+
+    post_json(
+        client,
+        &format!("{API_BASE_URL}/submissions"),
+        &json!({ "problemId": id, "language": language_id, "sourceCode": code }),
+        &cookie,
+    )?;
+
+    let records: Vec<SubmissionRecord> = http::get_json(
+        client,
+        &format!("{API_BASE_URL}/submission_records/users/{username}/problems/{id}?size=1"),
+    )?;
+
+    let url = match records.first() {
+        Some(SubmissionRecord { judge_id }) => format!(
+            "https://onlinejudge.u-aizu.ac.jp/status/users/{username}/submissions/1/{id}/judge/\
+             {judge_id}/{language_id}",
+        ),
+        None => format!("https://onlinejudge.u-aizu.ac.jp/status/users/{username}/submissions/1"),
+    };
+    return url.parse().map_err(Into::into);
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct SubmissionRecord {
+        judge_id: u64,
+    }
+}
+
+fn get_json_with_retries<T: DeserializeOwned>(
+    client: &Client,
+    url: &str,
+    shell: &RefCell<&mut Shell>,
+) -> anyhow::Result<T> {
+    http::with_retries(shell, || http::get_json(client, url))
+}
+
+fn post_json(
+    client: &Client,
+    url: &str,
+    body: &serde_json::Value,
+    cookie: &str,
+) -> anyhow::Result<Response> {
+    let req = client
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(COOKIE, cookie)
+        .body(body.to_string());
+
+    super::replay::send(client, req)
+        .and_then(|res| res.error_for_status().map_err(Into::into))
+        .with_context(|| format!("could not post to {url}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn problem_id() {
+        for url in &[
+            "https://onlinejudge.u-aizu.ac.jp/problems/ITP1_1_A",
+            "https://onlinejudge.u-aizu.ac.jp/courses/lesson/2/ITP1/1/ITP1_1_A",
+            "https://judge.u-aizu.ac.jp/onlinejudge/description.jsp?id=ITP1_1_A&lang=jp",
+        ] {
+            assert_eq!(
+                Some("ITP1_1_A"),
+                super::problem_id(&url.parse().unwrap()).as_deref(),
+            );
+        }
+        assert_eq!(
+            None,
+            super::problem_id(&"https://onlinejudge.u-aizu.ac.jp/home".parse().unwrap()),
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! Virtual contests of [AtCoder Problems](https://kenkoooo.com/atcoder).
+
+use crate::{
+    shell::Shell,
+    web::{cache::ProblemCache, http},
+};
+use anyhow::Context as _;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use url::Url;
+
+static BASE_URL: &str = "https://kenkoooo.com/atcoder";
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct VirtualContest {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    pub(crate) start_epoch_second: u64,
+    pub(crate) duration_second: u64,
+    pub(crate) penalty_second: u64,
+    /// In the order of the contest.
+    pub(crate) problems: Vec<VirtualContestProblem>,
+    pub(crate) participants: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct VirtualContestProblem {
+    /// Label in the virtual contest, such as `A`.
+    pub(crate) index: String,
+    /// Such as `abc100_a`.
+    pub(crate) id: String,
+    /// URL in the original contest.
+    pub(crate) url: Url,
+    /// Overrides the original score if set.
+    pub(crate) point: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Submission {
+    pub(crate) epoch_second: u64,
+    pub(crate) problem_id: String,
+    pub(crate) point: f64,
+    pub(crate) result: String,
+}
+
+/// Whether the URL is of a virtual contest, i.e. `https://kenkoooo.com/atcoder/#/contest/show/{id}`.
+pub(crate) fn is_virtual_contest_url(url: &Url) -> bool {
+    virtual_contest_id(url).is_some()
+}
+
+/// The ID is in the fragment since AtCoder Problems is a single-page application.
+fn virtual_contest_id(url: &Url) -> Option<&str> {
+    if url.host_str() != Some("kenkoooo.com") || !url.path().starts_with("/atcoder") {
+        return None;
+    }
+    let id = url
+        .fragment()?
+        .strip_prefix("/contest/show/")?
+        .split(&['/', '?'][..])
+        .next()?;
+    Some(id).filter(|id| !id.is_empty())
+}
+
+/// Retrieves the problems and the participants of the virtual contest.
+///
+/// The problems are cached. The participants in the record may be outdated in offline mode.
+pub(crate) fn get_virtual_contest(
+    url: &Url,
+    cache: &ProblemCache,
+    shell: &mut Shell,
+) -> anyhow::Result<VirtualContest> {
+    let id = virtual_contest_id(url)
+        .with_context(|| format!("not a virtual contest of AtCoder Problems: {url}"))?;
+    let key = &format!("atcoder-problems virtual-contest {id}");
+    cache.get_or_retrieve(key, || retrieve_virtual_contest(id, shell))
+}
+
+fn retrieve_virtual_contest(id: &str, shell: &mut Shell) -> anyhow::Result<VirtualContest> {
+    let client = &http::reqwest_client()?;
+    let shell = &RefCell::new(shell);
+
+    let Contest {
+        info,
+        mut problems,
+        participants,
+    } = http::with_retries(shell, || {
+        http::get_json(client, &format!("{BASE_URL}/internal-api/contest/get/{id}"))
+    })?;
+
+    let contest_problems: Vec<ContestProblem> = http::with_retries(shell, || {
+        http::get_json(
+            client,
+            &format!("{BASE_URL}/resources/contest-problem.json"),
+        )
+    })?;
+
+    problems.sort_by_key(|p| p.order);
+
+    let problems = problems
+        .into_iter()
+        .enumerate()
+        .map(|(i, Problem { id, point, .. })| {
+            let contest = original_contest(&contest_problems, &id)
+                .with_context(|| format!("could not find the contest of `{id}`"))?;
+            Ok(VirtualContestProblem {
+                index: index_label(i),
+                url: format!("https://atcoder.jp/contests/{contest}/tasks/{id}").parse()?,
+                id,
+                point,
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    return Ok(VirtualContest {
+        id: info.id,
+        title: info.title,
+        start_epoch_second: info.start_epoch_second,
+        duration_second: info.duration_second,
+        penalty_second: info.penalty_second,
+        problems,
+        participants,
+    });
+
+    #[derive(Deserialize)]
+    struct Contest {
+        info: Info,
+        problems: Vec<Problem>,
+        participants: Vec<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct Info {
+        id: String,
+        title: String,
+        start_epoch_second: u64,
+        duration_second: u64,
+        #[serde(default)]
+        penalty_second: u64,
+    }
+
+    #[derive(Deserialize)]
+    struct Problem {
+        id: String,
+        point: Option<f64>,
+        order: Option<u64>,
+    }
+}
+
+/// Retrieves the submissions of the user since the start of the virtual contest.
+pub(crate) fn user_submissions(
+    client: &Client,
+    user: &str,
+    from_second: u64,
+) -> anyhow::Result<Vec<Submission>> {
+    let mut url = format!("{BASE_URL}/atcoder-api/v3/user/submissions").parse::<Url>()?;
+    url.query_pairs_mut()
+        .append_pair("user", user)
+        .append_pair("from_second", &from_second.to_string());
+    http::get_json(client, url.as_str())
+}
+
+#[derive(Deserialize)]
+struct ContestProblem {
+    contest_id: String,
+    problem_id: String,
+}
+
+/// A problem may be shared among several contests. Prefers the one that the ID is named after.
+fn original_contest<'a>(
+    contest_problems: &'a [ContestProblem],
+    problem_id: &str,
+) -> Option<&'a str> {
+    let candidates = contest_problems
+        .iter()
+        .filter(|p| p.problem_id == problem_id)
+        .map(|p| &*p.contest_id)
+        .collect::<Vec<_>>();
+    candidates
+        .iter()
+        .find(|c| problem_id.starts_with(&format!("{}_", c.replace('-', "_"))))
+        .or_else(|| candidates.first())
+        .copied()
+}
+
+/// `A`, `B`, ..., `Z`, `AA`, `AB`, ...
+fn index_label(i: usize) -> String {
+    let mut label = vec![];
+    let mut n = i + 1;
+    while n > 0 {
+        n -= 1;
+        label.push(b'A' + (n % 26) as u8);
+        n /= 26;
+    }
+    label.reverse();
+    String::from_utf8(label).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ContestProblem;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn virtual_contest_id() {
+        for url in &[
+            "https://kenkoooo.com/atcoder/#/contest/show/0b1bbcaf-bd6c-4e2a-9c5f-3a6e1e1b0f0e",
+            "https://kenkoooo.com/atcoder/#/contest/show/0b1bbcaf-bd6c-4e2a-9c5f-3a6e1e1b0f0e?activeTab=Standings",
+        ] {
+            assert_eq!(
+                Some("0b1bbcaf-bd6c-4e2a-9c5f-3a6e1e1b0f0e"),
+                super::virtual_contest_id(&url.parse().unwrap()),
+            );
+        }
+        assert_eq!(
+            None,
+            super::virtual_contest_id(&"https://kenkoooo.com/atcoder/#/table/".parse().unwrap()),
+        );
+    }
+
+    #[test]
+    fn original_contest() {
+        let contest_problems = [
+            ("abc100", "abc100_a"),
+            ("abc100", "abc099_d"),
+            ("abc099", "abc099_d"),
+        ]
+        .iter()
+        .map(|&(contest_id, problem_id)| ContestProblem {
+            contest_id: contest_id.to_owned(),
+            problem_id: problem_id.to_owned(),
+        })
+        .collect::<Vec<_>>();
+        assert_eq!(
+            Some("abc099"),
+            super::original_contest(&contest_problems, "abc099_d"),
+        );
+        assert_eq!(
+            Some("abc100"),
+            super::original_contest(&contest_problems, "abc100_a"),
+        );
+        assert_eq!(None, super::original_contest(&contest_problems, "abc101_a"));
+    }
+
+    #[test]
+    fn index_label() {
+        assert_eq!(
+            ["A", "B", "Z", "AA", "AB"],
+            [0, 1, 25, 26, 27].map(super::index_label),
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Retrieved problems cached under `{ cache directory }/cargo-compete/problems`.
+//!
+//! Every successful retrieval is recorded. In offline mode, the records are replayed instead of
+//! sending requests, so a directory of them works as fixtures.
+
+use anyhow::{bail, Context as _};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+pub(crate) struct ProblemCache {
+    dir: PathBuf,
+    offline: bool,
+}
+
+impl ProblemCache {
+    /// Offline mode is enabled by `--offline` or `$CARGO_COMPETE_OFFLINE`.
+    pub(crate) fn new(cache_dir: &Path, cli_opt_offline: bool) -> Self {
+        Self {
+            dir: cache_dir.join("problems"),
+            offline: is_offline(cli_opt_offline),
+        }
+    }
+
+    /// Retrieves without recording, for the values too large to cache such as the system test
+    /// cases.
+    pub(crate) fn retrieve_uncached<T>(
+        &self,
+        key: &str,
+        retrieve: impl FnOnce() -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        if self.offline {
+            bail!("`{}` is not cached, and offline mode is enabled", key);
+        }
+        retrieve()
+    }
+
+    pub(crate) fn get_or_retrieve<T: Serialize + DeserializeOwned>(
+        &self,
+        key: &str,
+        retrieve: impl FnOnce() -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let path = &self
+            .dir
+            .join(format!("{:x}.json", Sha256::digest(key.as_bytes())));
+
+        if self.offline {
+            if !path.exists() {
+                bail!(
+                    "`{}` is not cached. run the command once without offline mode",
+                    key,
+                );
+            }
+            let Record::<String, _> { value, .. } = crate::fs::read_json(path)?;
+            return Ok(value);
+        }
+
+        let value = retrieve()?;
+        crate::fs::create_dir_all(&self.dir)?;
+        crate::fs::write_json(path, Record { key, value: &value })
+            .with_context(|| "could not cache the retrieved problems")?;
+        Ok(value)
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub(super) struct Record<K, V> {
+    pub(super) key: K,
+    pub(super) value: V,
+}
+
+/// Whether offline mode is enabled by `--offline` or `$CARGO_COMPETE_OFFLINE`.
+pub(crate) fn is_offline(cli_opt_offline: bool) -> bool {
+    cli_opt_offline || offline_by_env()
+}
+
+/// Whether `$CARGO_COMPETE_OFFLINE` is set to a non-empty value.
+fn offline_by_env() -> bool {
+    env::var_os("CARGO_COMPETE_OFFLINE").is_some_and(|v| !v.is_empty())
+}
@@ -0,0 +1,393 @@
+//! Upcoming and running contests.
+
+use super::{http, session};
+use anyhow::{bail, Context as _};
+use chrono::{DateTime, TimeZone as _, Utc};
+use scraper::{Html, Selector};
+use serde::Deserialize;
+use snowchains_core::web::{CookieStorage, PlatformKind};
+use std::path::Path;
+use url::Url;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Contest {
+    pub(crate) platform: PlatformKind,
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) url: Url,
+    pub(crate) start: DateTime<Utc>,
+    pub(crate) duration: chrono::Duration,
+    /// `None` if it is unknown, e.g. when not logged in or on yukicoder.
+    pub(crate) registered: Option<bool>,
+}
+
+/// Lists the upcoming and running contests on the platform, sorted by the start time.
+pub(crate) fn list(platform: PlatformKind, cookies_path: &Path) -> anyhow::Result<Vec<Contest>> {
+    let client = &http::reqwest_client()?;
+    let cookie_storage = &CookieStorage::with_jsonl(cookies_path)?;
+    let get = |url: &str| session::get_with_cookies(client, &url.parse()?, cookie_storage);
+
+    let mut contests = match platform {
+        PlatformKind::Atcoder => {
+            let html = get("https://atcoder.jp/contests/")?.text()?;
+            let mut contests = parse_atcoder(&html)?;
+
+            // The registrations are only shown on the page of each contest.
+            if session::username_in_html(PlatformKind::Atcoder, &html).is_some() {
+                for contest in &mut contests {
+                    let html = get(contest.url.as_str())?.text()?;
+                    contest.registered = Some(atcoder_registered(&contest.id, &html));
+                }
+            }
+            contests
+        }
+        PlatformKind::Codeforces => {
+            let json = get("https://codeforces.com/api/contest.list?gym=false")?.text()?;
+            let mut contests = parse_codeforces(&json)?;
+
+            let html = get("https://codeforces.com/contests")?.text()?;
+            if session::username_in_html(PlatformKind::Codeforces, &html).is_some() {
+                for contest in &mut contests {
+                    contest.registered = Some(codeforces_registered(&contest.id, &html));
+                }
+            }
+            contests
+        }
+        PlatformKind::Yukicoder => {
+            let mut contests = vec![];
+            for url in &[
+                "https://yukicoder.me/api/v1/contest/current",
+                "https://yukicoder.me/api/v1/contest/future",
+            ] {
+                contests.extend(parse_yukicoder(&get(url)?.text()?)?);
+            }
+            contests
+        }
+    };
+
+    contests.sort_by_key(|c| c.start);
+    Ok(contests)
+}
+
+fn parse_atcoder(html: &str) -> anyhow::Result<Vec<Contest>> {
+    let html = Html::parse_document(html);
+    let selector = |s: &str| Selector::parse(s).unwrap();
+
+    let mut contests = vec![];
+
+    for row in html.select(&selector(
+        "#contest-table-action tbody tr, #contest-table-upcoming tbody tr",
+    )) {
+        let text = |selector: &Selector| -> Option<String> {
+            row.select(selector)
+                .next()
+                .map(|e| e.text().collect::<String>().trim().to_owned())
+        };
+
+        let start = text(&selector("time")).with_context(|| "missing the start time")?;
+        let start = DateTime::parse_from_str(&start, "%Y-%m-%d %H:%M:%S%z")
+            .with_context(|| format!("could not parse {start:?}"))?
+            .with_timezone(&Utc);
+
+        let link = row
+            .select(&selector(r#"a[href^="/contests/"]"#))
+            .next()
+            .with_context(|| "missing the link to the contest")?;
+        let id = link.value().attr("href").unwrap()["/contests/".len()..].to_owned();
+        let name = link.text().collect::<String>().trim().to_owned();
+
+        let duration = row
+            .select(&selector("td"))
+            .nth(2)
+            .map(|e| e.text().collect::<String>())
+            .with_context(|| "missing the duration")?;
+        let duration = parse_hh_mm(duration.trim())?;
+
+        contests.push(Contest {
+            platform: PlatformKind::Atcoder,
+            url: format!("https://atcoder.jp/contests/{id}").parse()?,
+            id,
+            name,
+            start,
+            duration,
+            registered: None,
+        });
+    }
+    Ok(contests)
+}
+
+/// Whether the page of the contest has no button to register.
+fn atcoder_registered(contest: &str, html: &str) -> bool {
+    !html.contains(&format!(r#"action="/contests/{contest}/register""#))
+}
+
+/// Retrieves the index of the first task in the task list of the AtCoder contest, e.g. `A`.
+pub(crate) fn atcoder_first_task(contest: &str, cookies_path: &Path) -> anyhow::Result<String> {
+    let client = &http::reqwest_client()?;
+    let cookie_storage = &CookieStorage::with_jsonl(cookies_path)?;
+    let url = format!("https://atcoder.jp/contests/{contest}/tasks").parse()?;
+    let html = session::get_with_cookies(client, &url, cookie_storage)?.text()?;
+    parse_atcoder_first_task(&html).with_context(|| format!("no tasks found in {url}"))
+}
+
+fn parse_atcoder_first_task(html: &str) -> Option<String> {
+    let html = Html::parse_document(html);
+    let selector = Selector::parse("#main-container table tbody tr td.text-center > a").unwrap();
+    let index = html.select(&selector).next()?.text().collect::<String>();
+    Some(index.trim().to_owned()).filter(|index| !index.is_empty())
+}
+
+fn parse_codeforces(json: &str) -> anyhow::Result<Vec<Contest>> {
+    #[derive(Deserialize)]
+    struct ContestList {
+        status: String,
+        #[serde(default)]
+        comment: Option<String>,
+        #[serde(default)]
+        result: Vec<CodeforcesContest>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct CodeforcesContest {
+        id: u64,
+        name: String,
+        phase: String,
+        duration_seconds: i64,
+        start_time_seconds: Option<i64>,
+    }
+
+    let ContestList {
+        status,
+        comment,
+        result,
+    } = serde_json::from_str(json)?;
+
+    if status != "OK" {
+        bail!(
+            "Codeforces API returned `{}`: {}",
+            status,
+            comment.unwrap_or_default(),
+        );
+    }
+
+    result
+        .into_iter()
+        .filter(|c| c.phase == "BEFORE" || c.phase == "CODING")
+        .flat_map(|c| {
+            let start = c.start_time_seconds?;
+            Some((c, start))
+        })
+        .map(|(c, start)| {
+            Ok(Contest {
+                platform: PlatformKind::Codeforces,
+                id: c.id.to_string(),
+                name: c.name,
+                url: format!("https://codeforces.com/contest/{}", c.id).parse()?,
+                start: Utc.timestamp(start, 0),
+                duration: chrono::Duration::seconds(c.duration_seconds),
+                registered: None,
+            })
+        })
+        .collect()
+}
+
+/// Whether the row of the contest in `/contests` says that the registration is completed.
+fn codeforces_registered(contest: &str, html: &str) -> bool {
+    let html = Html::parse_document(html);
+    let selector = Selector::parse(&format!(r#"tr[data-contestid="{contest}"]"#)).unwrap();
+    html.select(&selector)
+        .next()
+        .is_some_and(|row| row.text().any(|s| s.contains("Registration completed")))
+}
+
+fn parse_yukicoder(json: &str) -> anyhow::Result<Vec<Contest>> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct YukicoderContest {
+        id: u64,
+        name: String,
+        date: String,
+        end_date: String,
+    }
+
+    let parse = |s: &str| -> anyhow::Result<_> {
+        Ok(DateTime::parse_from_rfc3339(s)
+            .with_context(|| format!("could not parse {s:?}"))?
+            .with_timezone(&Utc))
+    };
+
+    serde_json::from_str::<Vec<YukicoderContest>>(json)?
+        .into_iter()
+        .map(|c| {
+            let start = parse(&c.date)?;
+            Ok(Contest {
+                platform: PlatformKind::Yukicoder,
+                id: c.id.to_string(),
+                name: c.name,
+                url: format!("https://yukicoder.me/contests/{}", c.id).parse()?,
+                start,
+                duration: parse(&c.end_date)? - start,
+                registered: None,
+            })
+        })
+        .collect()
+}
+
+fn parse_hh_mm(s: &str) -> anyhow::Result<chrono::Duration> {
+    let (h, m) = s
+        .split_once(':')
+        .and_then(|(h, m)| Some((h.parse().ok()?, m.parse().ok()?)))
+        .with_context(|| format!("expected `HH:MM`, got {s:?}"))?;
+    Ok(chrono::Duration::hours(h) + chrono::Duration::minutes(m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Contest;
+    use chrono::{TimeZone as _, Utc};
+    use pretty_assertions::assert_eq;
+    use snowchains_core::web::PlatformKind;
+
+    #[test]
+    fn parse_atcoder() -> anyhow::Result<()> {
+        let contests = super::parse_atcoder(
+            r#"<!DOCTYPE html>
+<html>
+<body>
+<div id="contest-table-action">
+<table>
+<thead><tr><th>Start Time</th><th>Contest Name</th><th>Duration</th><th>Rated Range</th></tr></thead>
+<tbody>
+<tr>
+<td class="text-center"><a href="http://www.timeanddate.com/worldclock/fixedtime.html?iso=20210320T2100&p1=248" target="blank"><time class="fixtime fixtime-full">2021-03-20 21:00:00+0900</time></a></td>
+<td><span aria-hidden="true" data-toggle="tooltip" data-placement="top" title="Algorithm">Ⓐ</span> <span class="user-blue">◉</span> <a href="/contests/abc196">AtCoder Beginner Contest 196</a></td>
+<td class="text-center">01:40</td>
+<td class="text-center"> - 1999</td>
+</tr>
+</tbody>
+</table>
+</div>
+<div id="contest-table-upcoming">
+<table>
+<thead><tr><th>Start Time</th><th>Contest Name</th><th>Duration</th><th>Rated Range</th></tr></thead>
+<tbody>
+<tr>
+<td class="text-center"><a href="http://www.timeanddate.com/worldclock/fixedtime.html?iso=20210321T2100&p1=248" target="blank"><time class="fixtime fixtime-full">2021-03-21 21:00:00+0900</time></a></td>
+<td><span aria-hidden="true" data-toggle="tooltip" data-placement="top" title="Algorithm">Ⓐ</span> <span class="user-red">◉</span> <a href="/contests/arc115">AtCoder Regular Contest 115</a></td>
+<td class="text-center">02:00</td>
+<td class="text-center"> - 2799</td>
+</tr>
+</tbody>
+</table>
+</div>
+</body>
+</html>
+"#,
+        )?;
+
+        assert_eq!(
+            vec![
+                Contest {
+                    platform: PlatformKind::Atcoder,
+                    id: "abc196".to_owned(),
+                    name: "AtCoder Beginner Contest 196".to_owned(),
+                    url: "https://atcoder.jp/contests/abc196".parse()?,
+                    start: Utc.ymd(2021, 3, 20).and_hms(12, 0, 0),
+                    duration: chrono::Duration::minutes(100),
+                    registered: None,
+                },
+                Contest {
+                    platform: PlatformKind::Atcoder,
+                    id: "arc115".to_owned(),
+                    name: "AtCoder Regular Contest 115".to_owned(),
+                    url: "https://atcoder.jp/contests/arc115".parse()?,
+                    start: Utc.ymd(2021, 3, 21).and_hms(12, 0, 0),
+                    duration: chrono::Duration::hours(2),
+                    registered: None,
+                },
+            ],
+            contests,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_atcoder_first_task() {
+        let index = super::parse_atcoder_first_task(
+            r#"<!DOCTYPE html>
+<html>
+<body>
+<div id="main-container">
+<table class="table">
+<thead><tr><th></th><th>Task Name</th><th>Time Limit</th><th>Memory Limit</th></tr></thead>
+<tbody>
+<tr>
+<td class="text-center no-break"><a href="/contests/abc001/tasks/abc001_1">A</a></td>
+<td><a href="/contests/abc001/tasks/abc001_1">積雪深差</a></td>
+<td class="text-right">2 sec</td>
+<td class="text-right">64 MB</td>
+</tr>
+<tr>
+<td class="text-center no-break"><a href="/contests/abc001/tasks/abc001_2">B</a></td>
+<td><a href="/contests/abc001/tasks/abc001_2">視程の通報</a></td>
+<td class="text-right">2 sec</td>
+<td class="text-right">64 MB</td>
+</tr>
+</tbody>
+</table>
+</div>
+</body>
+</html>
+"#,
+        );
+        assert_eq!(Some("A"), index.as_deref());
+
+        assert_eq!(
+            None,
+            super::parse_atcoder_first_task("<html><body></body></html>"),
+        );
+    }
+
+    #[test]
+    fn parse_codeforces() -> anyhow::Result<()> {
+        let contests = super::parse_codeforces(
+            r#"{"status":"OK","result":[{"id":1500,"name":"Codeforces Round #707 (Div. 1)","type":"CF","phase":"BEFORE","frozen":false,"durationSeconds":7200,"startTimeSeconds":1616322900,"relativeTimeSeconds":-3600},{"id":1499,"name":"Educational Codeforces Round 106","type":"ICPC","phase":"FINISHED","frozen":false,"durationSeconds":7200,"startTimeSeconds":1616079900,"relativeTimeSeconds":240000}]}"#,
+        )?;
+
+        assert_eq!(
+            vec![Contest {
+                platform: PlatformKind::Codeforces,
+                id: "1500".to_owned(),
+                name: "Codeforces Round #707 (Div. 1)".to_owned(),
+                url: "https://codeforces.com/contest/1500".parse()?,
+                start: Utc.timestamp(1616322900, 0),
+                duration: chrono::Duration::hours(2),
+                registered: None,
+            }],
+            contests,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_yukicoder() -> anyhow::Result<()> {
+        let contests = super::parse_yukicoder(
+            r#"[{"Id":310,"Name":"yukicoder contest 290","Date":"2021-03-26T21:20:00+09:00","EndDate":"2021-03-26T23:20:00+09:00","ProblemIdList":[]}]"#,
+        )?;
+
+        assert_eq!(
+            vec![Contest {
+                platform: PlatformKind::Yukicoder,
+                id: "310".to_owned(),
+                name: "yukicoder contest 290".to_owned(),
+                url: "https://yukicoder.me/contests/310".parse()?,
+                start: Utc.ymd(2021, 3, 26).and_hms(12, 20, 0),
+                duration: chrono::Duration::hours(2),
+                registered: None,
+            }],
+            contests,
+        );
+        Ok(())
+    }
+}
@@ -1,43 +1,170 @@
-use crate::shell::Shell;
-use anyhow::{anyhow, Context as _};
+use crate::{
+    shell::Shell,
+    user_config::{self, CredentialStoreKind, PasswordCommand, UserConfigLogin},
+};
+use anyhow::{anyhow, bail, Context as _};
+use argon2::Argon2;
+use camino::Utf8PathBuf;
+use chacha20poly1305::{
+    aead::{rand_core::RngCore as _, Aead as _, AeadCore as _, KeyInit as _, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::{cell::RefCell, env, path::PathBuf};
+use snowchains_core::web::PlatformKind;
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    convert::TryFrom as _,
+    env,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use strum::{EnumString, EnumVariantNames};
 
+/// Reads the username and password from `${PLATFORM}_USERNAME`/`${PLATFORM}_PASSWORD`, the
+/// user-level config, or the terminal.
+///
+/// Under a profile, they are read from `${PLATFORM}_${PROFILE}_USERNAME`/
+/// `${PLATFORM}_${PROFILE}_PASSWORD` and `profiles.<profile>.credentials` instead.
 pub(crate) fn username_and_password<'a>(
     shell: &'a RefCell<&'a mut Shell>,
+    profile: Option<&str>,
+    platform: PlatformKind,
     username_prompt: &'static str,
     password_prompt: &'static str,
 ) -> impl 'a + FnMut() -> anyhow::Result<(String, String)> {
+    let profile = profile.map(ToOwned::to_owned);
+    let mut non_interactive = false;
+
     move || -> _ {
+        if non_interactive {
+            bail!(
+                "could not login to {} with the non-interactive password",
+                platform,
+            );
+        }
+
         let mut shell = shell.borrow_mut();
-        let username = shell.read_reply(username_prompt)?;
-        let password = shell.read_password(password_prompt)?;
+        let env_prefix = platform.to_string().to_uppercase();
+        let login = user_config::load()?
+            .logins(profile.as_deref())
+            .login(platform);
+
+        let (username, password, is_non_interactive) = read_username_and_password(
+            &mut shell,
+            &env_prefix,
+            profile.as_deref(),
+            login,
+            username_prompt,
+            password_prompt,
+        )?;
+        non_interactive = is_non_interactive;
         Ok((username, password))
     }
 }
 
-pub(crate) fn dropbox_access_token() -> anyhow::Result<String> {
-    if let Some(value) = env_var("DROPBOX_ACCESS_TOKEN")? {
+/// Reads the username and password for AOJ from `$AOJ_USERNAME`/`$AOJ_PASSWORD`, the user-level
+/// config, or the terminal. Namespaced by the profile like [`username_and_password`].
+pub(crate) fn aoj_username_and_password(
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<(String, String)> {
+    let login = user_config::load()?.logins(profile).aoj;
+    let (username, password, _) =
+        read_username_and_password(shell, "AOJ", profile, login, "Username: ", "Password: ")?;
+    Ok((username, password))
+}
+
+/// Also returns whether the password was given without the terminal.
+fn read_username_and_password(
+    shell: &mut Shell,
+    env_prefix: &str,
+    profile: Option<&str>,
+    login: UserConfigLogin,
+    username_prompt: &'static str,
+    password_prompt: &'static str,
+) -> anyhow::Result<(String, String, bool)> {
+    let UserConfigLogin {
+        username,
+        password_command,
+    } = login;
+
+    let username = if let Some(username) = env_var(&env_var_name(env_prefix, "USERNAME", profile))?
+    {
+        username
+    } else if let Some(username) = username {
+        username
+    } else {
+        shell.read_reply(username_prompt)?
+    };
+
+    let (password, non_interactive) =
+        if let Some(password) = env_var(&env_var_name(env_prefix, "PASSWORD", profile))? {
+            (password, true)
+        } else if let Some(password_command) = password_command {
+            (run_password_command(&password_command, shell)?, true)
+        } else {
+            (shell.read_password(password_prompt)?, false)
+        };
+
+    Ok((username, password, non_interactive))
+}
+
+fn run_password_command(
+    password_command: &PasswordCommand,
+    shell: &mut Shell,
+) -> anyhow::Result<String> {
+    let cwd = env::current_dir().with_context(|| "could not get the current directory")?;
+    let cwd = Utf8PathBuf::try_from(cwd)?;
+
+    let (program, args) = match password_command {
+        PasswordCommand::Shell(command) if cfg!(windows) => {
+            ("cmd".to_owned(), vec!["/C".to_owned(), command.clone()])
+        }
+        PasswordCommand::Shell(command) => {
+            ("sh".to_owned(), vec!["-c".to_owned(), command.clone()])
+        }
+        PasswordCommand::Args(args) => match args.split_first() {
+            Some((program, args)) => (program.clone(), args.to_owned()),
+            None => bail!("`password-command` is empty"),
+        },
+    };
+
+    let output = crate::process::with_which(program, &cwd)?
+        .args(&args)
+        .read_with_shell_status(shell)
+        .with_context(|| "`password-command` failed")?;
+
+    // Like `pass show`, the password is on the first line.
+    output
+        .lines()
+        .next()
+        .map(ToOwned::to_owned)
+        .with_context(|| "`password-command` printed nothing")
+}
+
+pub(crate) fn dropbox_access_token(
+    cookies_path: &Path,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<String> {
+    if let Some(value) = env_var(&Token::Dropbox.env_vars(profile)[0])? {
         return Ok(value);
     }
 
-    let path = token_path("dropbox.json")?;
+    let store = CredentialStore::load(cookies_path, profile)?;
 
-    let DropboxJson { access_token } = crate::fs::read_json(&path).with_context(|| {
-        format!(
-            r#"first, save a JSON to `{}` in the following format.
-```
-{{
-  "access_token": "<Dropbox access token>"
-}}
-```
+    let DropboxJson { access_token } = store
+        .get(Token::Dropbox, shell)?
+        .with_context(|| {
+            r#"first, save a Dropbox access token with `cargo compete credentials set dropbox`.
 The access token must have these permissions.
 - `files.metadata.read`
 - `sharing.read`
-"#,
-            path.display()
-        )
-    })?;
+"#
+        })
+        .and_then(|content| Token::Dropbox.parse(&content))?;
 
     return Ok(access_token);
 
@@ -47,55 +174,367 @@ The access token must have these permissions.
     }
 }
 
-pub(crate) fn yukicoder_api_key(shell: &mut Shell) -> anyhow::Result<String> {
-    if let Some(value) = env_var("YUKICODER_API_KEY")? {
+pub(crate) fn yukicoder_api_key(
+    cookies_path: &Path,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<String> {
+    if let Some(value) = env_var(&Token::Yukicoder.env_vars(profile)[0])? {
         return Ok(value);
     }
 
-    let path = token_path("yukicoder.json")?;
-    if path.exists() {
-        crate::fs::read_json(path)
-    } else {
-        let api_key = shell.read_password("yukicoder API key: ")?;
-        crate::fs::create_dir_all(path.parent().unwrap())?;
-        crate::fs::write_json(path, &api_key)?;
-        Ok(api_key)
+    let store = CredentialStore::load(cookies_path, profile)?;
+    Token::Yukicoder.parse(&store.get_or_prompt(Token::Yukicoder, shell)?)
+}
+
+/// Like [`yukicoder_api_key`], but returns `None` instead of asking.
+pub(crate) fn stored_yukicoder_api_key(
+    cookies_path: &Path,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<Option<String>> {
+    if let Some(value) = env_var(&Token::Yukicoder.env_vars(profile)[0])? {
+        return Ok(Some(value));
     }
+
+    let store = CredentialStore::load(cookies_path, profile)?;
+    store
+        .get(Token::Yukicoder, shell)?
+        .map(|content| Token::Yukicoder.parse(&content))
+        .transpose()
 }
 
-pub(crate) fn codeforces_api_key_and_secret(shell: &mut Shell) -> anyhow::Result<(String, String)> {
-    if let (Some(api_key), Some(api_secret)) = (
-        env_var("CODEFORCES_API_KEY")?,
-        env_var("CODEFORCES_API_SECRET")?,
-    ) {
+pub(crate) fn codeforces_api_key_and_secret(
+    cookies_path: &Path,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<(String, String)> {
+    let env_vars = Token::Codeforces.env_vars(profile);
+    if let (Some(api_key), Some(api_secret)) = (env_var(&env_vars[0])?, env_var(&env_vars[1])?) {
         return Ok((api_key, api_secret));
     }
 
-    let path = token_path("codeforces.json")?;
+    let store = CredentialStore::load(cookies_path, profile)?;
 
     let CodeforcesJson {
         api_key,
         api_secret,
-    } = if path.exists() {
-        crate::fs::read_json(path)?
-    } else {
-        let api_key = shell.read_password("Codeforces API key: ")?;
-        let api_secret = shell.read_password("Codeforces API secret: ")?;
-        let content = CodeforcesJson {
-            api_key,
-            api_secret,
+    } = Token::Codeforces.parse(&store.get_or_prompt(Token::Codeforces, shell)?)?;
+
+    Ok((api_key, api_secret))
+}
+
+/// Like [`codeforces_api_key_and_secret`], but returns `None` instead of asking.
+pub(crate) fn stored_codeforces_api_key_and_secret(
+    cookies_path: &Path,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<Option<(String, String)>> {
+    let env_vars = Token::Codeforces.env_vars(profile);
+    if let (Some(api_key), Some(api_secret)) = (env_var(&env_vars[0])?, env_var(&env_vars[1])?) {
+        return Ok(Some((api_key, api_secret)));
+    }
+
+    let store = CredentialStore::load(cookies_path, profile)?;
+
+    store
+        .get(Token::Codeforces, shell)?
+        .map(|content| {
+            let CodeforcesJson {
+                api_key,
+                api_secret,
+            } = Token::Codeforces.parse(&content)?;
+            Ok((api_key, api_secret))
+        })
+        .transpose()
+}
+
+#[derive(Deserialize)]
+struct CodeforcesJson {
+    api_key: String,
+    api_secret: String,
+}
+
+/// A token stored in a [`CredentialStore`]. Each of them is stored as the same JSON as the
+/// plaintext file.
+#[derive(EnumString, EnumVariantNames, strum::Display, Debug, Clone, Copy)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Token {
+    Dropbox,
+    Yukicoder,
+    Codeforces,
+}
+
+impl Token {
+    pub(crate) const ALL: &'static [Self] = &[Self::Dropbox, Self::Yukicoder, Self::Codeforces];
+
+    /// Environment variables that take precedence over the store, namespaced by the profile.
+    pub(crate) fn env_vars(self, profile: Option<&str>) -> Vec<String> {
+        let (prefix, names): (_, &[_]) = match self {
+            Self::Dropbox => ("DROPBOX", &["ACCESS_TOKEN"]),
+            Self::Yukicoder => ("YUKICODER", &["API_KEY"]),
+            Self::Codeforces => ("CODEFORCES", &["API_KEY", "API_SECRET"]),
         };
-        crate::fs::create_dir_all(path.parent().unwrap())?;
-        crate::fs::write_json(path, &content)?;
-        content
+        names
+            .iter()
+            .map(|name| env_var_name(prefix, name, profile))
+            .collect()
+    }
+
+    /// Asks the user for the token and returns the JSON to store.
+    pub(crate) fn prompt(self, shell: &mut Shell) -> anyhow::Result<String> {
+        let content = match self {
+            Self::Dropbox => serde_json::json!({
+                "access_token": shell.read_password("Dropbox access token: ")?,
+            }),
+            Self::Yukicoder => shell.read_password("yukicoder API key: ")?.into(),
+            Self::Codeforces => serde_json::json!({
+                "api_key": shell.read_password("Codeforces API key: ")?,
+                "api_secret": shell.read_password("Codeforces API secret: ")?,
+            }),
+        };
+        Ok(content.to_string())
+    }
+
+    fn parse<T: for<'de> Deserialize<'de>>(self, content: &str) -> anyhow::Result<T> {
+        serde_json::from_str(content).with_context(|| format!("invalid credentials for `{self}`"))
+    }
+
+    fn file_name(self) -> String {
+        format!("{self}.json")
+    }
+}
+
+/// Where the [`Token`]s are stored.
+///
+/// The cookies are not stored here. They stay in plaintext in `cookies.jsonl` since
+/// `snowchains_core` reads and writes the file directly through `CookieStorage::with_jsonl`.
+pub(crate) enum CredentialStore {
+    Plaintext { dir: PathBuf },
+    Keyring { profile: Option<String> },
+    EncryptedFile { path: PathBuf },
+}
+
+impl CredentialStore {
+    const KEYRING_SERVICE: &'static str = "cargo-compete";
+
+    /// Selects the store with `$CARGO_COMPETE_CREDENTIAL_STORE` or `credentials.store` in the
+    /// user-level config.
+    ///
+    /// The tokens are namespaced by the profile in the same way as `cookies_path`.
+    pub(crate) fn load(cookies_path: &Path, profile: Option<&str>) -> anyhow::Result<Self> {
+        let kind = if let Some(kind) = env_var("CARGO_COMPETE_CREDENTIAL_STORE")? {
+            kind.parse()
+                .map_err(|_| anyhow!("invalid $CARGO_COMPETE_CREDENTIAL_STORE: {:?}", kind))?
+        } else {
+            user_config::load()?.credentials.store
+        };
+
+        Ok(match kind {
+            CredentialStoreKind::Plaintext => Self::Plaintext {
+                dir: cookies_path.with_file_name("tokens"),
+            },
+            CredentialStoreKind::Keyring => Self::Keyring {
+                profile: profile.map(ToOwned::to_owned),
+            },
+            CredentialStoreKind::EncryptedFile => Self::EncryptedFile {
+                path: cookies_path.with_file_name("tokens.enc"),
+            },
+        })
+    }
+
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Self::Plaintext { dir } => format!("plaintext files in `{}`", dir.display()),
+            Self::Keyring { profile: None } => "the OS keyring".to_owned(),
+            Self::Keyring {
+                profile: Some(profile),
+            } => format!("the OS keyring (profile `{profile}`)"),
+            Self::EncryptedFile { path } => format!("an encrypted file at `{}`", path.display()),
+        }
+    }
+
+    pub(crate) fn get(&self, token: Token, shell: &mut Shell) -> anyhow::Result<Option<String>> {
+        match self {
+            Self::Plaintext { dir } => {
+                let path = dir.join(token.file_name());
+                path.exists()
+                    .then(|| crate::fs::read_to_string(path))
+                    .transpose()
+            }
+            Self::Keyring { profile } => {
+                match keyring_entry(profile.as_deref(), token)?.get_password() {
+                    Ok(content) => Ok(Some(content)),
+                    Err(keyring::Error::NoEntry) => Ok(None),
+                    Err(err) => Err(err).with_context(|| "could not read the keyring"),
+                }
+            }
+            Self::EncryptedFile { path } => {
+                Ok(read_encrypted(path, shell)?.remove(&token.to_string()))
+            }
+        }
+    }
+
+    pub(crate) fn set(&self, token: Token, content: &str, shell: &mut Shell) -> anyhow::Result<()> {
+        match self {
+            Self::Plaintext { dir } => {
+                crate::fs::create_dir_all(dir)?;
+                crate::fs::write(dir.join(token.file_name()), content)
+            }
+            Self::Keyring { profile } => keyring_entry(profile.as_deref(), token)?
+                .set_password(content)
+                .with_context(|| "could not write to the keyring"),
+            Self::EncryptedFile { path } => {
+                let mut tokens = read_encrypted(path, shell)?;
+                tokens.insert(token.to_string(), content.to_owned());
+                write_encrypted(path, &tokens)
+            }
+        }
+    }
+
+    /// Returns `false` if the token is not stored.
+    pub(crate) fn remove(&self, token: Token, shell: &mut Shell) -> anyhow::Result<bool> {
+        match self {
+            Self::Plaintext { dir } => {
+                let path = dir.join(token.file_name());
+                if !path.exists() {
+                    return Ok(false);
+                }
+                crate::fs::remove_file(path)?;
+                Ok(true)
+            }
+            Self::Keyring { profile } => {
+                match keyring_entry(profile.as_deref(), token)?.delete_password() {
+                    Ok(()) => Ok(true),
+                    Err(keyring::Error::NoEntry) => Ok(false),
+                    Err(err) => Err(err).with_context(|| "could not write to the keyring"),
+                }
+            }
+            Self::EncryptedFile { path } => {
+                let mut tokens = read_encrypted(path, shell)?;
+                if tokens.remove(&token.to_string()).is_none() {
+                    return Ok(false);
+                }
+                write_encrypted(path, &tokens)?;
+                Ok(true)
+            }
+        }
+    }
+
+    fn get_or_prompt(&self, token: Token, shell: &mut Shell) -> anyhow::Result<String> {
+        if let Some(content) = self.get(token, shell)? {
+            return Ok(content);
+        }
+        let content = token.prompt(shell)?;
+        self.set(token, &content, shell)?;
+        Ok(content)
+    }
+}
+
+fn keyring_entry(profile: Option<&str>, token: Token) -> anyhow::Result<keyring::Entry> {
+    let user = match profile {
+        Some(profile) => format!("{profile}/{token}"),
+        None => token.to_string(),
     };
+    keyring::Entry::new(CredentialStore::KEYRING_SERVICE, &user)
+        .with_context(|| "could not access the keyring")
+}
 
-    return Ok((api_key, api_secret));
+/// The passphrase is asked at most once per process.
+static PASSPHRASE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+fn passphrase(shell: &mut Shell) -> anyhow::Result<String> {
+    let mut passphrase = PASSPHRASE.lock().unwrap();
+    if passphrase.is_none() {
+        *passphrase = Some(
+            if let Some(value) = env_var("CARGO_COMPETE_CREDENTIAL_PASSPHRASE")? {
+                value
+            } else {
+                shell.read_password("Passphrase for the credential store: ")?
+            },
+        );
+    }
+    Ok(passphrase.clone().unwrap())
+}
 
-    #[derive(Deserialize, Serialize)]
-    struct CodeforcesJson {
-        api_key: String,
-        api_secret: String,
+fn read_encrypted(
+    path: &std::path::Path,
+    shell: &mut Shell,
+) -> anyhow::Result<BTreeMap<String, String>> {
+    let passphrase = passphrase(shell)?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let plaintext = decrypt(&crate::fs::read_json(path)?, &passphrase)
+        .with_context(|| format!("could not decrypt `{}`", path.display()))?;
+    serde_json::from_slice(&plaintext)
+        .with_context(|| format!("could not parse the content of `{}`", path.display()))
+}
+
+fn write_encrypted(
+    path: &std::path::Path,
+    tokens: &BTreeMap<String, String>,
+) -> anyhow::Result<()> {
+    let passphrase = PASSPHRASE
+        .lock()
+        .unwrap()
+        .clone()
+        .expect("should have been read");
+    let encrypted = encrypt(&serde_json::to_vec(tokens)?, &passphrase)?;
+    crate::fs::create_dir_all(path.parent().unwrap())?;
+    crate::fs::write_json(path, encrypted)
+}
+
+#[derive(Deserialize, Serialize)]
+struct Encrypted {
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+fn encrypt(plaintext: &[u8], passphrase: &str) -> anyhow::Result<Encrypted> {
+    let mut salt = [0; 16];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(passphrase, &salt)?
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| anyhow!("could not encrypt the credentials"))?;
+    Ok(Encrypted {
+        salt: base64::encode(salt),
+        nonce: base64::encode(nonce),
+        ciphertext: base64::encode(ciphertext),
+    })
+}
+
+fn decrypt(encrypted: &Encrypted, passphrase: &str) -> anyhow::Result<Vec<u8>> {
+    let salt = base64::decode(&encrypted.salt)?;
+    let nonce = base64::decode(&encrypted.nonce)?;
+    let ciphertext = base64::decode(&encrypted.ciphertext)?;
+    if nonce.len() != 12 {
+        bail!("invalid nonce");
+    }
+    cipher(passphrase, &salt)?
+        .decrypt(Nonce::from_slice(&nonce), &*ciphertext)
+        .map_err(|_| anyhow!("wrong passphrase, or the file is corrupted"))
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> anyhow::Result<ChaCha20Poly1305> {
+    let mut key = [0; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| anyhow!("could not derive a key: {}", err))?;
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+/// `${PREFIX}_${NAME}`, or `${PREFIX}_${PROFILE}_${NAME}` under a profile so that the accounts do
+/// not share them. The profile name is uppercased, and `-` is replaced with `_`.
+fn env_var_name(prefix: &str, name: &str, profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!(
+            "{prefix}_{}_{name}",
+            profile.to_uppercase().replace('-', "_"),
+        ),
+        None => format!("{prefix}_{name}"),
     }
 }
 
@@ -108,12 +547,27 @@ fn env_var(name: &str) -> anyhow::Result<Option<String>> {
         .transpose()
 }
 
-fn token_path(file_name: &str) -> anyhow::Result<PathBuf> {
-    let data_local_dir =
-        dirs_next::data_local_dir().with_context(|| "could not find the local data directory")?;
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
 
-    Ok(data_local_dir
-        .join("cargo-compete")
-        .join("tokens")
-        .join(file_name))
+    #[test]
+    fn encrypt_and_decrypt() -> anyhow::Result<()> {
+        let encrypted = super::encrypt(b"{}", "passphrase")?;
+        assert_eq!(b"{}".to_vec(), super::decrypt(&encrypted, "passphrase")?);
+        assert!(super::decrypt(&encrypted, "wrong").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn env_var_name() {
+        assert_eq!(
+            "ATCODER_USERNAME",
+            super::env_var_name("ATCODER", "USERNAME", None),
+        );
+        assert_eq!(
+            "ATCODER_FOR_PRACTICE_USERNAME",
+            super::env_var_name("ATCODER", "USERNAME", Some("for-practice")),
+        );
+    }
 }
@@ -0,0 +1,311 @@
+//! `[http]` in the user-level `config.toml` and `compete.toml`.
+//!
+//! `snowchains_core` builds its own clients, so the settings are applied to it through `timeout`
+//! and [`with_env`]. `oj` and `oj-api` take them from [`env_vars`].
+
+use crate::shell::Shell;
+use anyhow::{bail, Context as _};
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    cell::RefCell,
+    env,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    thread,
+    time::Duration,
+};
+use url::Url;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_RETRIES: u32 = 2;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+thread_local! {
+    /// Per thread like the mode of `super::replay`.
+    static CONFIG: RefCell<HttpConfig> = RefCell::default();
+}
+
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct HttpConfig {
+    /// Timeout for each request. `"0s"` disables it.
+    #[serde(default, with = "humantime_serde")]
+    pub(crate) timeout: Option<Duration>,
+    /// Number of retries for idempotent requests that failed with a timeout, a connection error,
+    /// or a 5xx status.
+    pub(crate) retries: Option<u32>,
+    /// Proxy for all of the requests.
+    pub(crate) proxy: Option<Url>,
+    /// PEM file of additional root certificates. Relative to the file that contains it.
+    pub(crate) ca_bundle: Option<PathBuf>,
+}
+
+impl HttpConfig {
+    /// Takes each key from `self` if it exists, otherwise from `other`.
+    fn or(self, other: Self) -> Self {
+        Self {
+            timeout: self.timeout.or(other.timeout),
+            retries: self.retries.or(other.retries),
+            proxy: self.proxy.or(other.proxy),
+            ca_bundle: self.ca_bundle.or(other.ca_bundle),
+        }
+    }
+
+    fn resolve_ca_bundle(mut self, config_path: &Path) -> Self {
+        if let (Some(ca_bundle), Some(dir)) = (&mut self.ca_bundle, config_path.parent()) {
+            *ca_bundle = dir.join(&*ca_bundle);
+        }
+        self
+    }
+}
+
+/// Loads `[http]` from `compete.toml` in `cwd` or its parents and from the user-level config, and
+/// applies it to the following requests.
+///
+/// Like `crate::profile`, only `http` is read so that the commands not for a package work with a
+/// broken `compete.toml`.
+pub(crate) fn init(cwd: &Path) -> anyhow::Result<()> {
+    let user_config = crate::user_config::load()?
+        .http
+        .resolve_ca_bundle(&crate::user_config::path()?);
+
+    let config = match from_compete_toml(cwd)? {
+        Some(config) => config.or(user_config),
+        None => user_config,
+    };
+
+    if let Some(ca_bundle) = &config.ca_bundle {
+        if !ca_bundle.exists() {
+            bail!("`http.ca-bundle` not found: `{}`", ca_bundle.display());
+        }
+    }
+
+    CONFIG.with(|c| *c.borrow_mut() = config);
+    Ok(())
+}
+
+fn from_compete_toml(cwd: &Path) -> anyhow::Result<Option<HttpConfig>> {
+    let path = match cwd
+        .ancestors()
+        .map(|p| p.join("compete.toml"))
+        .find(|p| p.exists())
+    {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+
+    let config = crate::fs::read_to_string(&path)?
+        .parse::<toml::Value>()
+        .with_context(|| format!("could not read a TOML file at `{}`", path.display()))?;
+
+    config
+        .get("http")
+        .cloned()
+        .map(|http| {
+            http.try_into::<HttpConfig>()
+                .map(|http| http.resolve_ca_bundle(&path))
+                .with_context(|| format!("invalid `http` in `{}`", path.display()))
+        })
+        .transpose()
+}
+
+fn config() -> HttpConfig {
+    CONFIG.with(|c| c.borrow().clone())
+}
+
+pub(crate) fn timeout() -> Option<Duration> {
+    match config().timeout {
+        None => Some(DEFAULT_TIMEOUT),
+        Some(timeout) if timeout.is_zero() => None,
+        Some(timeout) => Some(timeout),
+    }
+}
+
+fn retries() -> u32 {
+    config().retries.unwrap_or(DEFAULT_RETRIES)
+}
+
+/// A client for the requests that are not made through `snowchains_core`.
+pub(crate) fn reqwest_client() -> anyhow::Result<reqwest::blocking::Client> {
+    let HttpConfig {
+        proxy, ca_bundle, ..
+    } = config();
+
+    let mut builder = reqwest::blocking::Client::builder();
+    if let Some(timeout) = timeout() {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(proxy) = proxy {
+        // Without the system proxies, which `reqwest` reads only once. See `with_env`.
+        builder = builder.no_proxy().proxy(reqwest::Proxy::all(proxy)?);
+    }
+    if let Some(ca_bundle) = ca_bundle {
+        let pem = crate::fs::read_to_string(&ca_bundle)?;
+        builder = builder.add_root_certificate(
+            reqwest::Certificate::from_pem(pem.as_bytes())
+                .with_context(|| format!("could not parse `{}`", ca_bundle.display()))?,
+        );
+    }
+    builder.build().map_err(Into::into)
+}
+
+/// The environment variables that give `proxy` and `ca-bundle` to `oj` and `oj-api`.
+pub(crate) fn env_vars() -> Vec<(&'static str, OsString)> {
+    let HttpConfig {
+        proxy, ca_bundle, ..
+    } = config();
+
+    let mut vars = vec![];
+    if let Some(proxy) = proxy {
+        vars.push(("HTTP_PROXY", proxy.as_str().into()));
+        vars.push(("HTTPS_PROXY", proxy.as_str().into()));
+    }
+    if let Some(ca_bundle) = ca_bundle {
+        vars.push(("SSL_CERT_FILE", ca_bundle.clone().into()));
+        vars.push(("REQUESTS_CA_BUNDLE", ca_bundle.into()));
+    }
+    vars
+}
+
+/// Runs an operation of `snowchains_core` with `proxy` and `ca-bundle`.
+///
+/// `snowchains_core` builds its clients in each operation and takes no settings for them except
+/// the timeout, so [`env_vars`] are set only while the operation runs and restored afterwards.
+/// `reqwest` reads the proxy only when the first client with the system proxies is built, which
+/// is why [`reqwest_client`] does not use the system proxies when `proxy` is set.
+pub(crate) fn with_env<T>(f: impl FnOnce() -> T) -> T {
+    static LOCK: Mutex<()> = Mutex::new(());
+
+    struct Restore(Vec<(&'static str, Option<OsString>)>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            for (key, value) in self.0.drain(..) {
+                match value {
+                    Some(value) => env::set_var(key, value),
+                    None => env::remove_var(key),
+                }
+            }
+        }
+    }
+
+    let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let vars = env_vars();
+    let _restore = Restore(
+        vars.iter()
+            .map(|&(key, _)| (key, env::var_os(key)))
+            .collect(),
+    );
+    for (key, value) in vars {
+        env::set_var(key, value);
+    }
+    f()
+}
+
+/// Sends a GET request to a JSON API.
+pub(crate) fn get_json<T: DeserializeOwned>(
+    client: &reqwest::blocking::Client,
+    url: &str,
+) -> anyhow::Result<T> {
+    let res = super::replay::send(client, client.get(url))
+        .and_then(|res| res.error_for_status().map_err(Into::into))
+        .with_context(|| format!("could not get {url}"))?;
+    serde_json::from_str(&res.text()?)
+        .with_context(|| format!("could not parse the JSON from {url}"))
+}
+
+/// Runs `f` again with exponential backoff while it fails with a transient error.
+///
+/// Only for idempotent operations.
+pub(crate) fn with_retries<T>(
+    shell: &RefCell<&mut Shell>,
+    mut f: impl FnMut() -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let retries = retries();
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 0;
+
+    loop {
+        shell.borrow_mut().take_last_status();
+        let result = f();
+        let last_status = shell.borrow_mut().take_last_status();
+        match result {
+            Err(err) if attempt < retries && is_transient(&err, last_status) => {
+                attempt += 1;
+                shell.borrow_mut().warn(format!(
+                    "{}. retrying in {} ({}/{})",
+                    err,
+                    humantime::format_duration(backoff),
+                    attempt,
+                    retries,
+                ))?;
+                thread::sleep(backoff);
+                backoff *= 2;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// `snowchains_core` reports unexpected statuses with `bail!`, so the status of the last response
+/// is taken from the shell.
+pub(crate) fn is_transient(err: &anyhow::Error, last_status: Option<StatusCode>) -> bool {
+    last_status.is_some_and(|s| s.is_server_error())
+        || err.chain().any(|cause| {
+            cause.downcast_ref::<reqwest::Error>().is_some_and(|err| {
+                err.is_timeout()
+                    || err.is_connect()
+                    || err.status().is_some_and(|s| s.is_server_error())
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HttpConfig;
+    use pretty_assertions::assert_eq;
+    use reqwest::StatusCode;
+    use std::{path::Path, time::Duration};
+
+    #[test]
+    fn or() -> anyhow::Result<()> {
+        let compete_toml = toml::from_str::<HttpConfig>(
+            r#"timeout = "1m"
+ca-bundle = "./ca.pem"
+"#,
+        )?
+        .resolve_ca_bundle(Path::new("/contests/compete.toml"));
+
+        let user_config = toml::from_str::<HttpConfig>(
+            r#"timeout = "10s"
+retries = 5
+proxy = "http://localhost:8080"
+"#,
+        )?;
+
+        assert_eq!(
+            HttpConfig {
+                timeout: Some(Duration::from_secs(60)),
+                retries: Some(5),
+                proxy: Some("http://localhost:8080".parse()?),
+                ca_bundle: Some(Path::new("/contests/./ca.pem").to_owned()),
+            },
+            compete_toml.or(user_config),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn is_transient() {
+        let err = &anyhow::anyhow!("expected [200], got 503 Service Unavailable");
+        assert!(super::is_transient(
+            err,
+            Some(StatusCode::SERVICE_UNAVAILABLE),
+        ));
+        assert!(!super::is_transient(err, Some(StatusCode::NOT_FOUND)));
+        assert!(!super::is_transient(err, None));
+    }
+}
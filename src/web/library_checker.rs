@@ -0,0 +1,277 @@
+//! [Library Checker](https://judge.yosupo.jp).
+//!
+//! The test cases are not served by the judge. Like `oj`, they are generated locally from a clone
+//! of [the problem repository](https://github.com/yosupo06/library-checker-problems), which
+//! requires Python 3 and a C++ compiler. The checker of each problem is compiled alongside them.
+
+use crate::{shell::Shell, web::retrieve_testcases::Problem};
+use anyhow::Context as _;
+use camino::{Utf8Path, Utf8PathBuf};
+use indexmap::IndexMap;
+use serde::Deserialize;
+use snowchains_core::testsuite::{
+    BatchTestSuite, CheckerShell, Match, PartialBatchTestCase, TestSuite,
+};
+use std::{borrow::Cow, env, path::Path, time::Duration};
+use url::Url;
+
+/// Names to the inputs and outputs.
+type TextFiles = IndexMap<String, (String, Option<String>)>;
+
+static REPOSITORY_URL: &str = "https://github.com/yosupo06/library-checker-problems";
+
+/// Whether the URL is of a problem, i.e. `https://judge.yosupo.jp/problem/{name}`.
+pub(crate) fn is_problem_url(url: &Url) -> bool {
+    problem_name(url).is_some()
+}
+
+fn problem_name(url: &Url) -> Option<&str> {
+    if url.host_str() != Some("judge.yosupo.jp") {
+        return None;
+    }
+    match *url.path_segments()?.collect::<Vec<_>>() {
+        ["problem", name] if !name.is_empty() => Some(name),
+        _ => None,
+    }
+}
+
+/// Generates the test cases of the problem. Only the examples are returned unless `system` is
+/// `true`.
+pub(crate) fn get_problem(
+    url: &Url,
+    system: bool,
+    cwd: &Utf8Path,
+    cache_dir: &Path,
+    shell: &mut Shell,
+) -> anyhow::Result<Problem<Option<String>>> {
+    let name =
+        problem_name(url).with_context(|| format!("not a Library Checker problem: {url}"))?;
+
+    let repo_dir = &repository_dir(cache_dir, cwd, shell)?;
+    let problem_dir = match find_problem_dir(repo_dir, name)? {
+        Some(problem_dir) => problem_dir,
+        None => {
+            // The problem may have been added after the clone.
+            crate::process::with_which("git", cwd)?
+                .args(&["pull", "--ff-only"])
+                .cwd(repo_dir)
+                .exec_with_shell_status(shell)?;
+            find_problem_dir(repo_dir, name)?
+                .with_context(|| format!("could not find `{name}` in `{repo_dir}`"))?
+        }
+    };
+
+    crate::process::with_which("python3", repo_dir)?
+        .args(&["generate.py", "-p", name])
+        .cwd(repo_dir)
+        .exec_with_shell_status(shell)?;
+
+    let (test_suite, text_files) = read_problem_dir(&problem_dir, system)?;
+
+    Ok(Problem {
+        index: None,
+        url: url.clone(),
+        test_suite: TestSuite::Batch(test_suite),
+        text_files,
+        contest_url: None,
+        statement: None,
+    })
+}
+
+/// Reads the generated test cases. Only the examples are in the test suite, and the rest are
+/// returned as text files if `system` is `true`.
+fn read_problem_dir(
+    problem_dir: &Utf8Path,
+    system: bool,
+) -> anyhow::Result<(BatchTestSuite, TextFiles)> {
+    let Info { timelimit } =
+        toml::from_str(&crate::fs::read_to_string(problem_dir.join("info.toml"))?)
+            .with_context(|| format!("could not parse `{}`", problem_dir.join("info.toml")))?;
+
+    let checker = problem_dir.join(format!("checker{}", env::consts::EXE_SUFFIX));
+
+    let mut cases = vec![];
+    let mut text_files = IndexMap::new();
+
+    for (name, input, output) in read_test_cases(problem_dir)? {
+        if system {
+            text_files.insert(name, (input, Some(output)));
+        } else if name.starts_with("example_") {
+            cases.push(PartialBatchTestCase {
+                name: Some(name),
+                r#in: input.into(),
+                out: Some(output.into()),
+                timelimit: None,
+                r#match: None,
+            });
+        }
+    }
+
+    let test_suite = BatchTestSuite {
+        timelimit: timelimit.map(Duration::from_secs_f64),
+        r#match: Match::Checker {
+            cmd: checker_cmd(&checker),
+            shell: CheckerShell::Bash,
+        },
+        cases,
+        extend: vec![],
+    };
+    return Ok((test_suite, text_files));
+
+    #[derive(Deserialize)]
+    struct Info {
+        /// In seconds.
+        timelimit: Option<f64>,
+    }
+}
+
+/// Checkers are always run with Bash, which is Git Bash on Windows. Bash there also accepts Windows
+/// paths written with `/` instead of `\`.
+fn checker_cmd(checker: &Utf8Path) -> String {
+    let checker = if cfg!(windows) {
+        checker.as_str().replace('\\', "/")
+    } else {
+        checker.as_str().to_owned()
+    };
+    let checker = shell_escape::unix::escape(Cow::from(checker));
+    format!(r#"{checker} "$INPUT" "$ACTUAL_OUTPUT" "$EXPECTED_OUTPUT""#)
+}
+
+/// Clones the problem repository into `cache_dir` if it does not exist yet.
+fn repository_dir(
+    cache_dir: &Path,
+    cwd: &Utf8Path,
+    shell: &mut Shell,
+) -> anyhow::Result<Utf8PathBuf> {
+    let cache_dir = Utf8PathBuf::from_path_buf(cache_dir.to_owned())
+        .map_err(|p| anyhow::anyhow!("invalid utf-8 path: {:?}", p))?;
+    let repo_dir = cache_dir.join("library-checker-problems");
+
+    if !repo_dir.exists() {
+        crate::fs::create_dir_all(&cache_dir)?;
+        crate::process::with_which("git", cwd)?
+            .args(&["clone", "--depth", "1", REPOSITORY_URL, repo_dir.as_str()])
+            .cwd(&cache_dir)
+            .exec_with_shell_status(shell)?;
+    }
+    Ok(repo_dir)
+}
+
+/// Finds `{ category }/{ name }` that contains `info.toml`.
+fn find_problem_dir(repo_dir: &Utf8Path, name: &str) -> anyhow::Result<Option<Utf8PathBuf>> {
+    Ok(crate::fs::read_dir(repo_dir)?
+        .into_iter()
+        .map(|category| category.join(name))
+        .find(|problem_dir| problem_dir.join("info.toml").exists())
+        .and_then(|problem_dir| Utf8PathBuf::from_path_buf(problem_dir).ok()))
+}
+
+/// Reads `in/*.in` and `out/*.out` generated by `generate.py`, sorted by the name.
+fn read_test_cases(problem_dir: &Utf8Path) -> anyhow::Result<Vec<(String, String, String)>> {
+    let mut test_cases = vec![];
+    for in_path in crate::fs::read_dir(problem_dir.join("in"))? {
+        if in_path.extension() != Some("in".as_ref()) {
+            continue;
+        }
+        let name = in_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        let out_path = problem_dir.join("out").join(format!("{name}.out"));
+        let input = crate::fs::read_to_string(&in_path)?;
+        let output = crate::fs::read_to_string(&out_path)?;
+        test_cases.push((name, input, output));
+    }
+    test_cases.sort();
+    Ok(test_cases)
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8Path;
+    use pretty_assertions::assert_eq;
+    use snowchains_core::testsuite::{CheckerShell, Match};
+    use std::{env, time::Duration};
+
+    #[test]
+    fn read_problem_dir() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-library-checker-")
+            .tempdir()?;
+        let problem_dir = Utf8Path::from_path(tempdir.path()).unwrap();
+
+        crate::fs::write(problem_dir.join("info.toml"), "timelimit = 5.0\n")?;
+        for (name, input, output) in &[
+            ("random_00", "3 4\n", "7\n"),
+            ("example_01", "1000000000 1000000000\n", "2000000000\n"),
+            ("example_00", "1 2\n", "3\n"),
+        ] {
+            crate::fs::create_dir_all(problem_dir.join("in"))?;
+            crate::fs::create_dir_all(problem_dir.join("out"))?;
+            crate::fs::write(problem_dir.join("in").join(format!("{name}.in")), input)?;
+            crate::fs::write(problem_dir.join("out").join(format!("{name}.out")), output)?;
+        }
+        crate::fs::write(problem_dir.join("in").join("params.h"), "")?;
+
+        let checker = problem_dir.join(format!("checker{}", env::consts::EXE_SUFFIX));
+        let expected_match = Match::Checker {
+            cmd: super::checker_cmd(&checker),
+            shell: CheckerShell::Bash,
+        };
+
+        let (test_suite, text_files) = super::read_problem_dir(problem_dir, false)?;
+        assert_eq!(Some(Duration::from_secs(5)), test_suite.timelimit);
+        assert_eq!(expected_match, test_suite.r#match);
+        assert_eq!(
+            vec![Some("example_00"), Some("example_01")],
+            test_suite
+                .cases
+                .iter()
+                .map(|c| c.name.as_deref())
+                .collect::<Vec<_>>(),
+        );
+        assert!(text_files.is_empty());
+
+        let (test_suite, text_files) = super::read_problem_dir(problem_dir, true)?;
+        assert!(test_suite.cases.is_empty());
+        assert_eq!(
+            vec!["example_00", "example_01", "random_00"],
+            text_files.keys().collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            ("3 4\n".to_owned(), Some("7\n".to_owned())),
+            text_files["random_00"],
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn checker_cmd() {
+        let (checker, expected) = if cfg!(windows) {
+            (
+                r"C:\Users\me\AppData\Local\cargo-compete\aplusb\checker.exe",
+                r#"C:/Users/me/AppData/Local/cargo-compete/aplusb/checker.exe "$INPUT" "$ACTUAL_OUTPUT" "$EXPECTED_OUTPUT""#,
+            )
+        } else {
+            (
+                "/home/me/.cache/cargo compete/aplusb/checker",
+                r#"'/home/me/.cache/cargo compete/aplusb/checker' "$INPUT" "$ACTUAL_OUTPUT" "$EXPECTED_OUTPUT""#,
+            )
+        };
+        assert_eq!(expected, super::checker_cmd(Utf8Path::new(checker)));
+    }
+
+    #[test]
+    fn is_problem_url() {
+        assert!(super::is_problem_url(
+            &"https://judge.yosupo.jp/problem/aplusb".parse().unwrap()
+        ));
+        assert!(!super::is_problem_url(
+            &"https://judge.yosupo.jp/submissions".parse().unwrap()
+        ));
+        assert!(!super::is_problem_url(
+            &"https://atcoder.jp/problem/aplusb".parse().unwrap()
+        ));
+    }
+}
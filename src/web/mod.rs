@@ -1,11 +1,34 @@
+pub(crate) mod aoj;
+pub(crate) mod atcoder_problems;
+pub(crate) mod cache;
+pub(crate) mod contests;
 pub(crate) mod credentials;
+pub(crate) mod http;
+pub(crate) mod library_checker;
+pub(crate) mod replay;
 pub(crate) mod retrieve_testcases;
+pub(crate) mod schedule;
+pub(crate) mod session;
+pub(crate) mod standings;
+pub(crate) mod statement;
 pub(crate) mod url;
 
 use std::time::Duration;
 
-pub(crate) const TIMEOUT: Option<Duration> = Some(Duration::from_secs(30));
+/// Intervals between submissions when submitting multiple problems at once.
+pub(crate) const ATCODER_SUBMIT_INTERVAL: Duration = Duration::from_secs(5);
+/// Codeforces rejects submissions sent in quick succession.
+pub(crate) const CODEFORCES_SUBMIT_INTERVAL: Duration = Duration::from_secs(10);
+pub(crate) const YUKICODER_SUBMIT_INTERVAL: Duration = Duration::from_secs(5);
+pub(crate) const OTHER_SUBMIT_INTERVAL: Duration = Duration::from_secs(5);
 
 pub(crate) static ATCODER_RUST_LANG_ID: &str = "4050";
 pub(crate) static CODEFORCES_RUST_LANG_ID: &str = "75";
 pub(crate) static YUKICODER_RUST_LANG_ID: &str = "rust";
+pub(crate) static AOJ_RUST_LANG_ID: &str = "Rust";
+
+pub(crate) static ATCODER_SOURCE_SIZE_LIMIT: &str = "512KiB";
+pub(crate) static CODEFORCES_SOURCE_SIZE_LIMIT: &str = "64KiB";
+
+/// `#![feature(..)]` is rejected by the stable toolchains that the judges use.
+pub(crate) static FORBIDDEN_PATTERN_FEATURE_ATTRIBUTE: &str = r"#!\s*\[\s*feature\s*\(";
@@ -0,0 +1,239 @@
+//! Record and replay of the HTTP exchanges that cargo-compete sends by itself.
+//!
+//! With `$CARGO_COMPETE_RECORD_DIR`, the responses to the requests sent with [`send`] are recorded
+//! in the directory. With `crate::Context::replay_dir`, they are replayed from the directory and
+//! nothing is sent.
+//!
+//! The requests of `snowchains_core` (`login`, `submit`, `download`, and so on for AtCoder,
+//! Codeforces, and yukicoder) do not go through [`send`], so they are neither recorded nor
+//! replayed.
+//!
+//! A request is identified by its method and URL, or without the query if it is signed with the
+//! credentials. Request bodies, cookies, and `Set-Cookie` are
+//! never recorded since they may contain the credentials. If the same request is sent more than
+//! once, only the last response is kept.
+
+use super::cache::Record;
+use anyhow::{anyhow, bail, Context as _};
+use reqwest::{
+    blocking::{Client, RequestBuilder, Response},
+    header::SET_COOKIE,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use std::{
+    cell::RefCell,
+    env,
+    error::Error as _,
+    path::{Path, PathBuf},
+};
+
+thread_local! {
+    /// Per thread since the tests run in parallel.
+    static MODE: RefCell<Option<Mode>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone)]
+enum Mode {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+/// Replays from `replay_dir` if any, otherwise records if `$CARGO_COMPETE_RECORD_DIR` is set.
+pub(crate) fn init(replay_dir: Option<PathBuf>) {
+    let mode = match replay_dir {
+        Some(replay_dir) => Some(Mode::Replay(replay_dir)),
+        None => env::var_os("CARGO_COMPETE_RECORD_DIR")
+            .filter(|v| !v.is_empty())
+            .map(|record_dir| Mode::Record(record_dir.into())),
+    };
+    MODE.with(|m| *m.borrow_mut() = mode);
+}
+
+fn mode() -> Option<Mode> {
+    MODE.with(|m| m.borrow().clone())
+}
+
+/// Sends a request, or replays the recorded response.
+pub(crate) fn send(client: &Client, req: RequestBuilder) -> anyhow::Result<Response> {
+    send_with_query(client, req, true)
+}
+
+/// Like [`send`], but keeps the query out of the record and the errors, for the requests signed
+/// with the credentials in the query.
+pub(crate) fn send_without_query(client: &Client, req: RequestBuilder) -> anyhow::Result<Response> {
+    send_with_query(client, req, false)
+}
+
+fn send_with_query(
+    client: &Client,
+    req: RequestBuilder,
+    with_query: bool,
+) -> anyhow::Result<Response> {
+    let req = req.build()?;
+    let mut url = req.url().clone();
+    if !with_query {
+        url.set_query(None);
+    }
+    let key = &format!("{} {}", req.method(), url);
+
+    let execute = |req| -> anyhow::Result<_> {
+        client.execute(req).map_err(|err| {
+            if with_query {
+                err.into()
+            } else {
+                // `reqwest::Error` shows the URL.
+                err.source()
+                    .map(|source| anyhow!("{}", source))
+                    .unwrap_or_else(|| anyhow!("could not send the request"))
+            }
+        })
+    };
+
+    match mode() {
+        None => execute(req),
+        Some(Mode::Record(dir)) => {
+            let res = execute(req)?;
+            let res = RecordedResponse {
+                status: res.status().as_u16(),
+                headers: res
+                    .headers()
+                    .iter()
+                    .filter(|(name, _)| *name != SET_COOKIE)
+                    .flat_map(|(name, value)| {
+                        Some((name.as_str().to_owned(), value.to_str().ok()?.to_owned()))
+                    })
+                    .collect(),
+                body: res.text()?,
+            };
+            write(&dir, key, &res)?;
+            res.into_response()
+        }
+        Some(Mode::Replay(dir)) => read::<RecordedResponse>(&dir, key)?.into_response(),
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct RecordedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl RecordedResponse {
+    fn into_response(self) -> anyhow::Result<Response> {
+        let mut res = ::http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            res = res.header(&**name, &**value);
+        }
+        Ok(res.body(self.body)?.into())
+    }
+}
+
+fn path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{:x}.json", Sha256::digest(key.as_bytes())))
+}
+
+fn write(dir: &Path, key: &str, value: &impl Serialize) -> anyhow::Result<()> {
+    crate::fs::create_dir_all(dir)?;
+    crate::fs::write_json(path(dir, key), Record { key, value })
+        .with_context(|| format!("could not record `{key}`"))
+}
+
+fn read<T: DeserializeOwned>(dir: &Path, key: &str) -> anyhow::Result<T> {
+    let path = path(dir, key);
+    if !path.exists() {
+        bail!("`{}` is not recorded in `{}`", key, dir.display());
+    }
+    let Record::<String, _> { value, .. } = crate::fs::read_json(path)?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Mode, MODE};
+    use pretty_assertions::assert_eq;
+    use reqwest::blocking::Client;
+    use std::{
+        io::{BufRead as _, BufReader, Write as _},
+        net::TcpListener,
+        thread,
+    };
+
+    #[test]
+    fn record_and_replay() -> anyhow::Result<()> {
+        let dir = tempfile::Builder::new()
+            .prefix("cargo-compete-replay-test-")
+            .tempdir()?;
+
+        // Serves one response, then goes away.
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/hello?name=ferris", listener.local_addr()?);
+        let server = thread::spawn(move || -> std::io::Result<()> {
+            let (stream, _) = listener.accept()?;
+            let mut stream = BufReader::new(stream);
+            let mut line = "".to_owned();
+            while stream.read_line(&mut line)? > 2 {
+                line.clear();
+            }
+            stream.get_mut().write_all(
+                b"HTTP/1.1 200 OK\r\n\
+                  Content-Type: text/plain\r\n\
+                  Set-Cookie: session=secret\r\n\
+                  Content-Length: 5\r\n\
+                  Connection: close\r\n\
+                  \r\n\
+                  hello",
+            )
+        });
+
+        let client = &Client::builder().no_proxy().build()?;
+        let set_mode = |mode| MODE.with(|m| *m.borrow_mut() = Some(mode));
+
+        set_mode(Mode::Record(dir.path().to_owned()));
+        let res = super::send(client, client.get(&url))?;
+        assert_eq!(None, res.headers().get("set-cookie"));
+        assert_eq!("hello", res.text()?);
+        server.join().unwrap()?;
+
+        set_mode(Mode::Replay(dir.path().to_owned()));
+        let res = super::send(client, client.get(&url))?;
+        assert_eq!(200, res.status().as_u16());
+        assert_eq!("text/plain", res.headers()["content-type"]);
+        assert_eq!(None, res.headers().get("set-cookie"));
+        assert_eq!("hello", res.text()?);
+
+        let err = super::send(client, client.post(&url)).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with(&format!("`POST {url}` is not recorded")));
+
+        MODE.with(|m| *m.borrow_mut() = None);
+        dir.close().map_err(Into::into)
+    }
+
+    #[test]
+    fn send_without_query() -> anyhow::Result<()> {
+        let dir = tempfile::Builder::new()
+            .prefix("cargo-compete-replay-test-")
+            .tempdir()?;
+
+        // Nothing listens to it.
+        let addr = TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+        let url = format!("http://{addr}/api/user.friends?apiKey=secret");
+        let client = &Client::builder().no_proxy().build()?;
+
+        MODE.with(|m| *m.borrow_mut() = None);
+        let err = super::send_without_query(client, client.get(&url)).unwrap_err();
+        assert!(!format!("{err:?}").contains("secret"));
+
+        MODE.with(|m| *m.borrow_mut() = Some(Mode::Replay(dir.path().to_owned())));
+        let err = super::send_without_query(client, client.get(&url)).unwrap_err();
+        assert!(err.to_string().starts_with(&format!(
+            "`GET http://{addr}/api/user.friends` is not recorded"
+        )));
+
+        MODE.with(|m| *m.borrow_mut() = None);
+        dir.close().map_err(Into::into)
+    }
+}
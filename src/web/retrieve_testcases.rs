@@ -1,15 +1,18 @@
 use crate::{
+    config::CargoCompeteConfigStatement,
     oj_api,
     project::{PackageExt as _, PackageMetadataCargoCompeteBinExample},
     shell::Shell,
-    web::credentials,
+    web::{aoj, cache::ProblemCache, credentials, http, library_checker, statement::Statement},
 };
-use anyhow::{ensure, Context};
+use anyhow::{bail, ensure, Context};
 use camino::{Utf8Path, Utf8PathBuf};
 use cargo_metadata as cm;
 use indexmap::{indexmap, IndexMap};
+use itertools::Itertools as _;
 use maplit::{btreemap, btreeset};
 use percent_encoding::PercentDecode;
+use serde::{Deserialize, Serialize};
 use snowchains_core::{
     testsuite::{Additional, BatchTestSuite, Match, PartialBatchTestCase, TestSuite},
     web::{
@@ -32,54 +35,83 @@ use url::Url;
 
 pub(crate) fn dl_only_system_test_cases(
     url: &Url,
+    offline: bool,
     cookies_path: &Path,
+    profile: Option<&str>,
     cwd: &Utf8Path,
+    cache_dir: &Path,
     shell: &mut Shell,
 ) -> anyhow::Result<()> {
+    if offline {
+        bail!(
+            "the system test cases for {url} are not downloaded yet, and offline mode is enabled"
+        );
+    }
+
     let system_test_cases_dir = &system_test_cases_dir(url)?;
     let in_dir = &system_test_cases_dir.join("in");
     let out_dir = &system_test_cases_dir.join("out");
 
     let Problem { text_files, .. } = match url.host_str() {
         Some("atcoder.jp") => {
+            let dropbox_access_token =
+                credentials::dropbox_access_token(cookies_path, profile, shell)?;
+
             let shell = RefCell::new(shell.borrow_mut());
 
-            let username_and_password =
-                &mut credentials::username_and_password(&shell, "Username: ", "Password: ");
+            let username_and_password = &mut credentials::username_and_password(
+                &shell,
+                profile,
+                PlatformKind::Atcoder,
+                "Username: ",
+                "Password: ",
+            );
 
-            take(Atcoder::exec(RetrieveTestCases {
-                targets: ProblemsInContest::Urls {
-                    urls: btreeset!(url.clone()),
-                },
-                credentials: AtcoderRetrieveSampleTestCasesCredentials {
-                    username_and_password,
-                },
-                full: Some(RetrieveFullTestCases {
-                    credentials: AtcoderRetrieveFullTestCasesCredentials {
-                        dropbox_access_token: credentials::dropbox_access_token()?,
-                    },
-                }),
-                cookie_storage: CookieStorage::with_jsonl(cookies_path)?,
-                timeout: crate::web::TIMEOUT,
-                shell: &shell,
+            take(http::with_retries(&shell, || {
+                http::with_env(|| {
+                    Atcoder::exec(RetrieveTestCases {
+                        targets: ProblemsInContest::Urls {
+                            urls: btreeset!(url.clone()),
+                        },
+                        credentials: AtcoderRetrieveSampleTestCasesCredentials {
+                            username_and_password,
+                        },
+                        full: Some(RetrieveFullTestCases {
+                            credentials: AtcoderRetrieveFullTestCasesCredentials {
+                                dropbox_access_token: dropbox_access_token.clone(),
+                            },
+                        }),
+                        cookie_storage: CookieStorage::with_jsonl(cookies_path)?,
+                        timeout: http::timeout(),
+                        shell: &shell,
+                    })
+                })
             })?)
         }
-        Some("yukicoder.me") => take(Yukicoder::exec(RetrieveTestCases {
-            targets: YukicoderRetrieveTestCasesTargets::Urls(btreeset!(url.clone())),
-            credentials: (),
-            full: Some(RetrieveFullTestCases {
-                credentials: YukicoderRetrieveFullTestCasesCredentials {
-                    api_key: credentials::yukicoder_api_key(shell)?,
-                },
-            }),
-            cookie_storage: (),
-            timeout: crate::web::TIMEOUT,
-            shell: &RefCell::new(shell.borrow_mut()),
-        })?),
-        _ => {
-            let problem = oj_api::get_problem(url, true, cwd, shell)?;
-            Problem::from_oj_api(problem, true)
+        Some("yukicoder.me") => {
+            let api_key = credentials::yukicoder_api_key(cookies_path, profile, shell)?;
+
+            let shell = RefCell::new(shell.borrow_mut());
+
+            take(http::with_retries(&shell, || {
+                http::with_env(|| {
+                    Yukicoder::exec(RetrieveTestCases {
+                        targets: YukicoderRetrieveTestCasesTargets::Urls(btreeset!(url.clone())),
+                        credentials: (),
+                        full: Some(RetrieveFullTestCases {
+                            credentials: YukicoderRetrieveFullTestCasesCredentials {
+                                api_key: api_key.clone(),
+                            },
+                        }),
+                        cookie_storage: (),
+                        timeout: http::timeout(),
+                        shell: &shell,
+                    })
+                })
+            })?)
         }
+        // Too large for `ProblemCache`. They are cached in `system_test_cases_dir` instead.
+        _ => dl_from_other(url, true, cwd, cache_dir, None, shell)?,
     };
 
     if !text_files.is_empty() {
@@ -114,27 +146,118 @@ pub(crate) fn dl_for_existing_package(
     overwrite: bool,
     workspace_root: &Utf8Path,
     test_suite_path: &liquid::Template,
+    statement: &CargoCompeteConfigStatement,
     cookies_path: &Path,
+    profile: Option<&str>,
+    cache_dir: &Path,
+    cache: &ProblemCache,
     shell: &mut Shell,
 ) -> anyhow::Result<()> {
+    retrieve_for_targets(
+        package_metadata_bin,
+        package_metadata_example,
+        bin_name_aliases,
+        example_name_aliases,
+        full,
+        workspace_root,
+        statement,
+        cookies_path,
+        profile,
+        cache_dir,
+        cache,
+        shell,
+    )?
+    .save(
+        workspace_root,
+        package.manifest_dir(),
+        test_suite_path,
+        overwrite,
+        shell,
+    )?;
+    Ok(())
+}
+
+/// Problems retrieved for `bin`/`example` targets, not saved yet.
+pub(crate) struct RetrievedForTargets<'a> {
+    pub(crate) problems: Vec<Problem<Option<String>>>,
+    /// Names and aliases of the targets for each URL.
+    targets: BTreeMap<&'a Url, BTreeSet<(&'a String, &'a String)>>,
+}
+
+impl RetrievedForTargets<'_> {
+    /// Saves the test cases of `problems` for each of the targets, returning the paths of the test
+    /// suites.
+    pub(crate) fn save(
+        self,
+        workspace_root: &Utf8Path,
+        pkg_manifest_dir: &Utf8Path,
+        test_suite_path: &liquid::Template,
+        overwrite: bool,
+        shell: &mut Shell,
+    ) -> anyhow::Result<Vec<Utf8PathBuf>> {
+        let Self { problems, targets } = self;
+
+        save_test_cases(
+            workspace_root,
+            pkg_manifest_dir,
+            test_suite_path,
+            overwrite,
+            problems,
+            |url, _| {
+                targets
+                    .get(url)
+                    .into_iter()
+                    .flatten()
+                    .map(|&(bin_name, _)| bin_name.clone())
+                    .collect()
+            },
+            |url, _| {
+                targets
+                    .get(url)
+                    .into_iter()
+                    .flatten()
+                    .map(|&(_, bin_alias)| bin_alias.clone())
+                    .collect()
+            },
+            shell,
+        )
+    }
+}
+
+/// Retrieves the problems of the `bin`/`example` targets, which may be on different platforms.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn retrieve_for_targets<'a>(
+    package_metadata_bin: &'a IndexMap<String, PackageMetadataCargoCompeteBinExample>,
+    package_metadata_example: &'a IndexMap<String, PackageMetadataCargoCompeteBinExample>,
+    bin_name_aliases: Option<&HashSet<String>>,
+    example_name_aliases: Option<&HashSet<String>>,
+    full: bool,
+    workspace_root: &Utf8Path,
+    statement: &CargoCompeteConfigStatement,
+    cookies_path: &Path,
+    profile: Option<&str>,
+    cache_dir: &Path,
+    cache: &ProblemCache,
+    shell: &mut Shell,
+) -> anyhow::Result<RetrievedForTargets<'a>> {
     let mut snowchains_targets: BTreeMap<_, BTreeMap<_, BTreeSet<_>>> = btreemap!();
     let mut oj_targets: BTreeMap<_, BTreeSet<_>> = btreemap!();
     let mut bin_name_aliases = bin_name_aliases.cloned();
     let mut example_name_aliases = example_name_aliases.cloned();
 
-    for (name, PackageMetadataCargoCompeteBinExample { alias, problem }) in itertools::chain(
+    for (name, PackageMetadataCargoCompeteBinExample { alias, problem, .. }) in itertools::chain(
         package_metadata_bin.iter().filter(
             |&(name, PackageMetadataCargoCompeteBinExample { alias, .. })| {
                 bin_name_aliases
                     .as_mut()
-                    .map_or(true, |ss| ss.remove(name) || ss.remove(alias))
+                    .is_none_or(|ss| ss.remove(name) || ss.remove(alias))
             },
         ),
         package_metadata_example.iter().filter(
             |&(name, PackageMetadataCargoCompeteBinExample { alias, .. })| {
                 example_name_aliases
                     .as_mut()
-                    .map_or(true, |ss| ss.remove(name) || ss.remove(alias))
+                    .is_none_or(|ss| ss.remove(name) || ss.remove(alias))
             },
         ),
     ) {
@@ -159,94 +282,158 @@ pub(crate) fn dl_for_existing_package(
     if let Some(targets) = snowchains_targets.get(&PlatformKind::Atcoder) {
         let urls = targets.keys().copied().cloned().collect();
         let targets = ProblemsInContest::Urls { urls };
-        outcome.extend(dl_from_atcoder(targets, full, cookies_path, shell)?);
+        outcome.extend(dl_from_atcoder(
+            targets,
+            full,
+            cookies_path,
+            profile,
+            cache,
+            shell,
+        )?);
     }
 
     if let Some(targets) = snowchains_targets.get(&PlatformKind::Codeforces) {
         let urls = targets.keys().copied().cloned().collect();
         let targets = ProblemsInContest::Urls { urls };
-        outcome.extend(dl_from_codeforces(targets, cookies_path, shell)?);
+        outcome.extend(dl_from_codeforces(
+            targets,
+            cookies_path,
+            profile,
+            cache,
+            shell,
+        )?);
     }
 
     if let Some(targets) = snowchains_targets.get(&PlatformKind::Yukicoder) {
         let urls = targets.keys().copied().cloned().collect();
         let targets = YukicoderRetrieveTestCasesTargets::Urls(urls);
-        outcome.extend(dl_from_yukicoder(targets, full, shell)?);
+        outcome.extend(dl_from_yukicoder(
+            targets,
+            full,
+            cookies_path,
+            profile,
+            cache,
+            shell,
+        )?);
     }
 
     let mut outcome = outcome.into_iter().map(Into::into).collect::<Vec<_>>();
 
     for url in oj_targets.keys() {
-        outcome.push(Problem::from_oj_api(
-            oj_api::get_problem(url, full, workspace_root, shell)?,
+        outcome.push(dl_from_other(
+            url,
             full,
-        ));
+            workspace_root,
+            cache_dir,
+            Some(cache),
+            shell,
+        )?);
     }
 
-    save_test_cases(
-        workspace_root,
-        package.manifest_dir(),
-        test_suite_path,
-        overwrite,
-        outcome,
-        |url, _| {
-            snowchains_targets
-                .values()
-                .chain(iter::once(&oj_targets))
-                .flat_map(|m| m.get(url))
-                .flatten()
-                .map(|&(bin_name, _)| bin_name.clone())
-                .collect()
-        },
-        |url, _| {
-            snowchains_targets
-                .values()
-                .chain(iter::once(&oj_targets))
-                .flat_map(|m| m.get(url))
-                .flatten()
-                .map(|&(_, bin_alias)| bin_alias.clone())
-                .collect()
-        },
-        shell,
-    )?;
-    Ok(())
+    crate::web::statement::retrieve_all(&mut outcome, statement, cookies_path, cache, shell)?;
+
+    let mut targets = oj_targets;
+    targets.extend(snowchains_targets.into_values().flatten());
+
+    Ok(RetrievedForTargets {
+        problems: outcome,
+        targets,
+    })
+}
+
+/// Retrieves a problem on a platform other than AtCoder, Codeforces, and yukicoder.
+///
+/// Library Checker and AOJ are supported natively. The others are retrieved with `oj-api`.
+pub(crate) fn dl_from_other(
+    url: &Url,
+    system: bool,
+    cwd: &Utf8Path,
+    cache_dir: &Path,
+    cache: Option<&ProblemCache>,
+    shell: &mut Shell,
+) -> anyhow::Result<Problem<Option<String>>> {
+    // The system test cases are too large for `ProblemCache`.
+    let cache = cache.filter(|_| !system);
+
+    if !(library_checker::is_problem_url(url) || aoj::is_problem_url(url)) {
+        let problem = oj_api::get_problem(url, system, cwd, cache, shell)?;
+        return Ok(Problem::from_oj_api(problem, system));
+    }
+
+    let get_problem = |shell: &mut Shell| {
+        if library_checker::is_problem_url(url) {
+            library_checker::get_problem(url, system, cwd, cache_dir, shell)
+        } else {
+            aoj::get_problem(url, system, cwd, shell)
+        }
+    };
+
+    if let Some(cache) = cache {
+        let key = format!("get-problem {url}");
+        cache.get_or_retrieve(&key, || get_problem(shell))
+    } else {
+        get_problem(shell)
+    }
 }
 
 pub(crate) fn dl_from_atcoder(
     targets: ProblemsInContest,
     full: bool,
     cookies_path: &Path,
+    profile: Option<&str>,
+    cache: &ProblemCache,
     shell: &mut Shell,
 ) -> anyhow::Result<Vec<Problem<String>>> {
-    let shell = RefCell::new(shell.borrow_mut());
-
-    let credentials = AtcoderRetrieveSampleTestCasesCredentials {
-        username_and_password: &mut credentials::username_and_password(
-            &shell,
-            "Username: ",
-            "Password: ",
-        ),
-    };
-
-    let full = if full {
-        Some(RetrieveFullTestCases {
-            credentials: AtcoderRetrieveFullTestCasesCredentials {
-                dropbox_access_token: credentials::dropbox_access_token()?,
-            },
-        })
+    let key = &cache_key(PlatformKind::Atcoder, &targets);
+    let retrieve = || dl_from_atcoder_without_cache(targets, full, cookies_path, profile, shell);
+    if full {
+        // The system test cases are too large for `ProblemCache`.
+        cache.retrieve_uncached(&format!("{key} full"), retrieve)
     } else {
-        None
-    };
+        cache.get_or_retrieve(key, retrieve)
+    }
+}
+
+fn dl_from_atcoder_without_cache(
+    targets: ProblemsInContest,
+    full: bool,
+    cookies_path: &Path,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<Vec<Problem<String>>> {
+    let dropbox_access_token = full
+        .then(|| credentials::dropbox_access_token(cookies_path, profile, shell))
+        .transpose()?;
 
-    let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
+    let shell = RefCell::new(shell.borrow_mut());
 
-    Atcoder::exec(RetrieveTestCases {
-        targets,
-        credentials,
-        full,
-        cookie_storage,
-        timeout: crate::web::TIMEOUT,
-        shell: &shell,
+    let username_and_password = &mut credentials::username_and_password(
+        &shell,
+        profile,
+        PlatformKind::Atcoder,
+        "Username: ",
+        "Password: ",
+    );
+
+    http::with_retries(&shell, || {
+        http::with_env(|| {
+            Atcoder::exec(RetrieveTestCases {
+                targets: targets.clone(),
+                credentials: AtcoderRetrieveSampleTestCasesCredentials {
+                    username_and_password,
+                },
+                full: dropbox_access_token.clone().map(|dropbox_access_token| {
+                    RetrieveFullTestCases {
+                        credentials: AtcoderRetrieveFullTestCasesCredentials {
+                            dropbox_access_token,
+                        },
+                    }
+                }),
+                cookie_storage: CookieStorage::with_jsonl(cookies_path)?,
+                timeout: http::timeout(),
+                shell: &shell,
+            })
+        })
     })
     .map(|RetrieveTestCasesOutcome { problems, .. }| problems.into_iter().map(Into::into).collect())
 }
@@ -254,27 +441,45 @@ pub(crate) fn dl_from_atcoder(
 pub(crate) fn dl_from_codeforces(
     targets: ProblemsInContest,
     cookies_path: &Path,
+    profile: Option<&str>,
+    cache: &ProblemCache,
     shell: &mut Shell,
 ) -> anyhow::Result<Vec<Problem<String>>> {
-    let shell = RefCell::new(shell.borrow_mut());
-
-    let credentials = CodeforcesRetrieveSampleTestCasesCredentials {
-        username_and_password: &mut credentials::username_and_password(
-            &shell,
-            "Username: ",
-            "Password: ",
-        ),
-    };
+    let key = &cache_key(PlatformKind::Codeforces, &targets);
+    cache.get_or_retrieve(key, || {
+        dl_from_codeforces_without_cache(targets, cookies_path, profile, shell)
+    })
+}
 
-    let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
+fn dl_from_codeforces_without_cache(
+    targets: ProblemsInContest,
+    cookies_path: &Path,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<Vec<Problem<String>>> {
+    let shell = RefCell::new(shell.borrow_mut());
 
-    Codeforces::exec(RetrieveTestCases {
-        targets,
-        credentials,
-        full: None,
-        cookie_storage,
-        timeout: crate::web::TIMEOUT,
-        shell: &shell,
+    let username_and_password = &mut credentials::username_and_password(
+        &shell,
+        profile,
+        PlatformKind::Codeforces,
+        "Username: ",
+        "Password: ",
+    );
+
+    http::with_retries(&shell, || {
+        http::with_env(|| {
+            Codeforces::exec(RetrieveTestCases {
+                targets: targets.clone(),
+                credentials: CodeforcesRetrieveSampleTestCasesCredentials {
+                    username_and_password,
+                },
+                full: None,
+                cookie_storage: CookieStorage::with_jsonl(cookies_path)?,
+                timeout: http::timeout(),
+                shell: &shell,
+            })
+        })
     })
     .map(|RetrieveTestCasesOutcome { problems, .. }| problems.into_iter().map(Into::into).collect())
 }
@@ -282,29 +487,101 @@ pub(crate) fn dl_from_codeforces(
 pub(crate) fn dl_from_yukicoder(
     targets: YukicoderRetrieveTestCasesTargets,
     full: bool,
+    cookies_path: &Path,
+    profile: Option<&str>,
+    cache: &ProblemCache,
     shell: &mut Shell,
 ) -> anyhow::Result<Vec<Problem<String>>> {
-    let full = if full {
-        Some(RetrieveFullTestCases {
-            credentials: YukicoderRetrieveFullTestCasesCredentials {
-                api_key: credentials::yukicoder_api_key(shell)?,
-            },
-        })
-    } else {
-        None
+    let key = match &targets {
+        YukicoderRetrieveTestCasesTargets::ProblemNos(nos) => {
+            format!("yukicoder problems {}", nos.iter().join(","))
+        }
+        YukicoderRetrieveTestCasesTargets::Contest(contest, None) => {
+            format!("yukicoder contest {contest}")
+        }
+        YukicoderRetrieveTestCasesTargets::Contest(contest, Some(problems)) => {
+            format!(
+                "yukicoder contest {contest} problems {}",
+                problems.iter().join(",")
+            )
+        }
+        YukicoderRetrieveTestCasesTargets::Urls(urls) => {
+            format!("yukicoder urls {}", urls.iter().join(" "))
+        }
     };
 
+    let retrieve = || dl_from_yukicoder_without_cache(targets, full, cookies_path, profile, shell);
+    if full {
+        // The system test cases are too large for `ProblemCache`.
+        cache.retrieve_uncached(&format!("{key} full"), retrieve)
+    } else {
+        cache.get_or_retrieve(&key, retrieve)
+    }
+}
+
+fn dl_from_yukicoder_without_cache(
+    targets: YukicoderRetrieveTestCasesTargets,
+    full: bool,
+    cookies_path: &Path,
+    profile: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<Vec<Problem<String>>> {
+    let api_key = full
+        .then(|| credentials::yukicoder_api_key(cookies_path, profile, shell))
+        .transpose()?;
+
     let shell = RefCell::new(shell.borrow_mut());
 
-    Yukicoder::exec(RetrieveTestCases {
-        targets,
-        credentials: (),
-        full,
-        cookie_storage: (),
-        timeout: crate::web::TIMEOUT,
-        shell: &shell,
+    return http::with_retries(&shell, || {
+        http::with_env(|| {
+            Yukicoder::exec(RetrieveTestCases {
+                targets: clone_targets(&targets),
+                credentials: (),
+                full: api_key.clone().map(|api_key| RetrieveFullTestCases {
+                    credentials: YukicoderRetrieveFullTestCasesCredentials { api_key },
+                }),
+                cookie_storage: (),
+                timeout: http::timeout(),
+                shell: &shell,
+            })
+        })
     })
-    .map(|RetrieveTestCasesOutcome { problems, .. }| problems.into_iter().map(Into::into).collect())
+    .map(|RetrieveTestCasesOutcome { problems, .. }| {
+        problems.into_iter().map(Into::into).collect()
+    });
+
+    fn clone_targets(
+        targets: &YukicoderRetrieveTestCasesTargets,
+    ) -> YukicoderRetrieveTestCasesTargets {
+        match targets {
+            YukicoderRetrieveTestCasesTargets::ProblemNos(nos) => {
+                YukicoderRetrieveTestCasesTargets::ProblemNos(nos.clone())
+            }
+            YukicoderRetrieveTestCasesTargets::Contest(contest, problems) => {
+                YukicoderRetrieveTestCasesTargets::Contest(contest.clone(), problems.clone())
+            }
+            YukicoderRetrieveTestCasesTargets::Urls(urls) => {
+                YukicoderRetrieveTestCasesTargets::Urls(urls.clone())
+            }
+        }
+    }
+}
+
+fn cache_key(platform: PlatformKind, targets: &ProblemsInContest) -> String {
+    match targets {
+        ProblemsInContest::Indexes {
+            contest,
+            problems: None,
+        } => format!("{platform} contest {contest}"),
+        ProblemsInContest::Indexes {
+            contest,
+            problems: Some(problems),
+        } => format!(
+            "{platform} contest {contest} problems {}",
+            problems.iter().join(",")
+        ),
+        ProblemsInContest::Urls { urls } => format!("{platform} urls {}", urls.iter().join(" ")),
+    }
 }
 
 pub(crate) fn system_test_cases_dir(problem_url: &Url) -> anyhow::Result<PathBuf> {
@@ -339,6 +616,7 @@ pub(crate) fn save_test_cases<I>(
         url,
         mut test_suite,
         text_files,
+        statement,
         ..
     } in problems
     {
@@ -450,19 +728,33 @@ pub(crate) fn save_test_cases<I>(
                     },
                 ),
             )?;
+
+            if let Some(statement) = &statement {
+                let dir = path.with_file_name(&bin_alias);
+                statement.save(&dir)?;
+                shell.status(
+                    "Saved",
+                    format!(
+                        "the statement to {}",
+                        dir.join(crate::web::statement::FILE_NAME)
+                    ),
+                )?;
+            }
         }
     }
     acc.sort();
     Ok(acc)
 }
 
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct Problem<I> {
     pub(crate) index: I,
     pub(crate) url: Url,
     pub(crate) test_suite: TestSuite,
     pub(crate) text_files: IndexMap<String, (String, Option<String>)>,
     pub(crate) contest_url: Option<Url>,
+    #[serde(default)]
+    pub(crate) statement: Option<Statement>,
 }
 
 impl Problem<Option<String>> {
@@ -525,25 +817,24 @@ impl Problem<Option<String>> {
             }),
             text_files,
             contest_url: problem.context.contest.as_ref().and_then(|c| c.url.clone()),
+            statement: None,
         }
     }
 }
 
 impl Problem<String> {
-    pub(crate) fn from_oj_api_with_alphabet(
-        problem: oj_api::Problem,
-        system: bool,
-    ) -> anyhow::Result<Self> {
+    /// For the `new` command, which names the `bin`s after the indexes.
+    pub(crate) fn with_index(problem: Problem<Option<String>>) -> anyhow::Result<Self> {
         let Problem {
             index,
             url,
             test_suite,
             text_files,
             contest_url,
-        } = Problem::<Option<String>>::from_oj_api(problem, system);
+            statement,
+        } = problem;
 
-        let index =
-            index.with_context(|| "`context.alphabet` is required for the `new` command")?;
+        let index = index.with_context(|| format!("the index of {url} is required"))?;
 
         Ok(Self {
             index,
@@ -551,6 +842,7 @@ impl Problem<String> {
             test_suite,
             text_files,
             contest_url,
+            statement,
         })
     }
 }
@@ -563,6 +855,7 @@ impl From<Problem<String>> for Problem<Option<String>> {
             test_suite: problem.test_suite,
             text_files: problem.text_files,
             contest_url: problem.contest_url,
+            statement: problem.statement,
         }
     }
 }
@@ -579,6 +872,7 @@ impl From<snowchains_core::web::RetrieveTestCasesOutcomeProblem> for Problem<Str
                 .map(|(k, v)| (k, (v.r#in, v.out)))
                 .collect(),
             contest_url: problem.contest.map(|c| c.url),
+            statement: None,
         }
     }
 }
@@ -0,0 +1,299 @@
+use crate::shell::Shell;
+use anyhow::{bail, Context as _};
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, NaiveTime, TimeZone as _, Utc};
+use indicatif::{ProgressBar, ProgressStyle};
+use regex::Regex;
+use reqwest::{blocking::Response, header::DATE, StatusCode};
+use serde::Deserialize;
+use snowchains_core::web::PlatformKind;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+use url::Url;
+
+/// Parses `--at`.
+///
+/// Accepts RFC 3339, `%Y-%m-%d %H:%M[:%S]` in the local time zone, or `%H:%M[:%S]` of today.
+pub(crate) fn parse_time(s: &str) -> anyhow::Result<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.with_timezone(&Utc));
+    }
+
+    let naive = if let Some(naive) = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+    {
+        naive
+    } else if let Some(time) = ["%H:%M:%S", "%H:%M"]
+        .iter()
+        .find_map(|fmt| NaiveTime::parse_from_str(s, fmt).ok())
+    {
+        Local::today().naive_local().and_time(time)
+    } else {
+        bail!(
+            "expected RFC 3339, `%Y-%m-%d %H:%M[:%S]`, or `%H:%M[:%S]`, got {:?}",
+            s,
+        );
+    };
+
+    Local
+        .from_local_datetime(&naive)
+        .single()
+        .map(|time| time.with_timezone(&Utc))
+        .with_context(|| format!("ambiguous local time: {s:?}"))
+}
+
+/// Returns the start time of the contest that the problem belongs to, and the offset of the
+/// server's clock.
+pub(crate) fn contest_start_time(
+    problem_url: &Url,
+) -> anyhow::Result<(DateTime<Utc>, chrono::Duration)> {
+    match PlatformKind::from_url(problem_url) {
+        Ok(PlatformKind::Atcoder) => {
+            let contest = super::url::atcoder_contest(problem_url)?;
+            let url = format!("https://atcoder.jp/contests/{contest}").parse()?;
+            let (res, offset) = get(&url)?;
+            let html = res.text()?;
+
+            let time = Regex::new(r#"<time class=['"]fixtime-full['"]>([^<]+)</time>"#)
+                .unwrap()
+                .captures(&html)
+                .with_context(|| format!("could not find the start time in {url}"))?[1]
+                .to_owned();
+            let time = DateTime::parse_from_str(&time, "%Y-%m-%d %H:%M:%S%z")
+                .with_context(|| format!("could not parse {time:?}"))?;
+            Ok((time.with_timezone(&Utc), offset))
+        }
+        Ok(PlatformKind::Codeforces) => {
+            let contest = super::url::codeforces_contest(problem_url)?
+                .parse::<u64>()
+                .with_context(|| format!("could not find the contest ID in {problem_url}"))?;
+            // The API only has `contest.list`, which lists all of the contests.
+            let url = format!("https://codeforces.com/contests/{contest}").parse()?;
+            let (res, offset) = get(&url)?;
+            let start = codeforces_start_time(&res.text()?)
+                .with_context(|| format!("could not find the start time in {url}"))?;
+            Ok((start, offset))
+        }
+        Ok(PlatformKind::Yukicoder) => {
+            let contest = super::url::yukicoder_contest(problem_url)
+                .ok()
+                .filter(|_| problem_url.path().starts_with("/contests/"))
+                .with_context(|| format!("{problem_url} is not a URL of a contest"))?;
+            let url = format!("https://yukicoder.me/api/v1/contest/id/{contest}").parse()?;
+            let (res, offset) = get(&url)?;
+            let YukicoderContest { date } = serde_json::from_str(&res.text()?)?;
+            let date = DateTime::parse_from_rfc3339(&date)
+                .with_context(|| format!("could not parse {date:?}"))?;
+            Ok((date.with_timezone(&Utc), offset))
+        }
+        Err(_) => bail!("`--at-contest-start` is not supported for {problem_url}"),
+    }
+}
+
+/// Returns how far the server's clock is ahead of the local one, using the `Date` header.
+pub(crate) fn server_clock_offset(url: &Url) -> anyhow::Result<chrono::Duration> {
+    get(url).map(|(_, offset)| offset)
+}
+
+/// Sleeps until `at` on the server's clock, showing a countdown.
+pub(crate) fn wait_until(
+    at: DateTime<Utc>,
+    offset: chrono::Duration,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    if !offset.is_zero() {
+        shell.status(
+            "Clock",
+            format!(
+                "the server's clock is {}s {} the local one",
+                offset.num_seconds().abs(),
+                if offset > chrono::Duration::zero() {
+                    "ahead of"
+                } else {
+                    "behind"
+                },
+            ),
+        )?;
+    }
+
+    shell.status(
+        "Waiting",
+        format!("until {}", at.with_timezone(&Local).to_rfc3339()),
+    )?;
+
+    let progress = ProgressBar::new_spinner();
+    progress.set_draw_target(shell.progress_draw_target());
+    progress.set_style(ProgressStyle::default_spinner().template("{spinner} {msg}"));
+
+    loop {
+        let remaining = at - (Utc::now() + offset);
+        if remaining <= chrono::Duration::zero() {
+            break;
+        }
+        let secs = remaining.num_seconds();
+        progress.set_message(&format!(
+            "{:02}:{:02}:{:02}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60,
+        ));
+        progress.tick();
+        thread::sleep(
+            remaining
+                .to_std()
+                .unwrap_or_default()
+                .min(Duration::from_millis(200)),
+        );
+    }
+
+    progress.finish_and_clear();
+    Ok(())
+}
+
+/// Sends GET requests to the page until it is accessible, since the problems may be published a
+/// few seconds after the start time.
+pub(crate) fn wait_until_accessible(url: &Url, shell: &mut Shell) -> anyhow::Result<()> {
+    poll(shell, |_| get(url).map(|_| Some(())))
+}
+
+/// Runs `f` until it returns `Some`, waiting 1s, 2s, 4s, ... up to 30s between the attempts.
+///
+/// Only the failures because the contest has not started yet (404 or 403) and the transient ones
+/// are retried. Gives up after 10 minutes.
+pub(crate) fn poll<T>(
+    shell: &mut Shell,
+    mut f: impl FnMut(&mut Shell) -> anyhow::Result<Option<T>>,
+) -> anyhow::Result<T> {
+    const MAX_INTERVAL: Duration = Duration::from_secs(30);
+    const TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+    let deadline = Instant::now() + TIMEOUT;
+    let mut interval = Duration::from_secs(1);
+
+    loop {
+        shell.take_last_status();
+        let result = f(shell);
+        let last_status = shell.take_last_status();
+
+        let reason = match result {
+            Ok(Some(output)) => return Ok(output),
+            Ok(None) => "not available yet".to_owned(),
+            Err(err) if is_not_started(&err, last_status) => format!("{err}"),
+            Err(err) if super::http::is_transient(&err, last_status) => format!("{err}"),
+            Err(err) => return Err(err),
+        };
+
+        if Instant::now() + interval >= deadline {
+            bail!("gave up waiting: {}", reason);
+        }
+        shell.warn(format!(
+            "{}. retrying in {}",
+            reason,
+            humantime::format_duration(interval),
+        ))?;
+        thread::sleep(interval);
+        interval = (interval * 2).min(MAX_INTERVAL);
+    }
+}
+
+/// Whether the platform is hiding the page until the contest starts.
+fn is_not_started(err: &anyhow::Error, last_status: Option<StatusCode>) -> bool {
+    let is_not_started = |status| matches!(status, StatusCode::NOT_FOUND | StatusCode::FORBIDDEN);
+
+    last_status.is_some_and(is_not_started)
+        || err.chain().any(|cause| {
+            cause
+                .downcast_ref::<reqwest::Error>()
+                .and_then(reqwest::Error::status)
+                .is_some_and(is_not_started)
+        })
+}
+
+fn get(url: &Url) -> anyhow::Result<(Response, chrono::Duration)> {
+    let client = super::http::reqwest_client()?;
+
+    let before = Utc::now();
+    let res = super::replay::send(&client, client.get(url.clone()))
+        .and_then(|res| res.error_for_status().map_err(Into::into))
+        .with_context(|| format!("could not get {url}"))?;
+    let local = before + (Utc::now() - before) / 2;
+
+    let offset = res
+        .headers()
+        .get(DATE)
+        .and_then(|date| date.to_str().ok())
+        .and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+        .map(|date| date.with_timezone(&Utc) - local)
+        .unwrap_or_else(chrono::Duration::zero);
+
+    // The `Date` header has a resolution of one second.
+    let offset = if offset.num_seconds() == 0 {
+        chrono::Duration::zero()
+    } else {
+        offset
+    };
+    Ok((res, offset))
+}
+
+/// Finds the start time in the link to timeanddate.com, which is in Moscow time (`p1=166`).
+fn codeforces_start_time(html: &str) -> Option<DateTime<Utc>> {
+    let caps = Regex::new(
+        r"fixedtime\.html\?day=([0-9]+)&(?:amp;)?month=([0-9]+)&(?:amp;)?year=([0-9]+)&(?:amp;)?hour=([0-9]+)&(?:amp;)?min=([0-9]+)&(?:amp;)?sec=([0-9]+)&(?:amp;)?p1=166\b",
+    )
+    .unwrap()
+    .captures(html)?;
+    let num = |i: usize| caps[i].parse::<u32>().ok();
+
+    FixedOffset::east(3 * 3600)
+        .ymd_opt(num(3)? as _, num(2)?, num(1)?)
+        .single()?
+        .and_hms_opt(num(4)?, num(5)?, num(6)?)
+        .map(|time| time.with_timezone(&Utc))
+}
+
+#[derive(Deserialize)]
+struct YukicoderContest {
+    #[serde(rename = "Date")]
+    date: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone as _, Utc};
+    use pretty_assertions::assert_eq;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn parse_time() -> anyhow::Result<()> {
+        assert_eq!(
+            Utc.ymd(2021, 3, 20).and_hms(12, 0, 0),
+            super::parse_time("2021-03-20T21:00:00+09:00")?,
+        );
+        assert!(super::parse_time("21:00").is_ok());
+        assert!(super::parse_time("2021-03-20 21:00").is_ok());
+        assert!(super::parse_time("tomorrow").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn is_not_started() {
+        let err = &anyhow::anyhow!("expected [200], got 404 Not Found");
+        assert!(super::is_not_started(err, Some(StatusCode::NOT_FOUND)));
+        assert!(super::is_not_started(err, Some(StatusCode::FORBIDDEN)));
+        assert!(!super::is_not_started(err, Some(StatusCode::BAD_REQUEST)));
+        assert!(!super::is_not_started(err, None));
+    }
+
+    #[test]
+    fn codeforces_start_time() {
+        assert_eq!(
+            Some(Utc.ymd(2021, 3, 20).and_hms(14, 35, 0)),
+            super::codeforces_start_time(
+                r#"<a href="https://www.timeanddate.com/worldclock/fixedtime.html?day=20&amp;month=3&amp;year=2021&amp;hour=17&amp;min=35&amp;sec=0&amp;p1=166" target="_blank">"#,
+            ),
+        );
+        assert_eq!(None, super::codeforces_start_time("<html></html>"));
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::{bail, Context as _};
+use chacha20poly1305::aead::{rand_core::RngCore as _, OsRng};
+use itertools::Itertools as _;
+use regex::Regex;
+use reqwest::{
+    header::{AUTHORIZATION, COOKIE},
+    StatusCode,
+};
+use serde::Deserialize;
+use sha2::{Digest as _, Sha512};
+use snowchains_core::web::{CookieStorage, PlatformKind};
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use url::Url;
+
+pub(crate) fn base_url(platform: PlatformKind) -> Url {
+    match platform {
+        PlatformKind::Atcoder => "https://atcoder.jp/",
+        PlatformKind::Codeforces => "https://codeforces.com/",
+        PlatformKind::Yukicoder => "https://yukicoder.me/",
+    }
+    .parse()
+    .unwrap()
+}
+
+/// Returns the name of the user logged in with the stored cookies.
+///
+/// yukicoder is not supported since it is accessed with an API key.
+pub(crate) fn username(
+    platform: PlatformKind,
+    cookies_path: &Path,
+) -> anyhow::Result<Option<String>> {
+    if platform == PlatformKind::Yukicoder {
+        return Ok(None);
+    }
+
+    let html = get_with_cookies(
+        &super::http::reqwest_client()?,
+        &base_url(platform),
+        &CookieStorage::with_jsonl(cookies_path)?,
+    )?
+    .text()?;
+
+    Ok(username_in_html(platform, &html))
+}
+
+/// Finds the name of the logged-in user in a page of the platform.
+pub(crate) fn username_in_html(platform: PlatformKind, html: &str) -> Option<String> {
+    let pattern = match platform {
+        PlatformKind::Atcoder => r#"userScreenName\s*=\s*"([^"]+)""#,
+        PlatformKind::Codeforces => {
+            r#"<a href="/profile/([^"]+)">[^<]*</a>\s*\|\s*<a href="/[0-9a-f]+/logout""#
+        }
+        PlatformKind::Yukicoder => return None,
+    };
+
+    Regex::new(pattern)
+        .unwrap()
+        .captures(html)
+        .map(|caps| caps[1].to_owned())
+}
+
+/// Sends a GET request with the stored cookies.
+pub(crate) fn get_with_cookies(
+    client: &reqwest::blocking::Client,
+    url: &Url,
+    cookie_storage: &CookieStorage,
+) -> anyhow::Result<reqwest::blocking::Response> {
+    let cookie = cookie_storage
+        .cookie_store
+        .get_request_cookies(url)
+        .map(|cookie| format!("{}={}", cookie.name(), cookie.value()))
+        .join("; ");
+
+    super::replay::send(client, client.get(url.clone()).header(COOKIE, cookie))
+        .and_then(|res| res.error_for_status().map_err(Into::into))
+        .with_context(|| format!("could not get {url}"))
+}
+
+/// Removes the cookies for the platform, returning the number of them.
+pub(crate) fn remove_cookies(platform: PlatformKind, cookies_path: &Path) -> anyhow::Result<usize> {
+    if !cookies_path.exists() {
+        return Ok(0);
+    }
+
+    let url = base_url(platform);
+    let mut cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
+
+    let keys = cookie_storage
+        .cookie_store
+        .iter_any()
+        .filter(|cookie| cookie.domain.matches(&url))
+        .map(|cookie| {
+            (
+                String::from(&cookie.domain),
+                String::from(&cookie.path),
+                cookie.name().to_owned(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for (domain, path, name) in &keys {
+        cookie_storage.cookie_store.remove(domain, path, name);
+    }
+    (cookie_storage.on_update)(&cookie_storage.cookie_store)?;
+    Ok(keys.len())
+}
+
+/// Returns the name of the owner of the yukicoder API key, or `None` if the key is rejected.
+pub(crate) fn yukicoder_username(api_key: &str) -> anyhow::Result<Option<String>> {
+    let url = "https://yukicoder.me/api/v1/user/me";
+    let client = &super::http::reqwest_client()?;
+
+    let res = super::replay::send(
+        client,
+        client
+            .get(url)
+            .header(AUTHORIZATION, format!("Bearer {api_key}")),
+    )
+    .with_context(|| format!("could not get {url}"))?;
+
+    if matches!(
+        res.status(),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+    ) {
+        return Ok(None);
+    }
+
+    let User { name } = res
+        .error_for_status()
+        .and_then(|res| res.json())
+        .with_context(|| format!("could not get {url}"))?;
+    return Ok(Some(name));
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct User {
+        name: String,
+    }
+}
+
+/// Whether the Codeforces API key and secret are accepted, by calling `user.friends` with them.
+pub(crate) fn codeforces_api_key_is_valid(api_key: &str, api_secret: &str) -> anyhow::Result<bool> {
+    let time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let rand = format!("{:06}", OsRng.next_u32() % 1_000_000);
+    let url = codeforces_signed_url("user.friends", &[], api_key, api_secret, time, &rand)?;
+    // Without the query, which contains the API key and the signature.
+    let endpoint = "https://codeforces.com/api/user.friends";
+
+    let client = &super::http::reqwest_client()?;
+    let res = super::replay::send_without_query(client, client.get(url))
+        .with_context(|| format!("could not get {endpoint}"))?;
+
+    // Rejected with 400 and `{"status":"FAILED","comment":"apiKey: Incorrect API key"}`.
+    if res.status().is_server_error() {
+        bail!("could not get {}: {}", endpoint, res.status());
+    }
+    let Response { status } = res
+        .json()
+        .with_context(|| format!("could not parse the JSON from {endpoint}"))?;
+    return Ok(status == "OK");
+
+    #[derive(Deserialize)]
+    struct Response {
+        status: String,
+    }
+}
+
+/// Signs an API call as <https://codeforces.com/apiHelp> describes.
+fn codeforces_signed_url(
+    method: &str,
+    params: &[(&str, &str)],
+    api_key: &str,
+    api_secret: &str,
+    time: u64,
+    rand: &str,
+) -> anyhow::Result<Url> {
+    let time = time.to_string();
+    let params = params
+        .iter()
+        .copied()
+        .chain([("apiKey", api_key), ("time", &*time)])
+        .sorted()
+        .collect::<Vec<_>>();
+
+    let mut url = format!("https://codeforces.com/api/{method}").parse::<Url>()?;
+    url.query_pairs_mut().extend_pairs(&params);
+
+    let sig = Sha512::digest(
+        format!("{rand}/{method}?{}#{api_secret}", url.query().unwrap_or("")).as_bytes(),
+    );
+    url.query_pairs_mut()
+        .append_pair("apiSig", &format!("{rand}{sig:x}"));
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn codeforces_signed_url() -> anyhow::Result<()> {
+        let url = super::codeforces_signed_url(
+            "contest.hacks",
+            &[("contestId", "566")],
+            "xxx",
+            "yyy",
+            1_234_567_890,
+            "123456",
+        )?;
+        let (query, sig) = url.query().unwrap().split_once("&apiSig=").unwrap();
+        assert_eq!("apiKey=xxx&contestId=566&time=1234567890", query);
+        assert_eq!(6 + 128, sig.len());
+        assert!(sig.starts_with("123456"));
+        Ok(())
+    }
+}
@@ -0,0 +1,691 @@
+//! Standings of contests.
+
+use super::{
+    atcoder_problems::{self, Submission, VirtualContest},
+    http, session,
+};
+use anyhow::{bail, Context as _};
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use snowchains_core::web::{CookieStorage, PlatformKind};
+use std::{collections::HashMap, path::Path, thread, time::Duration};
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Standings {
+    /// Labels of the problems, such as `A`.
+    pub(crate) problems: Vec<String>,
+    /// Number of the participants who solved each problem.
+    pub(crate) solved: Vec<usize>,
+    pub(crate) participants: usize,
+    /// Rows of the requested users, in the order of the rank.
+    pub(crate) rows: Vec<StandingsRow>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct StandingsRow {
+    pub(crate) rank: u64,
+    pub(crate) user: String,
+    pub(crate) score: f64,
+    /// `None` on yukicoder.
+    pub(crate) penalty: Option<u64>,
+    /// Time of the last accepted submission from the start. Only on AtCoder.
+    pub(crate) elapsed: Option<Duration>,
+    pub(crate) results: Vec<ProblemResult>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ProblemResult {
+    pub(crate) accepted: bool,
+    pub(crate) score: f64,
+    /// Number of the rejected submissions.
+    pub(crate) failures: u64,
+}
+
+/// Retrieves the whole standings of the contest, keeping the rows of `users` only.
+///
+/// The names are compared case-insensitively.
+pub(crate) fn retrieve(
+    platform: PlatformKind,
+    contest: &str,
+    users: &[String],
+    cookies_path: &Path,
+) -> anyhow::Result<Standings> {
+    let client = &http::reqwest_client()?;
+    let cookie_storage = &CookieStorage::with_jsonl(cookies_path)?;
+
+    let (url, parse): (_, fn(&str, &[String]) -> _) = match platform {
+        PlatformKind::Atcoder => (
+            format!("https://atcoder.jp/contests/{contest}/standings/json"),
+            parse_atcoder,
+        ),
+        PlatformKind::Codeforces => (
+            format!("https://codeforces.com/api/contest.standings?contestId={contest}"),
+            parse_codeforces,
+        ),
+        PlatformKind::Yukicoder => (
+            format!("https://yukicoder.me/contests/{contest}/table"),
+            parse_yukicoder,
+        ),
+    };
+
+    let text = session::get_with_cookies(client, &url.parse()?, cookie_storage)?.text()?;
+    parse(&text, users).with_context(|| format!("could not parse the standings at {url}"))
+}
+
+/// Computes the standings of a virtual contest of AtCoder Problems from the submissions of all of the
+/// participants.
+pub(crate) fn retrieve_virtual(contest: &VirtualContest) -> anyhow::Result<Standings> {
+    let client = &http::reqwest_client()?;
+
+    let mut submissions = vec![];
+    for (i, user) in contest.participants.iter().enumerate() {
+        if i > 0 {
+            // AtCoder Problems asks for an interval of at least 1 second between requests.
+            thread::sleep(Duration::from_secs(1));
+        }
+        let user_submissions =
+            atcoder_problems::user_submissions(client, user, contest.start_epoch_second)?;
+        submissions.push((&**user, user_submissions));
+    }
+    Ok(aggregate_virtual(contest, submissions))
+}
+
+/// Like AtCoder, the rejected submissions before the first accepted one count as the penalty, and
+/// the rank is decided by the score and then by the time of the last accepted submission plus the
+/// penalty.
+fn aggregate_virtual(
+    contest: &VirtualContest,
+    submissions: Vec<(&str, Vec<Submission>)>,
+) -> Standings {
+    static REJECTED: &[&str] = &["WA", "TLE", "MLE", "RE", "OLE", "IE", "QLE"];
+
+    let start = contest.start_epoch_second;
+    let end = start + contest.duration_second;
+
+    let mut solved = vec![0; contest.problems.len()];
+    let mut rows = vec![];
+
+    for (user, mut submissions) in submissions {
+        submissions.sort_by_key(|s| s.epoch_second);
+
+        let mut last_accepted = None;
+        let results = contest
+            .problems
+            .iter()
+            .map(|problem| {
+                let mut result = ProblemResult::default();
+                for submission in submissions.iter().filter(|s| {
+                    s.problem_id == problem.id && (start..end).contains(&s.epoch_second)
+                }) {
+                    if submission.result == "AC" {
+                        result.accepted = true;
+                        result.score = problem.point.unwrap_or(submission.point);
+                        last_accepted = last_accepted.max(Some(submission.epoch_second - start));
+                        break;
+                    }
+                    if REJECTED.contains(&&*submission.result) {
+                        result.failures += 1;
+                    }
+                }
+                result
+            })
+            .collect::<Vec<_>>();
+
+        count_solved(&mut solved, &results);
+
+        rows.push(StandingsRow {
+            rank: 0,
+            user: user.to_owned(),
+            score: results.iter().map(|r| r.score).sum(),
+            penalty: Some(
+                results
+                    .iter()
+                    .filter(|r| r.accepted)
+                    .map(|r| r.failures)
+                    .sum(),
+            ),
+            elapsed: last_accepted.map(Duration::from_secs),
+            results,
+        });
+    }
+
+    let key = |row: &StandingsRow| {
+        let time = row
+            .elapsed
+            .map(|e| e.as_secs() + contest.penalty_second * row.penalty.unwrap_or_default());
+        (-row.score, time.unwrap_or(u64::MAX))
+    };
+    let keys = rows.iter().map(key).collect::<Vec<_>>();
+    for (row, k) in rows.iter_mut().zip(&keys) {
+        row.rank = 1 + keys.iter().filter(|other| other < &k).count() as u64;
+    }
+    rows.sort_by_key(|row| row.rank);
+
+    Standings {
+        problems: contest.problems.iter().map(|p| p.index.clone()).collect(),
+        solved,
+        participants: rows.len(),
+        rows,
+    }
+}
+
+fn parse_atcoder(json: &str, users: &[String]) -> anyhow::Result<Standings> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct AtcoderStandings {
+        task_info: Vec<TaskInfo>,
+        standings_data: Vec<Data>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct TaskInfo {
+        assignment: String,
+        task_screen_name: String,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Data {
+        rank: u64,
+        user_screen_name: String,
+        task_results: HashMap<String, Result>,
+        total_result: Result,
+    }
+
+    /// Scores are multiplied by 100, and `Elapsed` is in nanoseconds.
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Result {
+        score: i64,
+        penalty: u64,
+        #[serde(default)]
+        elapsed: u64,
+    }
+
+    let AtcoderStandings {
+        task_info,
+        standings_data,
+    } = serde_json::from_str(json)?;
+
+    let mut solved = vec![0; task_info.len()];
+    let mut rows = vec![];
+
+    for data in &standings_data {
+        let results = task_info
+            .iter()
+            .map(|task| {
+                data.task_results
+                    .get(&task.task_screen_name)
+                    .map(|result| ProblemResult {
+                        accepted: result.score > 0,
+                        score: result.score as f64 / 100.0,
+                        failures: result.penalty,
+                    })
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        count_solved(&mut solved, &results);
+
+        if contains(users, &data.user_screen_name) {
+            rows.push(StandingsRow {
+                rank: data.rank,
+                user: data.user_screen_name.clone(),
+                score: data.total_result.score as f64 / 100.0,
+                penalty: Some(data.total_result.penalty),
+                elapsed: Some(Duration::from_nanos(data.total_result.elapsed))
+                    .filter(|_| data.total_result.score > 0),
+                results,
+            });
+        }
+    }
+
+    Ok(Standings {
+        problems: task_info.into_iter().map(|t| t.assignment).collect(),
+        solved,
+        participants: standings_data.len(),
+        rows,
+    })
+}
+
+fn parse_codeforces(json: &str, users: &[String]) -> anyhow::Result<Standings> {
+    #[derive(Deserialize)]
+    struct ContestStandings {
+        status: String,
+        #[serde(default)]
+        comment: Option<String>,
+        result: Option<ContestStandingsResult>,
+    }
+
+    #[derive(Deserialize)]
+    struct ContestStandingsResult {
+        problems: Vec<Problem>,
+        rows: Vec<Row>,
+    }
+
+    #[derive(Deserialize)]
+    struct Problem {
+        index: String,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Row {
+        party: Party,
+        rank: u64,
+        points: f64,
+        penalty: u64,
+        problem_results: Vec<Result>,
+    }
+
+    #[derive(Deserialize)]
+    struct Party {
+        members: Vec<Member>,
+    }
+
+    #[derive(Deserialize)]
+    struct Member {
+        handle: String,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Result {
+        points: f64,
+        rejected_attempt_count: u64,
+        best_submission_time_seconds: Option<u64>,
+    }
+
+    let ContestStandings {
+        status,
+        comment,
+        result,
+    } = serde_json::from_str(json)?;
+
+    let ContestStandingsResult { problems, rows } = match result {
+        Some(result) if status == "OK" => result,
+        _ => bail!(
+            "Codeforces API returned `{}`: {}",
+            status,
+            comment.unwrap_or_default(),
+        ),
+    };
+
+    let mut solved = vec![0; problems.len()];
+    let participants = rows.len();
+
+    let rows = rows
+        .into_iter()
+        .flat_map(|row| {
+            let results = row
+                .problem_results
+                .iter()
+                .map(|result| ProblemResult {
+                    accepted: result.best_submission_time_seconds.is_some(),
+                    score: result.points,
+                    failures: result.rejected_attempt_count,
+                })
+                .collect::<Vec<_>>();
+
+            count_solved(&mut solved, &results);
+
+            let handles = row.party.members.iter().map(|m| &*m.handle);
+            if !handles.clone().any(|h| contains(users, h)) {
+                return None;
+            }
+
+            Some(StandingsRow {
+                rank: row.rank,
+                user: handles.collect::<Vec<_>>().join(", "),
+                score: row.points,
+                penalty: Some(row.penalty),
+                elapsed: None,
+                results,
+            })
+        })
+        .collect();
+
+    Ok(Standings {
+        problems: problems.into_iter().map(|p| p.index).collect(),
+        solved,
+        participants,
+        rows,
+    })
+}
+
+/// Scrapes the ranking table.
+///
+/// The columns are found by the headers: the one with links to the problems for each problem, and
+/// `順位` and `得点`/`Score` for the rank and the score. A cell of a problem is regarded as accepted if
+/// it starts with a positive number.
+fn parse_yukicoder(html: &str, users: &[String]) -> anyhow::Result<Standings> {
+    let html = Html::parse_document(html);
+    let selector = |s: &str| Selector::parse(s).unwrap();
+    let text = |e: ElementRef<'_>| e.text().collect::<String>().trim().to_owned();
+    let number = |s: &str| {
+        s.split_whitespace()
+            .next()
+            .and_then(|s| s.parse::<f64>().ok())
+    };
+
+    let table = html
+        .select(&selector("table"))
+        .find(|t| {
+            t.select(&selector(r#"a[href*="/users/"]"#))
+                .next()
+                .is_some()
+        })
+        .with_context(|| "could not find the ranking table")?;
+
+    let headers = table.select(&selector("thead th")).collect::<Vec<_>>();
+
+    let find_header = |names: &[&str]| {
+        headers
+            .iter()
+            .position(|&th| names.iter().any(|name| text(th).contains(name)))
+    };
+    let rank_col = find_header(&["順位", "Rank"]).unwrap_or(0);
+    let score_col = find_header(&["得点", "点数", "Score"])
+        .with_context(|| "could not find the column of the scores")?;
+
+    let problem_cols = headers
+        .iter()
+        .enumerate()
+        .filter(|(_, th)| {
+            th.select(&selector(r#"a[href*="/problems/"]"#))
+                .next()
+                .is_some()
+        })
+        .map(|(i, &th)| (i, text(th)))
+        .collect::<Vec<_>>();
+
+    let mut solved = vec![0; problem_cols.len()];
+    let mut participants = 0;
+    let mut rows = vec![];
+
+    for tr in table.select(&selector("tbody tr")) {
+        let user = match tr.select(&selector(r#"a[href*="/users/"]"#)).next() {
+            Some(a) => text(a),
+            None => continue,
+        };
+        let cells = tr.select(&selector("td")).map(text).collect::<Vec<_>>();
+        let cell = |i: usize| cells.get(i).map(|s| &**s).unwrap_or_default();
+
+        let results = problem_cols
+            .iter()
+            .map(|&(i, _)| {
+                let score = number(cell(i)).unwrap_or_default();
+                ProblemResult {
+                    accepted: score > 0.0,
+                    score,
+                    failures: 0,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        participants += 1;
+        count_solved(&mut solved, &results);
+
+        if contains(users, &user) {
+            rows.push(StandingsRow {
+                rank: cell(rank_col)
+                    .trim_end_matches('位')
+                    .parse()
+                    .with_context(|| format!("could not parse the rank of {user}"))?,
+                user,
+                score: number(cell(score_col)).unwrap_or_default(),
+                penalty: None,
+                elapsed: None,
+                results,
+            });
+        }
+    }
+
+    Ok(Standings {
+        problems: problem_cols.into_iter().map(|(_, name)| name).collect(),
+        solved,
+        participants,
+        rows,
+    })
+}
+
+fn count_solved(solved: &mut [usize], results: &[ProblemResult]) {
+    for (solved, result) in solved.iter_mut().zip(results) {
+        if result.accepted {
+            *solved += 1;
+        }
+    }
+}
+
+fn contains(users: &[String], user: &str) -> bool {
+    users.iter().any(|u| u.eq_ignore_ascii_case(user))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProblemResult, Standings, StandingsRow};
+    use crate::web::atcoder_problems::{Submission, VirtualContest, VirtualContestProblem};
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    #[test]
+    fn parse_atcoder() -> anyhow::Result<()> {
+        let standings = super::parse_atcoder(
+            r#"{"Fixed":false,"AdditionalColumns":null,"TaskInfo":[{"Assignment":"A","TaskName":"Difference Max","TaskScreenName":"abc196_a"},{"Assignment":"B","TaskName":"Round Down","TaskScreenName":"abc196_b"}],"StandingsData":[{"Rank":1,"UserScreenName":"tourist","TaskResults":{"abc196_a":{"Count":1,"Failure":0,"Penalty":0,"Score":10000,"Elapsed":60000000000,"Status":1,"Pending":false},"abc196_b":{"Count":1,"Failure":0,"Penalty":0,"Score":20000,"Elapsed":120000000000,"Status":1,"Pending":false}},"TotalResult":{"Count":2,"Accepted":2,"Penalty":0,"Score":30000,"Elapsed":120000000000}},{"Rank":2,"UserScreenName":"qryxip","TaskResults":{"abc196_a":{"Count":2,"Failure":1,"Penalty":1,"Score":10000,"Elapsed":180000000000,"Status":1,"Pending":false},"abc196_b":{"Count":2,"Failure":2,"Penalty":2,"Score":0,"Elapsed":0,"Status":6,"Pending":false}},"TotalResult":{"Count":4,"Accepted":1,"Penalty":1,"Score":10000,"Elapsed":480000000000}}]}"#,
+            &["QRYXIP".to_owned()],
+        )?;
+
+        assert_eq!(
+            Standings {
+                problems: vec!["A".to_owned(), "B".to_owned()],
+                solved: vec![2, 1],
+                participants: 2,
+                rows: vec![StandingsRow {
+                    rank: 2,
+                    user: "qryxip".to_owned(),
+                    score: 100.0,
+                    penalty: Some(1),
+                    elapsed: Some(Duration::from_secs(480)),
+                    results: vec![
+                        ProblemResult {
+                            accepted: true,
+                            score: 100.0,
+                            failures: 1,
+                        },
+                        ProblemResult {
+                            accepted: false,
+                            score: 0.0,
+                            failures: 2,
+                        },
+                    ],
+                }],
+            },
+            standings,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_codeforces() -> anyhow::Result<()> {
+        let standings = super::parse_codeforces(
+            r#"{"status":"OK","result":{"contest":{"id":1500,"name":"Codeforces Round #707 (Div. 1)","type":"CF","phase":"FINISHED"},"problems":[{"contestId":1500,"index":"A","name":"Going Home","type":"PROGRAMMING","points":500.0},{"contestId":1500,"index":"B","name":"Two chandeliers","type":"PROGRAMMING","points":1000.0}],"rows":[{"party":{"contestId":1500,"members":[{"handle":"tourist"}],"participantType":"CONTESTANT"},"rank":1,"points":1400.0,"penalty":0,"successfulHackCount":0,"unsuccessfulHackCount":0,"problemResults":[{"points":480.0,"rejectedAttemptCount":0,"type":"FINAL","bestSubmissionTimeSeconds":300},{"points":920.0,"rejectedAttemptCount":0,"type":"FINAL","bestSubmissionTimeSeconds":900}]},{"party":{"contestId":1500,"members":[{"handle":"qryxip"}],"participantType":"CONTESTANT"},"rank":2,"points":0.0,"penalty":0,"successfulHackCount":0,"unsuccessfulHackCount":0,"problemResults":[{"points":0.0,"rejectedAttemptCount":3,"type":"FINAL"},{"points":0.0,"rejectedAttemptCount":0,"type":"FINAL"}]}]}}"#,
+            &["qryxip".to_owned(), "someone".to_owned()],
+        )?;
+
+        assert_eq!(
+            Standings {
+                problems: vec!["A".to_owned(), "B".to_owned()],
+                solved: vec![1, 1],
+                participants: 2,
+                rows: vec![StandingsRow {
+                    rank: 2,
+                    user: "qryxip".to_owned(),
+                    score: 0.0,
+                    penalty: Some(0),
+                    elapsed: None,
+                    results: vec![
+                        ProblemResult {
+                            accepted: false,
+                            score: 0.0,
+                            failures: 3,
+                        },
+                        ProblemResult::default(),
+                    ],
+                }],
+            },
+            standings,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_yukicoder() -> anyhow::Result<()> {
+        let standings = super::parse_yukicoder(
+            r#"<!DOCTYPE html>
+<html>
+<body>
+<table class="table">
+<thead>
+<tr><th>順位</th><th>ユーザー</th><th><a href="/problems/no/1435">A</a></th><th><a href="/problems/no/1436">B</a></th><th>得点</th></tr>
+</thead>
+<tbody>
+<tr><td>1</td><td><a href="/users/1">tourist</a></td><td>100 (00:03)</td><td>200 (00:10)</td><td>300</td></tr>
+<tr><td>2</td><td><a href="/users/2">qryxip</a></td><td>100 (00:05)</td><td></td><td>100</td></tr>
+</tbody>
+</table>
+</body>
+</html>
+"#,
+            &["qryxip".to_owned()],
+        )?;
+
+        assert_eq!(
+            Standings {
+                problems: vec!["A".to_owned(), "B".to_owned()],
+                solved: vec![2, 1],
+                participants: 2,
+                rows: vec![StandingsRow {
+                    rank: 2,
+                    user: "qryxip".to_owned(),
+                    score: 100.0,
+                    penalty: None,
+                    elapsed: None,
+                    results: vec![
+                        ProblemResult {
+                            accepted: true,
+                            score: 100.0,
+                            failures: 0,
+                        },
+                        ProblemResult::default(),
+                    ],
+                }],
+            },
+            standings,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_virtual() {
+        let problem = |index: &str, id: &str, point| VirtualContestProblem {
+            index: index.to_owned(),
+            id: id.to_owned(),
+            url: format!("https://atcoder.jp/contests/abc196/tasks/{id}")
+                .parse()
+                .unwrap(),
+            point,
+        };
+        let submission = |elapsed: u64, problem_id: &str, point: f64, result: &str| Submission {
+            epoch_second: 1_600_000_000 + elapsed,
+            problem_id: problem_id.to_owned(),
+            point,
+            result: result.to_owned(),
+        };
+
+        let contest = VirtualContest {
+            id: "0b1bbcaf-bd6c-4e2a-9c5f-3a6e1e1b0f0e".to_owned(),
+            title: "ABC196".to_owned(),
+            start_epoch_second: 1_600_000_000,
+            duration_second: 3600,
+            penalty_second: 300,
+            problems: vec![
+                problem("A", "abc196_a", None),
+                problem("B", "abc196_b", Some(500.0)),
+            ],
+            participants: vec!["qryxip".to_owned(), "tourist".to_owned()],
+        };
+
+        let standings = super::aggregate_virtual(
+            &contest,
+            vec![
+                (
+                    "qryxip",
+                    vec![
+                        submission(180, "abc196_a", 100.0, "AC"),
+                        submission(60, "abc196_a", 0.0, "WA"),
+                        submission(120, "abc196_a", 0.0, "CE"),
+                        submission(600, "abc196_b", 0.0, "TLE"),
+                        submission(4000, "abc196_b", 200.0, "AC"),
+                    ],
+                ),
+                (
+                    "tourist",
+                    vec![
+                        submission(60, "abc196_a", 100.0, "AC"),
+                        submission(120, "abc196_b", 200.0, "AC"),
+                    ],
+                ),
+            ],
+        );
+
+        assert_eq!(
+            Standings {
+                problems: vec!["A".to_owned(), "B".to_owned()],
+                solved: vec![2, 1],
+                participants: 2,
+                rows: vec![
+                    StandingsRow {
+                        rank: 1,
+                        user: "tourist".to_owned(),
+                        score: 600.0,
+                        penalty: Some(0),
+                        elapsed: Some(Duration::from_secs(120)),
+                        results: vec![
+                            ProblemResult {
+                                accepted: true,
+                                score: 100.0,
+                                failures: 0,
+                            },
+                            ProblemResult {
+                                accepted: true,
+                                score: 500.0,
+                                failures: 0,
+                            },
+                        ],
+                    },
+                    StandingsRow {
+                        rank: 2,
+                        user: "qryxip".to_owned(),
+                        score: 100.0,
+                        penalty: Some(1),
+                        elapsed: Some(Duration::from_secs(180)),
+                        results: vec![
+                            ProblemResult {
+                                accepted: true,
+                                score: 100.0,
+                                failures: 1,
+                            },
+                            ProblemResult {
+                                accepted: false,
+                                score: 0.0,
+                                failures: 1,
+                            },
+                        ],
+                    },
+                ],
+            },
+            standings,
+        );
+    }
+}
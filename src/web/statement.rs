@@ -0,0 +1,606 @@
+//! Problem statements converted to Markdown.
+//!
+//! Math is kept as TeX (`$..$`) and images are saved next to `statement.md`.
+
+use crate::{
+    config::{CargoCompeteConfigStatement, CargoCompeteConfigStatementLang},
+    shell::Shell,
+    web::{cache::ProblemCache, http, retrieve_testcases::Problem},
+};
+use anyhow::Context as _;
+use camino::Utf8Path;
+use ego_tree::NodeRef;
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::{Deserialize, Serialize};
+use snowchains_core::web::{CookieStorage, PlatformKind};
+use std::{cell::RefCell, mem, path::Path};
+use url::Url;
+
+pub(crate) static FILE_NAME: &str = "statement.md";
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Statement {
+    pub(crate) markdown: String,
+    /// File names to Base64-encoded contents.
+    pub(crate) images: IndexMap<String, String>,
+}
+
+impl Statement {
+    /// Writes `statement.md` and `images/*` to `dir`.
+    pub(crate) fn save(&self, dir: &Utf8Path) -> anyhow::Result<()> {
+        crate::fs::create_dir_all(dir)?;
+        crate::fs::write(dir.join(FILE_NAME), &self.markdown)?;
+
+        if !self.images.is_empty() {
+            crate::fs::create_dir_all(dir.join("images"))?;
+        }
+        for (name, content) in &self.images {
+            let content = base64::decode(content)
+                .with_context(|| format!("could not decode the cached image `{name}`"))?;
+            crate::fs::write(dir.join("images").join(name), content)?;
+        }
+        Ok(())
+    }
+}
+
+/// Retrieves the statements if `statement.save` is enabled. The problems which already have them
+/// are skipped.
+///
+/// A failure does not stop the command since the test cases have already been retrieved.
+pub(crate) fn retrieve_all<I>(
+    problems: &mut [Problem<I>],
+    config: &CargoCompeteConfigStatement,
+    cookies_path: &Path,
+    cache: &ProblemCache,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    if !config.save {
+        return Ok(());
+    }
+
+    for problem in problems {
+        if problem.statement.is_some() {
+            continue;
+        }
+
+        let url = &problem.url;
+        let key = format!("statement {} {}", config.lang, url);
+
+        shell.status("Retrieving", format!("the statement of {url}"))?;
+
+        let statement = {
+            let shell = RefCell::new(&mut *shell);
+            cache.get_or_retrieve(&key, || {
+                http::with_retries(&shell, || retrieve(url, config.lang, cookies_path))
+            })
+        };
+
+        match statement {
+            Ok(statement) => problem.statement = Some(statement),
+            Err(err) => shell.warn(format!(
+                "could not retrieve the statement of {url}: {err:#}"
+            ))?,
+        }
+    }
+    Ok(())
+}
+
+fn retrieve(
+    url: &Url,
+    lang: CargoCompeteConfigStatementLang,
+    cookies_path: &Path,
+) -> anyhow::Result<Statement> {
+    let client = http::reqwest_client()?;
+    let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
+
+    let get = |url: &Url| super::session::get_with_cookies(&client, url, &cookie_storage);
+
+    let html = get(url)?.text()?;
+    let (markdown, images) = to_markdown(&Html::parse_document(&html), url, lang)?;
+
+    let images = images
+        .into_iter()
+        .map(|(name, url)| Ok((name, base64::encode(get(&url)?.bytes()?))))
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(Statement { markdown, images })
+}
+
+fn to_markdown(
+    html: &Html,
+    url: &Url,
+    lang: CargoCompeteConfigStatementLang,
+) -> anyhow::Result<(String, IndexMap<String, Url>)> {
+    let select = |selector: &str| html.select(&Selector::parse(selector).unwrap()).next();
+    let not_found = || format!("could not find the statement in {url}");
+
+    let mut markdown = Markdown::new(url);
+
+    match PlatformKind::from_url(url) {
+        Ok(PlatformKind::Atcoder) => {
+            if let Some(title) = select("span.h2") {
+                markdown.heading(1, title);
+            }
+            if let Some(limits) = html
+                .select(&Selector::parse("#main-container p").unwrap())
+                .find(|p| p.text().any(|s| s.contains("Time Limit")))
+            {
+                markdown.element(limits);
+            }
+            let statement = select(&format!("#task-statement span.lang-{lang}"))
+                .or_else(|| select("#task-statement"))
+                .with_context(not_found)?;
+            markdown.element(statement);
+        }
+        Ok(PlatformKind::Codeforces) => {
+            markdown.element(select("div.problem-statement").with_context(not_found)?);
+        }
+        Ok(PlatformKind::Yukicoder) => {
+            markdown.element(select("#content").with_context(not_found)?);
+        }
+        Err(_) => markdown.element(select("body").unwrap_or_else(|| html.root_element())),
+    }
+
+    Ok(markdown.finish())
+}
+
+struct Markdown<'a> {
+    base: &'a Url,
+    out: String,
+    images: IndexMap<String, Url>,
+}
+
+impl<'a> Markdown<'a> {
+    fn new(base: &'a Url) -> Self {
+        Self {
+            base,
+            out: String::new(),
+            images: IndexMap::new(),
+        }
+    }
+
+    /// Removes the trailing spaces and the consecutive blank lines, except in the code blocks.
+    fn finish(self) -> (String, IndexMap<String, Url>) {
+        let codeforces = PlatformKind::from_url(self.base).ok() == Some(PlatformKind::Codeforces);
+
+        let mut text = String::new();
+        let mut fence = None;
+
+        for line in self.out.lines() {
+            if let Some(open) = fence {
+                if line == open {
+                    fence = None;
+                }
+                text += line;
+                text.push('\n');
+                continue;
+            }
+
+            let line = line.trim_end();
+            if line.starts_with("```") {
+                fence = Some(line);
+            }
+            if !(line.is_empty() && (text.is_empty() || text.ends_with("\n\n"))) {
+                if codeforces {
+                    // Codeforces writes math as `$$$..$$$` and `$$$$$$..$$$$$$`.
+                    text += &line.replace("$$$$$$", "$$").replace("$$$", "$");
+                } else {
+                    text += line;
+                }
+                text.push('\n');
+            }
+        }
+
+        let len = text.trim_end_matches('\n').len();
+        text.truncate(len);
+        text.push('\n');
+        (text, self.images)
+    }
+
+    fn node(&mut self, node: NodeRef<'_, Node>) {
+        match node.value() {
+            Node::Text(text) => self.text(text),
+            Node::Element(_) => self.element(ElementRef::wrap(node).unwrap()),
+            _ => {}
+        }
+    }
+
+    fn children(&mut self, element: ElementRef<'_>) {
+        for child in element.children() {
+            self.node(child);
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        static WHITESPACE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+").unwrap());
+
+        let text = WHITESPACE.replace_all(text, " ");
+        if self.out.is_empty() || self.out.ends_with(['\n', ' ']) {
+            self.out += text.trim_start();
+        } else {
+            self.out += &text;
+        }
+    }
+
+    /// Renders the children into a separate buffer.
+    fn inline(&mut self, element: ElementRef<'_>) -> String {
+        let outer = mem::take(&mut self.out);
+        self.children(element);
+        let inner = mem::replace(&mut self.out, outer);
+        inner.trim().to_owned()
+    }
+
+    fn break_block(&mut self) {
+        let len = self.out.trim_end_matches(' ').len();
+        self.out.truncate(len);
+        if !(self.out.is_empty() || self.out.ends_with("\n\n")) {
+            self.out += if self.out.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            };
+        }
+    }
+
+    fn block(&mut self, f: impl FnOnce(&mut Self)) {
+        self.break_block();
+        f(self);
+        self.break_block();
+    }
+
+    fn heading(&mut self, level: usize, element: ElementRef<'_>) {
+        let text = self.inline(element).replace('\n', " ");
+        if !text.is_empty() {
+            self.block(|this| this.out += &format!("{} {}", "#".repeat(level), text));
+        }
+    }
+
+    fn element(&mut self, element: ElementRef<'_>) {
+        let value = element.value();
+        let has_class = |class| value.classes().any(|c| c == class);
+        let parent_has_class = |class| {
+            element
+                .parent()
+                .and_then(ElementRef::wrap)
+                .is_some_and(|p| p.value().classes().any(|c| c == class))
+        };
+
+        match value.name() {
+            "script" | "style" | "form" | "button" | "nav" | "input" | "select" => {}
+            _ if has_class("btn") => {}
+            name @ ("h1" | "h2" | "h3" | "h4" | "h5" | "h6") => {
+                self.heading(usize::from(name.as_bytes()[1] - b'0'), element);
+            }
+            // Codeforces
+            _ if has_class("title") => {
+                self.heading(if parent_has_class("header") { 1 } else { 4 }, element);
+            }
+            _ if has_class("section-title") => self.heading(3, element),
+            _ if has_class("property-title") => {
+                let text = self.inline(element);
+                self.out += &format!("**{text}**: ");
+            }
+            "pre" => {
+                let mut text = String::new();
+                pre_text(element, &mut text);
+                let text = text.strip_suffix('\n').unwrap_or(&text);
+                // Longer than any run of backticks in the text.
+                let fence = "`".repeat(3.max(longest_backtick_run(text) + 1));
+                self.block(|this| this.out += &format!("{fence}\n{text}\n{fence}"));
+            }
+            "var" => {
+                let text = element.text().collect::<String>();
+                self.out += &format!("${}$", text.trim());
+            }
+            "code" | "kbd" | "tt" | "samp" => {
+                let text = element.text().collect::<String>();
+                self.out += &format!("`{}`", text.trim());
+            }
+            "strong" | "b" => self.wrap("**", element),
+            "em" | "i" => self.wrap("*", element),
+            "br" => self.out.push('\n'),
+            "hr" => self.block(|this| this.out += "---"),
+            name @ ("ul" | "ol") => {
+                let ordered = name == "ol";
+                self.block(|this| {
+                    for (i, item) in element
+                        .children()
+                        .filter_map(ElementRef::wrap)
+                        .filter(|e| e.value().name() == "li")
+                        .enumerate()
+                    {
+                        let marker = if ordered {
+                            format!("{}. ", i + 1)
+                        } else {
+                            "- ".to_owned()
+                        };
+                        let indent = format!("\n{}", " ".repeat(marker.len()));
+                        let text = this.inline(item).replace('\n', &indent);
+                        this.out += &format!("{marker}{text}\n");
+                    }
+                });
+            }
+            "table" => {
+                let rows = element
+                    .select(&Selector::parse("tr").unwrap())
+                    .map(|row| {
+                        row.children()
+                            .filter_map(ElementRef::wrap)
+                            .filter(|c| matches!(c.value().name(), "td" | "th"))
+                            .map(|c| self.inline(c).replace('\n', " ").replace('|', "\\|"))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                self.block(|this| {
+                    for (i, cells) in rows.iter().enumerate() {
+                        this.out += &format!("| {} |\n", cells.join(" | "));
+                        if i == 0 {
+                            this.out += &format!("|{}\n", "---|".repeat(cells.len()));
+                        }
+                    }
+                });
+            }
+            "img" => {
+                if let Some(url) = value
+                    .attr("src")
+                    .and_then(|src| self.base.join(src).ok())
+                    .filter(|url| matches!(url.scheme(), "http" | "https"))
+                {
+                    let alt = value.attr("alt").unwrap_or_default();
+                    let name = self.image_name(url);
+                    self.out += &format!("![{alt}](images/{name})");
+                }
+            }
+            "a" => {
+                let text = self.inline(element);
+                match value
+                    .attr("href")
+                    .filter(|href| !(href.starts_with('#') || href.starts_with("javascript:")))
+                    .and_then(|href| self.base.join(href).ok())
+                {
+                    Some(href) if !text.is_empty() => self.out += &format!("[{text}]({href})"),
+                    _ => self.out += &text,
+                }
+            }
+            "p" | "div" | "section" | "article" | "blockquote" | "center" | "dl" | "dt" | "dd"
+            | "figure" => self.block(|this| this.children(element)),
+            _ => self.children(element),
+        }
+    }
+
+    fn wrap(&mut self, delimiter: &str, element: ElementRef<'_>) {
+        let text = self.inline(element);
+        if !text.is_empty() {
+            self.out += &format!("{delimiter}{text}{delimiter}");
+        }
+    }
+
+    fn image_name(&mut self, url: Url) -> String {
+        static UNSAFE: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^A-Za-z0-9._-]").unwrap());
+
+        let name = url
+            .path_segments()
+            .and_then(|mut s| s.next_back())
+            .map(|s| UNSAFE.replace_all(s, "_").into_owned())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "image".to_owned());
+
+        let name = match self.images.get(&name) {
+            Some(existing) if *existing == url => return name,
+            Some(_) => format!("{}-{}", self.images.len(), name),
+            None => name,
+        };
+        self.images.insert(name.clone(), url);
+        name
+    }
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+/// Text in `<pre>`, where each `<div>` is a line as in samples of Codeforces.
+fn pre_text(element: ElementRef<'_>, acc: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => *acc += text,
+            Node::Element(e) if e.name() == "br" => acc.push('\n'),
+            Node::Element(e) => {
+                pre_text(ElementRef::wrap(child).unwrap(), acc);
+                if e.name() == "div" && !acc.ends_with('\n') {
+                    acc.push('\n');
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::CargoCompeteConfigStatementLang;
+    use indexmap::indexmap;
+    use pretty_assertions::assert_eq;
+    use scraper::Html;
+    use url::Url;
+
+    #[test]
+    fn atcoder() -> anyhow::Result<()> {
+        let html = Html::parse_document(
+            r#"<!DOCTYPE html>
+<html>
+<body>
+<div id="main-container">
+<span class="h2">
+    A - Welcome to AtCoder
+    <a class="btn btn-default btn-sm" href="/contests/practice/tasks/practice_1/editorial">Editorial</a>
+</span>
+<p>
+    Time Limit: 2 sec / Memory Limit: 256 MB
+</p>
+<div id="task-statement">
+<span class="lang">
+<span class="lang-ja"><p>日本語</p></span>
+<span class="lang-en">
+<div class="part">
+<section>
+<h3>Problem Statement</h3>
+<p>Given integers <var>a</var>, <var>b</var>, <var>c</var> and a string <var>s</var>, print <strong>the sum</strong>.</p>
+<img src="/img/figure.png" alt="figure">
+</section>
+</div>
+<div class="part">
+<section>
+<h3>Constraints</h3>
+<ul>
+<li><var>1 \leq a, b, c \leq 1000</var></li>
+<li><var>1 \leq |s| \leq 100</var></li>
+</ul>
+</section>
+</div>
+<div class="part">
+<section>
+<h3>Sample Input 1 <span class="btn btn-default btn-sm btn-copy">Copy</span></h3>
+<pre>1
+2 3
+test
+</pre>
+</section>
+</div>
+</span>
+</span>
+</div>
+</div>
+</body>
+</html>
+"#,
+        );
+
+        let (markdown, images) = super::to_markdown(
+            &html,
+            &"https://atcoder.jp/contests/practice/tasks/practice_1".parse()?,
+            CargoCompeteConfigStatementLang::En,
+        )?;
+
+        assert_eq!(
+            r#"# A - Welcome to AtCoder
+
+Time Limit: 2 sec / Memory Limit: 256 MB
+
+### Problem Statement
+
+Given integers $a$, $b$, $c$ and a string $s$, print **the sum**.
+
+![figure](images/figure.png)
+
+### Constraints
+
+- $1 \leq a, b, c \leq 1000$
+- $1 \leq |s| \leq 100$
+
+### Sample Input 1
+
+```
+1
+2 3
+test
+```
+"#,
+            markdown,
+        );
+        assert_eq!(
+            indexmap!("figure.png".to_owned() => "https://atcoder.jp/img/figure.png".parse::<Url>()?),
+            images,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn codeforces() -> anyhow::Result<()> {
+        let html = Html::parse_document(
+            r#"<!DOCTYPE html>
+<html>
+<body>
+<div class="problem-statement">
+<div class="header">
+<div class="title">A. Watermelon</div>
+<div class="time-limit"><div class="property-title">time limit per test</div>1 second</div>
+</div>
+<div><p>Find whether $$$w$$$ can be divided.</p></div>
+<div class="input-specification"><div class="section-title">Input</div><p>The first line contains $$$w$$$ ($$$1 \le w \le 100$$$).</p></div>
+<div class="sample-tests">
+<div class="sample-test">
+<div class="input"><div class="title">Input</div><pre><div class="test-example-line">8</div></pre></div>
+</div>
+</div>
+</div>
+</body>
+</html>
+"#,
+        );
+
+        let (markdown, images) = super::to_markdown(
+            &html,
+            &"https://codeforces.com/contest/4/problem/A".parse()?,
+            CargoCompeteConfigStatementLang::En,
+        )?;
+
+        assert_eq!(
+            r#"# A. Watermelon
+
+**time limit per test**: 1 second
+
+Find whether $w$ can be divided.
+
+### Input
+
+The first line contains $w$ ($1 \le w \le 100$).
+
+#### Input
+
+```
+8
+```
+"#,
+            markdown,
+        );
+        assert!(images.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn code_blocks_are_kept_as_they_are() -> anyhow::Result<()> {
+        let html = Html::parse_document(
+            "<!DOCTYPE html>
+<html>
+<body>
+<div class=\"problem-statement\">
+<div class=\"header\"><div class=\"title\">A. Spaces</div></div>
+<div><p>Print $$$s$$$.</p></div>
+<div class=\"sample-tests\">
+<div class=\"sample-test\">
+<div class=\"output\"><div class=\"title\">Output</div><pre>a  \n\n\n$$$ ```\n</pre></div>
+</div>
+</div>
+</div>
+</body>
+</html>
+",
+        );
+
+        let (markdown, _) = super::to_markdown(
+            &html,
+            &"https://codeforces.com/contest/1/problem/A".parse()?,
+            CargoCompeteConfigStatementLang::En,
+        )?;
+
+        assert_eq!(
+            "# A. Spaces\n\nPrint $s$.\n\n#### Output\n\n````\na  \n\n\n$$$ ```\n````\n",
+            markdown,
+        );
+        Ok(())
+    }
+}
@@ -1,29 +1,49 @@
 use anyhow::{anyhow, Context as _};
+use snowchains_core::web::PlatformKind;
 use std::borrow::Cow;
 use url::Url;
 
+/// Contest ID of a problem URL, if any.
+pub(crate) fn contest(problem_url: &Url) -> Option<String> {
+    match PlatformKind::from_url(problem_url).ok()? {
+        PlatformKind::Atcoder => atcoder_contest(problem_url).ok(),
+        PlatformKind::Codeforces => codeforces_contest(problem_url).ok(),
+        PlatformKind::Yukicoder => problem_url
+            .path()
+            .starts_with("/contests/")
+            .then(|| yukicoder_contest(problem_url).ok())
+            .flatten(),
+    }
+}
+
 pub(crate) fn atcoder_contest(url: &Url) -> anyhow::Result<String> {
-    second_path_segment(url)
+    path_segment(url, 1)
 }
 
+/// Also for `/problemset/problem/{contest}/{index}`.
 pub(crate) fn codeforces_contest(url: &Url) -> anyhow::Result<String> {
-    second_path_segment(url)
+    if url.path().starts_with("/problemset/problem/") {
+        path_segment(url, 2)
+    } else {
+        path_segment(url, 1)
+    }
 }
 
 pub(crate) fn yukicoder_contest(url: &Url) -> anyhow::Result<String> {
-    second_path_segment(url)
+    path_segment(url, 1)
 }
 
-fn second_path_segment(url: &Url) -> anyhow::Result<String> {
+fn path_segment(url: &Url, index: usize) -> anyhow::Result<String> {
     let segments = url
         .path_segments()
         .map(|ss| ss.collect::<Vec<_>>())
         .unwrap_or_default();
 
-    let segment = segments.get(1).with_context(|| {
+    let segment = segments.get(index).with_context(|| {
         format!(
-            "the number of path segments is {} but the index is 1: {}",
+            "the number of path segments is {} but the index is {}: {}",
             segments.len(),
+            index,
             url,
         )
     })?;
@@ -52,4 +72,28 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn contest() {
+        for (expected, url) in &[
+            (
+                Some("1500"),
+                "https://codeforces.com/contest/1500/problem/A",
+            ),
+            (
+                Some("1500"),
+                "https://codeforces.com/problemset/problem/1500/A",
+            ),
+            (
+                Some("100000"),
+                "https://codeforces.com/gym/100000/problem/A",
+            ),
+            (None, "https://yukicoder.me/problems/no/1000"),
+        ] {
+            assert_eq!(
+                expected.map(ToOwned::to_owned),
+                super::contest(&url.parse().unwrap()),
+            );
+        }
+    }
 }
@@ -0,0 +1,60 @@
+pub mod common;
+
+use ignore::overrides::Override;
+use insta::{assert_json_snapshot, assert_snapshot};
+use std::path::Path;
+
+#[test]
+fn atcoder_practice_offline_url() -> anyhow::Result<()> {
+    let (output, tree) = common::run_with_cache_dir(
+        &Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("cache"),
+        |cwd| {
+            // No `add` in `compete.toml`.
+            std::fs::write(
+                cwd.join("compete.toml"),
+                r#"test-suite = "{{ manifest_dir }}/testcases/{{ bin_alias | kebabcase }}.yml"
+
+[template]
+src = ""
+"#,
+            )?;
+
+            std::fs::write(
+                cwd.join("Cargo.toml"),
+                r#"[package]
+name = "practice"
+version = "0.1.0"
+edition = "2018"
+
+[package.metadata.cargo-compete.bin]
+
+[dependencies]
+"#,
+            )?;
+
+            std::fs::create_dir_all(cwd.join("src"))?;
+            std::fs::write(cwd.join("src").join("main.rs"), "fn main() {}\n")?;
+            Ok(())
+        },
+        &b""[..],
+        &[
+            "",
+            "compete",
+            "a",
+            "--offline",
+            "https://atcoder.jp/contests/practice",
+        ],
+        |workspace_root, output| {
+            output
+                .replace(workspace_root.to_str().unwrap(), "{{ cwd }}")
+                .replace(['/', '\\'], "{{ slash_or_backslash }}")
+        },
+        |_| Ok(Override::empty()),
+    )?;
+    assert_snapshot!("atcoder_practice_offline_url_output", output);
+    assert_json_snapshot!("atcoder_practice_offline_url_file_tree", tree, { r#".**["Cargo.lock"]"# => ".." });
+    Ok(())
+}
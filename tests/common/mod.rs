@@ -32,16 +32,43 @@ pub fn run(
     args: &[&str],
     process_output: impl FnOnce(&Path, String) -> String,
     walk_override: impl FnOnce(&Path) -> Result<Override, ignore::Error>,
+) -> anyhow::Result<(String, serde_json::Value)> {
+    let cache_dir = tempfile::Builder::new()
+        .prefix("cargo-compete-test-cache")
+        .tempdir()?;
+
+    let ret = run_with_cache_dir(
+        cache_dir.path(),
+        before,
+        input,
+        args,
+        process_output,
+        walk_override,
+    )?;
+
+    cache_dir.close()?;
+    Ok(ret)
+}
+
+/// With `--offline`, `cache_dir` works as fixtures.
+pub fn run_with_cache_dir(
+    cache_dir: &Path,
+    before: impl FnOnce(&Path) -> anyhow::Result<()>,
+    input: impl BufRead + 'static,
+    args: &[&str],
+    process_output: impl FnOnce(&Path, String) -> String,
+    walk_override: impl FnOnce(&Path) -> Result<Override, ignore::Error>,
 ) -> anyhow::Result<(String, serde_json::Value)> {
     let workspace = tempfile::Builder::new()
         .prefix("cargo-compete-test-workspace")
         .tempdir()?;
 
-    let cookies_jsonl = tempfile::Builder::new()
-        .prefix("cargo-compete-test-cookies")
-        .suffix(".jsonl")
-        .tempfile()?
-        .into_temp_path();
+    // `submissions.jsonl` is put next to the cookies.
+    let data_local_dir = tempfile::Builder::new()
+        .prefix("cargo-compete-test-data-local")
+        .tempdir()?;
+    let cookies_jsonl = &data_local_dir.path().join("cookies.jsonl");
+    std::fs::write(cookies_jsonl, "")?;
 
     let (output_file, output) = tempfile::Builder::new()
         .prefix("cargo-compete-test-output")
@@ -52,19 +79,22 @@ pub fn run(
 
     let Opt::Compete(opt) = Opt::from_iter_safe(args)?;
 
-    cargo_compete::run(
-        opt,
-        cargo_compete::Context {
-            cwd: workspace.path().to_owned(),
-            cookies_path: Path::new(&cookies_jsonl).to_owned(),
-            shell: &mut Shell::from_read_write(Box::new(input), Box::new(output_file)),
-        },
-    )?;
+    let mut shell = Shell::from_read_write(Box::new(input), Box::new(output_file));
+
+    let ctx = cargo_compete::Context::new(
+        workspace.path().to_owned(),
+        cookies_jsonl.to_owned(),
+        &mut shell,
+    )?
+    .cache_dir(cache_dir.to_owned());
+
+    cargo_compete::run(opt, ctx)?;
 
     let output_content = process_output(workspace.path(), std::fs::read_to_string(&output)?);
     let tree = tree(workspace.as_ref(), walk_override(workspace.path())?)?;
 
     workspace.close()?;
+    data_local_dir.close()?;
     output.close()?;
 
     Ok((output_content, tree))
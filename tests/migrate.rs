@@ -0,0 +1,105 @@
+pub mod common;
+
+use ignore::overrides::Override;
+use insta::{assert_json_snapshot, assert_snapshot};
+use std::path::Path;
+
+#[test]
+fn workspace() -> anyhow::Result<()> {
+    let (output, tree) = common::run(
+        |cwd| {
+            setup(
+                cwd,
+                &[
+                    ("abc001", "\n[profile.dev]\nopt-level = 3\n"),
+                    ("abc002", ""),
+                ],
+            )
+        },
+        &b""[..],
+        &["", "compete", "migrate", "workspace"],
+        |workspace_root, output| {
+            output
+                .replace(workspace_root.to_str().unwrap(), "{{ cwd }}")
+                .replace(['/', '\\'], "{{ slash_or_backslash }}")
+        },
+        |_| Ok(Override::empty()),
+    )?;
+    assert_snapshot!("workspace_output", output);
+    assert_json_snapshot!("workspace_file_tree", tree, { r#".**["Cargo.lock"]"# => ".." });
+    Ok(())
+}
+
+#[test]
+fn workspace_with_different_profiles() {
+    let err = common::run(
+        |cwd| {
+            setup(
+                cwd,
+                &[
+                    ("abc001", "\n[profile.dev]\nopt-level = 3\n"),
+                    ("abc002", "\n[profile.dev]\nopt-level = 2\n"),
+                ],
+            )
+        },
+        &b""[..],
+        &["", "compete", "migrate", "workspace"],
+        |_, output| output,
+        |_| Ok(Override::empty()),
+    )
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .ends_with("make them the same, or remove either of them"));
+}
+
+fn setup(cwd: &Path, profiles: &[(&str, &str)]) -> anyhow::Result<()> {
+    std::fs::write(
+        cwd.join("compete.toml"),
+        r#"test-suite = "{{ manifest_dir }}/testcases/{{ bin_alias | kebabcase }}.yml"
+
+[template]
+src = ""
+
+[new]
+platform = "atcoder"
+path = "./{{ package_name }}"
+"#,
+    )?;
+
+    for &(name, profile) in profiles {
+        std::fs::create_dir_all(cwd.join(name).join("src").join("bin"))?;
+        std::fs::write(
+            cwd.join(name).join("Cargo.toml"),
+            format!(
+                r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2021"
+{profile}
+[package.metadata.cargo-compete.bin]
+{name}-a = {{ alias = "a", problem = "https://atcoder.jp/contests/{name}/tasks/{name}_a" }}
+
+[[bin]]
+name = "{name}-a"
+path = "src/bin/a.rs"
+
+[dependencies]
+"#,
+            ),
+        )?;
+        std::fs::write(
+            cwd.join(name).join("src").join("bin").join("a.rs"),
+            "fn main() {}\n",
+        )?;
+    }
+
+    // Not a package for a contest.
+    std::fs::create_dir_all(cwd.join("library").join("src"))?;
+    std::fs::write(
+        cwd.join("library").join("Cargo.toml"),
+        "[package]\nname = \"library\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )?;
+    std::fs::write(cwd.join("library").join("src").join("lib.rs"), "")?;
+    Ok(())
+}
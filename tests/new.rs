@@ -1,9 +1,9 @@
 pub mod common;
 
-use ignore::overrides::Override;
+use ignore::overrides::{Override, OverrideBuilder};
 use insta::{assert_json_snapshot, assert_snapshot};
 use snowchains_core::web::PlatformKind;
-use std::io::BufRead;
+use std::{io::BufRead, path::Path};
 
 #[test]
 fn atcoder_abc003() -> anyhow::Result<()> {
@@ -50,6 +50,194 @@ fn atcoder_practice() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn atcoder_practice_offline() -> anyhow::Result<()> {
+    let (output, tree) = run_offline(PlatformKind::Atcoder, "practice")?;
+    assert_snapshot!("atcoder_practice_offline_output", output);
+    assert_json_snapshot!("atcoder_practice_offline_file_tree", tree, { r#".**["Cargo.lock"]"# => ".." });
+    Ok(())
+}
+
+#[test]
+fn atcoder_practice_offline_append() -> anyhow::Result<()> {
+    let (output, tree) = common::run_with_cache_dir(
+        &Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("cache"),
+        |cwd| {
+            setup(cwd, PlatformKind::Atcoder)?;
+            std::fs::create_dir_all(cwd.join("practice").join("src").join("bin"))?;
+            std::fs::write(
+                cwd.join("practice").join("Cargo.toml"),
+                r#"[package]
+name = "practice"
+version = "0.1.0"
+edition = "2021"
+
+# Only A has been added.
+[package.metadata.cargo-compete.bin]
+practice-a = { alias = "a", problem = "https://atcoder.jp/contests/practice/tasks/practice_1" }
+
+[[bin]]
+name = "practice-a"
+path = "src/bin/a.rs"
+
+[dependencies]
+proconio = "=0.3.6"
+"#,
+            )?;
+            std::fs::write(
+                cwd.join("practice").join("src").join("bin").join("a.rs"),
+                "fn main() {}\n",
+            )?;
+            Ok(())
+        },
+        &b""[..],
+        &["", "compete", "n", "--offline", "--append", "practice"],
+        process_output,
+        |_| Ok(Override::empty()),
+    )?;
+    assert_snapshot!("atcoder_practice_offline_append_output", output);
+    assert_json_snapshot!("atcoder_practice_offline_append_file_tree", tree, { r#".**["Cargo.lock"]"# => ".." });
+    Ok(())
+}
+
+#[test]
+fn atcoder_practice_offline_workspace() -> anyhow::Result<()> {
+    let (output, tree) = common::run_with_cache_dir(
+        &Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("cache"),
+        |cwd| {
+            setup(cwd, PlatformKind::Atcoder)?;
+            let compete_toml = std::fs::read_to_string(cwd.join("compete.toml"))?;
+            std::fs::write(
+                cwd.join("compete.toml"),
+                compete_toml + "workspace = true\n",
+            )?;
+            std::fs::write(
+                cwd.join("Cargo.toml"),
+                r#"[workspace]
+members = [
+    "abc001",
+]
+"#,
+            )?;
+            Ok(())
+        },
+        &b""[..],
+        &["", "compete", "n", "--offline", "practice"],
+        process_output,
+        |_| Ok(Override::empty()),
+    )?;
+    assert_snapshot!("atcoder_practice_offline_workspace_output", output);
+    assert_json_snapshot!("atcoder_practice_offline_workspace_file_tree", tree, { r#".**["Cargo.lock"]"# => ".." });
+    Ok(())
+}
+
+#[test]
+fn atcoder_problems_virtual_offline() -> anyhow::Result<()> {
+    let (output, tree) = common::run_with_cache_dir(
+        &Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("cache"),
+        |cwd| setup(cwd, PlatformKind::Atcoder),
+        &b""[..],
+        &[
+            "",
+            "compete",
+            "n",
+            "--offline",
+            "--virtual",
+            "https://kenkoooo.com/atcoder/#/contest/show/0b1bbcaf-bd6c-4e2a-9c5f-3a6e1e1b0f0e",
+        ],
+        process_output,
+        |_| Ok(Override::empty()),
+    )?;
+    assert_snapshot!("atcoder_problems_virtual_offline_output", output);
+    assert_json_snapshot!("atcoder_problems_virtual_offline_file_tree", tree, { r#".**["Cargo.lock"]"# => ".." });
+    Ok(())
+}
+
+#[test]
+fn from_list_offline() -> anyhow::Result<()> {
+    let (output, tree) = common::run_with_cache_dir(
+        &Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("cache"),
+        |cwd| {
+            setup(cwd, PlatformKind::Atcoder)?;
+            std::fs::write(
+                cwd.join("problems.txt"),
+                r#"# Welcome to AtCoder
+https://atcoder.jp/contests/practice/tasks/practice_1
+sort https://atcoder.jp/contests/practice/tasks/practice_2
+
+hello https://onlinejudge.u-aizu.ac.jp/problems/ITP1_1_A
+"#,
+            )?;
+            Ok(())
+        },
+        &b""[..],
+        &[
+            "",
+            "compete",
+            "n",
+            "--offline",
+            "--from-list",
+            "problems.txt",
+            "--name",
+            "training",
+        ],
+        process_output,
+        |_| Ok(Override::empty()),
+    )?;
+    assert_snapshot!("from_list_offline_output", output);
+    assert_json_snapshot!("from_list_offline_file_tree", tree, { r#".**["Cargo.lock"]"# => ".." });
+    Ok(())
+}
+
+#[test]
+fn atcoder_practice_offline_post_new_hook() -> anyhow::Result<()> {
+    let git = which::which("git")?;
+    let (output, tree) = common::run_with_cache_dir(
+        &Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("cache"),
+        |cwd| {
+            setup(cwd, PlatformKind::Atcoder)?;
+            let compete_toml = std::fs::read_to_string(cwd.join("compete.toml"))?;
+            std::fs::write(
+                cwd.join("compete.toml"),
+                compete_toml
+                    + r#"
+[hooks]
+post-new = [["git", "init", "-q", "--bare", "{{ manifest_dir }}/{{ problems[1].bin_alias }}-{{ package_name }}.git"]]
+"#,
+            )?;
+            Ok(())
+        },
+        &b""[..],
+        &["", "compete", "n", "--offline", "practice"],
+        |workspace_root, output| {
+            process_output(workspace_root, output.replace(git.to_str().unwrap(), "git"))
+        },
+        |workspace_root| {
+            OverrideBuilder::new(workspace_root)
+                .add("!/practice/b-practice.git/*")?
+                .build()
+        },
+    )?;
+    assert_snapshot!("atcoder_practice_offline_post_new_hook_output", output);
+    assert_json_snapshot!("atcoder_practice_offline_post_new_hook_file_tree", tree, { r#".**["Cargo.lock"]"# => ".." });
+    Ok(())
+}
+
 // currently broken
 //#[test]
 //fn yukicoder_contest_100() -> anyhow::Result<()> {
@@ -65,11 +253,36 @@ fn run(
     input: impl BufRead + 'static,
 ) -> anyhow::Result<(String, serde_json::Value)> {
     common::run(
-        |cwd| -> _ {
-            std::fs::write(
-                cwd.join("compete.toml"),
-                format!(
-                    r#"test-suite = "{{{{ manifest_dir }}}}/testcases/{{{{ bin_alias | kebabcase }}}}.yml"
+        |cwd| setup(cwd, platform),
+        input,
+        &["", "compete", "n", contest],
+        process_output,
+        |_| Ok(Override::empty()),
+    )
+}
+
+fn run_offline(
+    platform: PlatformKind,
+    contest: &str,
+) -> anyhow::Result<(String, serde_json::Value)> {
+    common::run_with_cache_dir(
+        &Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("cache"),
+        |cwd| setup(cwd, platform),
+        &b""[..],
+        &["", "compete", "n", "--offline", contest],
+        process_output,
+        |_| Ok(Override::empty()),
+    )
+}
+
+fn setup(cwd: &Path, platform: PlatformKind) -> anyhow::Result<()> {
+    std::fs::write(
+        cwd.join("compete.toml"),
+        format!(
+            r#"test-suite = "{{{{ manifest_dir }}}}/testcases/{{{{ bin_alias | kebabcase }}}}.yml"
 
 [template]
 src = '''
@@ -88,29 +301,25 @@ proconio = "=0.3.6"
 platform = "{}"
 path = "./{{{{ package_name }}}}"
 "#,
-                    platform.to_kebab_case_str(),
-                ),
-            )?;
+            platform.to_kebab_case_str(),
+        ),
+    )?;
 
-            std::fs::create_dir(cwd.join(".cargo"))?;
+    std::fs::create_dir(cwd.join(".cargo"))?;
 
-            std::fs::write(
-                cwd.join(".cargo").join("config.toml"),
-                r#"[cargo-new]
+    std::fs::write(
+        cwd.join(".cargo").join("config.toml"),
+        r#"[cargo-new]
 name = ""
 email = ""
 "#,
-            )?;
+    )?;
 
-            Ok(())
-        },
-        input,
-        &["", "compete", "n", contest],
-        |workspace_root, output| {
-            output
-                .replace(workspace_root.to_str().unwrap(), "{{ cwd }}")
-                .replace(['/', '\\'], "{{ slash_or_backslash }}")
-        },
-        |_| Ok(Override::empty()),
-    )
+    Ok(())
+}
+
+fn process_output(workspace_root: &Path, output: String) -> String {
+    output
+        .replace(workspace_root.to_str().unwrap(), "{{ cwd }}")
+        .replace(['/', '\\'], "{{ slash_or_backslash }}")
 }
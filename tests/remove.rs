@@ -0,0 +1,102 @@
+pub mod common;
+
+use ignore::overrides::Override;
+use insta::{assert_json_snapshot, assert_snapshot};
+use std::path::Path;
+
+#[test]
+fn remove() -> anyhow::Result<()> {
+    let (output, tree) = run(&["", "compete", "remove", "b"], |_| Ok(()))?;
+    assert_snapshot!("remove_output", output);
+    assert_json_snapshot!("remove_file_tree", tree, { r#".**["Cargo.lock"]"# => ".." });
+    Ok(())
+}
+
+#[test]
+fn remove_archive() -> anyhow::Result<()> {
+    let (output, tree) = run(&["", "compete", "remove", "--archive", "b"], |_| Ok(()))?;
+    assert_snapshot!("remove_archive_output", output);
+    assert_json_snapshot!("remove_archive_file_tree", tree, { r#".**["Cargo.lock"]"# => ".." });
+    Ok(())
+}
+
+#[test]
+fn remove_archive_collision() {
+    let err = run(&["", "compete", "remove", "--archive", "b"], |cwd| {
+        std::fs::create_dir_all(cwd.join("archive").join("testcases"))?;
+        std::fs::write(cwd.join("archive").join("testcases").join("b.yml"), "").map_err(Into::into)
+    })
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .ends_with("b.yml` already exists. move or remove it to archive anyway"));
+}
+
+fn run(
+    args: &[&str],
+    before: fn(&Path) -> anyhow::Result<()>,
+) -> anyhow::Result<(String, serde_json::Value)> {
+    common::run(
+        |cwd| {
+            std::fs::write(
+                cwd.join("compete.toml"),
+                r#"test-suite = "{{ manifest_dir }}/testcases/{{ bin_alias | kebabcase }}.yml"
+
+[template]
+src = ""
+"#,
+            )?;
+
+            std::fs::write(
+                cwd.join("Cargo.toml"),
+                r#"[package]
+name = "practice"
+version = "0.1.0"
+edition = "2018"
+
+[package.metadata.cargo-compete.bin]
+# This comment should be kept.
+practice-a = { alias = "a", problem = "https://atcoder.jp/contests/practice/tasks/practice_1" }
+practice-b = { alias = "b", problem = "https://atcoder.jp/contests/practice/tasks/practice_2" }
+
+[[bin]]
+name = "practice-a"
+path = "src/bin/a.rs"
+
+[[bin]]
+name = "practice-b"
+path = "src/bin/b.rs"
+
+[dependencies]
+"#,
+            )?;
+
+            std::fs::create_dir_all(cwd.join("src").join("bin"))?;
+            std::fs::write(cwd.join("src").join("bin").join("a.rs"), "fn main() {}\n")?;
+            std::fs::write(cwd.join("src").join("bin").join("b.rs"), "fn main() {}\n")?;
+
+            std::fs::create_dir_all(cwd.join("testcases").join("b"))?;
+            std::fs::write(
+                cwd.join("testcases").join("a.yml"),
+                "---\ntype: Batch\ntimelimit: 2s\nmatch: Lines\n\ncases: []\n\nextend: []\n",
+            )?;
+            std::fs::write(
+                cwd.join("testcases").join("b.yml"),
+                "---\ntype: Interactive\ntimelimit: 2s\n",
+            )?;
+            std::fs::write(
+                cwd.join("testcases").join("b").join("statement.md"),
+                "# B - Interactive Sorting\n",
+            )?;
+            before(cwd)
+        },
+        &b""[..],
+        args,
+        |workspace_root, output| {
+            output
+                .replace(workspace_root.to_str().unwrap(), "{{ cwd }}")
+                .replace(['/', '\\'], "{{ slash_or_backslash }}")
+        },
+        |_| Ok(Override::empty()),
+    )
+}